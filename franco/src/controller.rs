@@ -7,11 +7,50 @@ pub use camera::*;
 pub mod debug;
 pub use debug::*;
 
+pub mod debug_ui;
+pub use debug_ui::*;
+
 pub mod player;
 pub use player::*;
 
 pub mod friend;
 pub use friend::*;
 
+pub mod pickup;
+pub use pickup::*;
+
+pub mod regatta;
+pub use regatta::*;
+
+pub mod level;
+pub use level::*;
+
+pub mod ambient;
+pub use ambient::*;
+
+pub mod sonar;
+pub use sonar::*;
+
+pub mod port;
+pub use port::*;
+
+pub mod hints;
+pub use hints::*;
+
 pub mod ui;
 pub use ui::*;
+
+pub mod stats;
+pub use stats::*;
+
+pub mod net;
+pub use net::*;
+
+pub mod pause;
+pub use pause::*;
+
+pub mod settings;
+pub use settings::*;
+
+pub mod tunables;
+pub use tunables::*;
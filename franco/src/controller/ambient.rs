@@ -0,0 +1,171 @@
+use crate::prelude::*;
+use model::{FishSchool, SchoolFish, JumpingFish};
+
+/// New schools appear somewhere between these distances from a boat, out of sight in the fog
+const SCHOOL_SPAWN_MIN_DISTANCE: f32 = 20.0;
+const SCHOOL_SPAWN_MAX_DISTANCE: f32 = 30.0;
+const SCHOOL_LIFETIME: f32 = 40.0;
+
+const SCHOOL_NEIGHBOUR_RADIUS: f32 = 2.0;
+const SCHOOL_SEPARATION_RADIUS: f32 = 0.5;
+const SCHOOL_SWIM_SPEED: f32 = 1.2;
+
+/// Fish within this distance of a boat bolt away from it
+const SCATTER_DISTANCE: f32 = 5.0;
+const SCATTER_SPEED: f32 = 4.0;
+
+const JUMP_MIN_DISTANCE: f32 = 6.0;
+const JUMP_MAX_DISTANCE: f32 = 20.0;
+
+
+pub struct AmbientController;
+
+impl AmbientController {
+	pub fn new(_engine: &mut toybox::Engine) -> AmbientController {
+		AmbientController
+	}
+
+	pub fn update(&mut self, model: &mut model::Model) {
+		let dt = 1.0/60.0;
+
+		let boats: Vec<Vec2> = model.boats()
+			.map(|boat| boat.map_position)
+			.collect();
+
+		let ambient = &mut model.world.ambient;
+
+		let nearest_boat_distance = |pos: Vec2| {
+			boats.iter()
+				.map(|&boat| (boat - pos).length())
+				.fold(f32::INFINITY, f32::min)
+		};
+
+		// Schools far from every boat are culled rather than simulated, and replaced nearer the action
+		ambient.schools.retain(|school| {
+			school.lifetime > 0.0 && nearest_boat_distance(school.center()) < model::AMBIENT_CULL_DISTANCE
+		});
+
+		if ambient.schools.len() < model::AMBIENT_MAX_SCHOOLS {
+			let boat = boats[rand::random::<usize>() % boats.len()];
+			ambient.schools.push(spawn_school(boat));
+		}
+
+		for school in ambient.schools.iter_mut() {
+			school.lifetime -= dt;
+			update_school(school, &boats);
+		}
+
+		for gull in ambient.gulls.iter_mut() {
+			gull.orbit_angle = (gull.orbit_angle + gull.orbit_speed * dt) % TAU;
+		}
+
+		for jumper in ambient.jumpers.iter_mut() {
+			jumper.phase += dt / model::AMBIENT_JUMP_TIME;
+			jumper.map_position += Vec2::from_angle(jumper.heading) * 2.0 * dt;
+		}
+
+		ambient.jumpers.retain(|jumper| jumper.phase < 1.0);
+
+		ambient.jump_timer -= dt;
+		if ambient.jump_timer <= 0.0 {
+			ambient.jump_timer = 2.0 + rand::random::<f32>() * 5.0;
+
+			let boat = boats[rand::random::<usize>() % boats.len()];
+			let distance = JUMP_MIN_DISTANCE + rand::random::<f32>() * (JUMP_MAX_DISTANCE - JUMP_MIN_DISTANCE);
+
+			ambient.jumpers.push(JumpingFish {
+				map_position: boat + Vec2::from_angle(rand::random::<f32>() * TAU) * distance,
+				heading: rand::random::<f32>() * TAU,
+				phase: 0.0,
+			});
+		}
+	}
+}
+
+
+fn spawn_school(boat: Vec2) -> FishSchool {
+	let distance = SCHOOL_SPAWN_MIN_DISTANCE + rand::random::<f32>() * (SCHOOL_SPAWN_MAX_DISTANCE - SCHOOL_SPAWN_MIN_DISTANCE);
+	let center = boat + Vec2::from_angle(rand::random::<f32>() * TAU) * distance;
+	let heading = Vec2::from_angle(rand::random::<f32>() * TAU);
+
+	let fish = (0..model::AMBIENT_SCHOOL_SIZE)
+		.map(|_| {
+			let offset = Vec2::new(rand::random::<f32>() - 0.5, rand::random::<f32>() - 0.5) * 2.0;
+
+			SchoolFish {
+				map_position: center + offset,
+				velocity: heading * SCHOOL_SWIM_SPEED,
+				depth: 0.5 + rand::random::<f32>() * 1.5,
+			}
+		})
+		.collect();
+
+	FishSchool {
+		fish,
+		lifetime: SCHOOL_LIFETIME * (0.5 + rand::random::<f32>()),
+	}
+}
+
+
+/// Classic boids within a school - each fish only looks at its own school, so this stays cheap
+fn update_school(school: &mut FishSchool, boats: &[Vec2]) {
+	let dt = 1.0/60.0;
+
+	let snapshot: Vec<(Vec2, Vec2)> = school.fish.iter()
+		.map(|fish| (fish.map_position, fish.velocity))
+		.collect();
+
+	for (idx, fish) in school.fish.iter_mut().enumerate() {
+		let mut separation = Vec2::zero();
+		let mut alignment = Vec2::zero();
+		let mut center = Vec2::zero();
+		let mut num_neighbours = 0;
+
+		for (other_idx, &(other_pos, other_vel)) in snapshot.iter().enumerate() {
+			let diff = fish.map_position - other_pos;
+			let dist = diff.length();
+
+			if other_idx == idx || dist > SCHOOL_NEIGHBOUR_RADIUS {
+				continue
+			}
+
+			if dist < SCHOOL_SEPARATION_RADIUS && dist > 0.001 {
+				separation += diff / dist * (1.0 - dist / SCHOOL_SEPARATION_RADIUS);
+			}
+
+			alignment += other_vel;
+			center += other_pos;
+			num_neighbours += 1;
+		}
+
+		let mut steering = separation * 4.0;
+
+		if num_neighbours > 0 {
+			steering += (alignment / num_neighbours as f32 - fish.velocity) * 0.5;
+			steering += (center / num_neighbours as f32 - fish.map_position) * 0.8;
+		}
+
+		let mut max_speed = SCHOOL_SWIM_SPEED;
+
+		for &boat in boats {
+			let diff = fish.map_position - boat;
+			let dist = diff.length();
+
+			if dist < SCATTER_DISTANCE && dist > 0.001 {
+				steering += diff / dist * (1.0 - dist / SCATTER_DISTANCE) * 20.0;
+				max_speed = SCATTER_SPEED;
+			}
+		}
+
+		fish.velocity += steering * dt;
+
+		let speed = fish.velocity.length();
+		if speed > max_speed {
+			fish.velocity = fish.velocity / speed * max_speed;
+		}
+	}
+
+	for fish in school.fish.iter_mut() {
+		fish.map_position += fish.velocity * dt;
+	}
+}
@@ -5,6 +5,12 @@ use model::camera::ControlMode;
 const ORBIT_CAMERA_PITCH_LIMIT: (f32, f32) = (-PI/2.0, -PI/64.0);
 const DEBUG_CAMERA_PITCH_LIMIT: (f32, f32) = (-PI/2.0, PI/2.0);
 
+/// The harbour camera looks out to sea past the moored boat, from over its shoulder
+const HARBOUR_CAMERA_YAW_OFFSET: f32 = -PI/4.0;
+const HARBOUR_CAMERA_PITCH: f32 = -PI/10.0;
+const HARBOUR_CAMERA_ZOOM: f32 = 14.0;
+const HARBOUR_CAMERA_BLEND_TIME: f32 = 1.5;
+
 toybox::declare_input_context! {
 	struct OrbitCameraActions "Orbit Camera Control" {
 	}
@@ -50,6 +56,12 @@ impl CameraController {
 	}
 
 	pub fn update(&mut self, engine: &mut toybox::Engine, model: &mut model::Model) {
+		let ports = &model.world.ports;
+		let harbour_heading = |player: &model::Player| player.harbour.port().map(|port| ports[port].heading);
+
+		let player_harbour_heading = harbour_heading(&model.player);
+		let split_harbour_heading = model.split_screen.as_ref().and_then(|split| harbour_heading(&split.player));
+
 		let camera = &mut model.camera;
 
 		if camera.control_mode != self.prev_mode {
@@ -65,13 +77,24 @@ impl CameraController {
 		}
 
 		match camera.control_mode {
-			ControlMode::OrbitPlayer => self.update_orbit(camera, &mut engine.input, model.ui.dragging_unclaimed_area),
+			ControlMode::OrbitPlayer => {
+				// The harbour view takes over while moored, so dragging doesn't orbit away from it
+				let dragging = model.ui.widgets.pressed_unclaimed() && player_harbour_heading.is_none();
+				self.update_orbit(camera, &mut engine.input, dragging, model.settings.invert_camera);
+				update_harbour_view(camera, player_harbour_heading);
+			}
+
 			ControlMode::FreeFly => self.update_debug(camera, engine.input.frame_state()),
 		}
+
+		// The second player has no pointer to orbit with, so their camera only follows zoom and harbours
+		if let Some(split) = &mut model.split_screen {
+			update_harbour_view(&mut split.camera, split_harbour_heading);
+		}
 	}
 
 
-	fn update_orbit(&mut self, camera: &mut model::Camera, input: &mut toybox::input::InputSystem, dragging: bool) {
+	fn update_orbit(&mut self, camera: &mut model::Camera, input: &mut toybox::input::InputSystem, dragging: bool, invert: bool) {
 		if dragging && !input.is_context_active(self.active_orbit_actions.context_id()) {
 			input.enter_context(self.active_orbit_actions.context_id());
 		} else if !dragging && input.is_context_active(self.active_orbit_actions.context_id()) {
@@ -81,13 +104,16 @@ impl CameraController {
 		let input_state = input.frame_state();
 		if let Some(mouse) = input_state.mouse(self.active_orbit_actions.mouse) {
 			let (pitch_min, pitch_max) = ORBIT_CAMERA_PITCH_LIMIT;
+			let mouse = match invert {
+				true => Vec2::new(mouse.x, -mouse.y),
+				false => mouse,
+			};
 
 			camera.yaw -= mouse.x * 0.5;
 			camera.pitch = (camera.pitch + mouse.y as f32 * 0.5).clamp(pitch_min, pitch_max);
 		}
 
-		let camera_orientation = Quat::from_yaw(camera.yaw) * Quat::from_pitch(camera.pitch);
-		camera.position = Vec3::from_y(1.0) - camera_orientation.forward() * camera.orbit_zoom;
+		update_orbit_position(camera);
 	}
 
 
@@ -116,3 +142,33 @@ impl CameraController {
 	}
 }
 
+
+
+
+fn update_orbit_position(camera: &mut model::Camera) {
+	let camera_orientation = Quat::from_yaw(camera.yaw) * Quat::from_pitch(camera.pitch);
+	let zoom = camera.harbour_blend.ease_quad_inout(camera.orbit_zoom, HARBOUR_CAMERA_ZOOM);
+	camera.position = Vec3::from_y(1.0) - camera_orientation.forward() * zoom;
+}
+
+
+/// Swings the camera round to look out of the harbour while `harbour_heading` is set, and hands back to the orbit camera once it isn't
+fn update_harbour_view(camera: &mut model::Camera, harbour_heading: Option<f32>) {
+	let blend_step = 1.0 / (HARBOUR_CAMERA_BLEND_TIME * 60.0);
+
+	match harbour_heading {
+		Some(heading) => {
+			camera.harbour_blend = (camera.harbour_blend + blend_step).min(1.0);
+
+			let target_yaw = heading + HARBOUR_CAMERA_YAW_OFFSET;
+			let yaw_diff = (target_yaw - camera.yaw + PI).rem_euclid(TAU) - PI;
+
+			camera.yaw += yaw_diff * camera.harbour_blend * 0.1;
+			camera.pitch += (HARBOUR_CAMERA_PITCH - camera.pitch) * camera.harbour_blend * 0.1;
+		}
+
+		None => camera.harbour_blend = (camera.harbour_blend - blend_step).max(0.0),
+	}
+
+	update_orbit_position(camera);
+}
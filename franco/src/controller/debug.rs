@@ -1,4 +1,12 @@
 use crate::prelude::*;
+use controller::DebugUi;
+use toybox::perf;
+
+/// Perf meters fill at this many milliseconds, and turn red beyond it
+const FRAME_BUDGET_MS: f64 = 1000.0 / 60.0;
+
+/// Teleporting to a friend lands the boat this far away, rather than right on top of it
+const TELEPORT_OFFSET: Vec2 = Vec2::new(3.0, 0.0);
 
 
 toybox::declare_input_context! {
@@ -6,9 +14,9 @@ toybox::declare_input_context! {
 		trigger toggle_active { "Toggle" [Scancode::Grave] }
 		trigger toggle_flycam { "Toggle Fly Cam" [Scancode::V] }
 		trigger toggle_wireframe { "Toggle Wireframe" [Scancode::Z] }
+		trigger cycle_color_blindness { "Cycle Colour Blindness Preview" [Scancode::F7] }
 
 		trigger win_game { "Win" [Scancode::F10] }
-		trigger dump_model { "Dump Model" [Scancode::F12] }
 	}
 }
 
@@ -48,6 +56,12 @@ impl DebugController {
 			}
 		}
 
+		model.debug_inspector.visible = engine.input.is_context_active(self.active_actions.context_id());
+
+		if model.debug_inspector.visible {
+			self.update_inspector(engine, model);
+		}
+
 		let input_state = engine.input.frame_state();
 
 		if input_state.active(self.actions.toggle_flycam) {
@@ -60,20 +74,193 @@ impl DebugController {
 		}
 
 		if input_state.active(self.actions.win_game) {
-			for friend in model.world.friends.iter_mut() {
-				friend.met_player = true;
-			}
-		}
+			let time = model.stats.session.playtime;
 
-		if input_state.active(self.actions.dump_model) {
-			println!("{:#?}", model.global);
-			println!("{:#?}", model.camera);
-			println!("{:#?}", model.world);
-			println!("{:#?}", model.player);
+			for friend in model.world.friends.iter_mut().filter(|friend| !friend.met_player()) {
+				friend.meet(time);
+			}
 		}
 
 		if input_state.active(self.actions.toggle_wireframe) {
 			model.global.wireframe_enabled = !model.global.wireframe_enabled;
 		}
+
+		// Steps through each kind of colour blindness, then back to normal vision
+		if input_state.active(self.actions.cycle_color_blindness) {
+			use model::ColorBlindness;
+
+			let simulated = &mut model.global.simulated_color_blindness;
+			let next = match *simulated {
+				None => Some(0),
+				Some(current) => ColorBlindness::ALL.iter().position(|&kind| kind == current)
+					.map(|idx| idx + 1)
+					.filter(|&idx| idx < ColorBlindness::ALL.len()),
+			};
+
+			*simulated = next.map(|idx| ColorBlindness::ALL[idx]);
+		}
+	}
+	fn update_inspector(&mut self, engine: &mut toybox::Engine, model: &mut model::Model) {
+		let dpi = toybox::window::display_dpi(&engine.window);
+		let metrics = model::UiMetrics::for_viewport(engine.gfx.backbuffer_size(), dpi, model.settings.ui_scale);
+		model.debug_inspector.metrics = metrics;
+
+		let input = engine.input.frame_state();
+		let pointer = input.mouse(self.active_actions.mouse).map(|pointer| metrics.pointer_to_ui(pointer));
+		let pressed = input.entered(self.active_actions.left_mouse);
+		let held = input.active(self.active_actions.left_mouse);
+
+		let mut ui = DebugUi::begin(&mut model.debug_inspector, pointer, pressed, held);
+
+		game_panel(&mut ui, &mut model.global, &model.tunables);
+		player_panel(&mut ui, &mut model.player, &model.tunables);
+		camera_panel(&mut ui, &mut model.camera, &model.tunables);
+		friends_panel(&mut ui, &mut model.world.friends, &mut model.player, model.stats.session.playtime);
+		perf_panel(&mut ui, engine.instrumenter.summary());
+	}
+}
+
+
+fn game_panel(ui: &mut DebugUi<'_>, global: &mut model::Global, tunables: &model::Tunables) {
+	use model::GameState;
+
+	if !ui.panel("Game") {
+		return
+	}
+
+	ui.label("state", format!("{:?}", global.game_state));
+
+	match ui.buttons("", &["play", "end", "post game"]) {
+		Some(0) => global.game_state = GameState::Playing,
+		Some(1) => global.game_state.notify_end_game(tunables),
+		Some(_) => global.game_state = GameState::PostGame,
+		None => {}
+	}
+
+	ui.label("mode", format!("{:?}", global.game_mode));
+	ui.label("network", format!("{:?}", global.net_role));
+	ui.toggle("wireframe", &mut global.wireframe_enabled);
+
+	match global.simulated_color_blindness {
+		Some(kind) => ui.label("colour preview", format!("{:?}", kind)),
+		None => ui.label("colour preview", "none"),
+	}
+
+	match ui.buttons("simulation", &["pause", "resume"]) {
+		Some(0) => global.pause(),
+		Some(_) => global.resume(),
+		None => {}
+	}
+}
+
+fn player_panel(ui: &mut DebugUi<'_>, player: &mut model::Player, tunables: &model::Tunables) {
+	if !ui.panel("Player") {
+		return
+	}
+
+	let top_speed = tunables.max_sail_speed as f32 * tunables.speed_per_sail;
+
+	ui.label("position", format!("{:.1}, {:.1}", player.map_position.x, player.map_position.y));
+	ui.slider("speed", &mut player.speed, 0.0, top_speed * 2.0);
+
+	// Heading isn't kept wrapped, so it's only written back if it's actually edited
+	let mut heading = player.heading.rem_euclid(TAU);
+	if ui.slider("heading", &mut heading, 0.0, TAU) {
+		player.heading = heading;
+	}
+
+	ui.label("sail", format!("{:?}", player.sail_state));
+	ui.label("harbour", format!("{:?}", player.harbour));
+}
+
+fn camera_panel(ui: &mut DebugUi<'_>, camera: &mut model::Camera, tunables: &model::Tunables) {
+	use model::camera::ControlMode;
+
+	if !ui.panel("Camera") {
+		return
+	}
+
+	let mut flycam = camera.control_mode == ControlMode::FreeFly;
+	if ui.toggle("fly cam", &mut flycam) {
+		camera.control_mode = match flycam {
+			true => ControlMode::FreeFly,
+			false => ControlMode::OrbitPlayer,
+		};
+	}
+
+	ui.label("position", format!("{:.1}, {:.1}, {:.1}", camera.position.x, camera.position.y, camera.position.z));
+	ui.slider("pitch", &mut camera.pitch, -PI/2.0, PI/2.0);
+
+	let mut yaw = (camera.yaw + PI).rem_euclid(TAU) - PI;
+	if ui.slider("yaw", &mut yaw, -PI, PI) {
+		camera.yaw = yaw;
+	}
+
+	ui.slider("zoom", &mut camera.orbit_zoom, tunables.min_orbit_zoom, tunables.max_orbit_zoom);
+}
+
+fn friends_panel(ui: &mut DebugUi<'_>, friends: &mut [model::Friend], player: &mut model::Player, time: f32) {
+	if !ui.panel("Friends") {
+		return
+	}
+
+	for friend in friends.iter_mut() {
+		let met = match friend.met_player() {
+			true => "met",
+			false => "not met",
+		};
+
+		ui.label(friend.name.as_str(), format!("{:?}, {}", friend.state, met));
+
+		match ui.buttons("", &["hang out", "follow", "go home"]) {
+			Some(0) => friend.state = model::FriendState::HangingOut,
+			Some(1) => friend.state = model::FriendState::Following,
+			Some(_) => friend.state = model::FriendState::ReturningHome,
+			None => {}
+		}
+
+		match ui.buttons("", &["teleport to", "mark met"]) {
+			Some(0) => player.map_position = friend.map_position + TELEPORT_OFFSET,
+			Some(_) if !friend.met_player() => friend.meet(time),
+			_ => {}
+		}
+	}
+}
+
+fn perf_panel(ui: &mut DebugUi<'_>, summary: Option<&perf::Summary>) {
+	if !ui.panel("Perf") {
+		return
+	}
+
+	let summary = match summary {
+		Some(summary) => summary,
+		None => {
+			ui.label("", "waiting for queries");
+			return
+		}
+	};
+
+	ui.label("ms", "cpu / gpu");
+	ui.label("triangles", summary.total_triangles);
+
+	let rows = std::iter::once(("frame", summary.total_cpu_time_ms, summary.total_gpu_time_ms))
+		.chain(summary.sections.iter().map(|section| (section.name.as_str(), section.cpu_time_ms, section.gpu_time_ms)));
+
+	for (name, cpu_time_ms, gpu_time_ms) in rows {
+		let meter = |time_ms: f64, color| {
+			let color = match time_ms > FRAME_BUDGET_MS {
+				true => Color::hsv(0.0, 0.7, 0.9),
+				false => color,
+			};
+
+			((time_ms / FRAME_BUDGET_MS) as f32, color)
+		};
+
+		let meters = [
+			meter(cpu_time_ms, Color::hsv(200.0, 0.6, 0.9)),
+			meter(gpu_time_ms, Color::hsv(140.0, 0.6, 0.8)),
+		];
+
+		ui.meters(name, &meters, format!("{:.1} / {:.1}", cpu_time_ms, gpu_time_ms));
 	}
-}
\ No newline at end of file
+}
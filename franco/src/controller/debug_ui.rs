@@ -0,0 +1,299 @@
+use crate::prelude::*;
+use model::{DebugDrawCommand, DebugInspector, DebugWidgetId, UiRect};
+use model::{DEBUG_INSPECTOR_LABEL_FRACTION, DEBUG_INSPECTOR_MARGIN, DEBUG_INSPECTOR_ROW_HEIGHT, DEBUG_INSPECTOR_WIDTH};
+
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::Display;
+use std::hash::{Hash, Hasher};
+
+/// Between the rows of a panel and the header of the next
+const PANEL_SPACING: f32 = 0.2;
+const TEXT_INSET: f32 = 0.2;
+
+/// Space kept around controls within their rows
+const CONTROL_INSET: Vec2 = Vec2::new(0.05, 0.08);
+
+
+/// Builds the debug inspector for a frame, handling input for each widget as it's added.
+/// Widgets report what happened to them as they're added, and edit values in place, so the inspector
+/// can be built straight from the model with no state of its own beyond what `DebugInspector` keeps.
+/// Rows stack down from the top left of the window.
+pub struct DebugUi<'i> {
+	inspector: &'i mut DebugInspector,
+
+	/// In ui units
+	pointer: Option<Vec2>,
+	pressed: bool,
+	held: bool,
+
+	/// The top left of the next row
+	cursor: Vec2,
+
+	/// The panel widgets are currently being added to, and how many rows into it.
+	/// Together with a widget's label, these tell apart widgets from one frame to the next.
+	panel: String,
+	row: usize,
+}
+
+impl<'i> DebugUi<'i> {
+	/// `pressed` is whether the mouse went down this frame, and `held` whether it's still down
+	pub fn begin(inspector: &'i mut DebugInspector, pointer: Option<Vec2>, pressed: bool, held: bool) -> DebugUi<'i> {
+		inspector.draw_list.clear();
+
+		if !held {
+			inspector.active_widget = None;
+		}
+
+		let extents = inspector.metrics.extents();
+		let cursor = Vec2::new(-extents.x, extents.y) + Vec2::new(DEBUG_INSPECTOR_MARGIN, -DEBUG_INSPECTOR_MARGIN);
+
+		DebugUi {
+			inspector,
+			pointer,
+			pressed,
+			held,
+			cursor,
+			panel: String::new(),
+			row: 0,
+		}
+	}
+
+	/// A header that expands and collapses the panel when clicked. Returns whether the panel's contents should be added.
+	pub fn panel(&mut self, title: &str) -> bool {
+		if !self.panel.is_empty() {
+			self.cursor.y -= PANEL_SPACING;
+		}
+
+		self.panel = title.to_owned();
+		self.row = 0;
+
+		let row = self.next_row();
+		let id = self.widget_id("", 0);
+
+		if self.clicked(id, row) {
+			self.inspector.toggle_panel(title);
+		}
+
+		let expanded = self.inspector.is_expanded(title);
+		let marker = match expanded {
+			true => "-",
+			false => "+",
+		};
+
+		let color = self.highlight(Color::hsv(210.0, 0.4, 0.35), row);
+		self.rect(row, color, true);
+		self.text(format!("{} {}", marker, title), row.point(Vec2::from_x(-1.0)) + Vec2::from_x(TEXT_INSET), Color::white(), gfx::TextAlign::Left);
+
+		expanded
+	}
+
+	/// A read only value
+	pub fn label(&mut self, name: &str, value: impl Display) {
+		let control = self.labelled_row(name);
+		self.text(value.to_string(), control.point(Vec2::from_x(1.0)) - Vec2::from_x(TEXT_INSET), Color::grey(0.85), gfx::TextAlign::Right);
+	}
+
+	/// Drag along the bar to set `value` anywhere between `min` and `max`. Returns whether `value` changed.
+	pub fn slider(&mut self, name: &str, value: &mut f32, min: f32, max: f32) -> bool {
+		let control = self.labelled_row(name);
+		let track = control.shrink(CONTROL_INSET);
+		let id = self.widget_id(name, 0);
+
+		let mut changed = false;
+
+		if let Some(pointer) = self.dragged(id, track) {
+			let fraction = ((pointer.x - track.min().x) / track.size.x).clamp(0.0, 1.0);
+			let new_value = min + (max - min) * fraction;
+
+			changed = new_value != *value;
+			*value = new_value;
+		}
+
+		let fraction = ((*value - min) / (max - min)).clamp(0.0, 1.0);
+		let fill = UiRect {
+			center: track.point(Vec2::from_x(-1.0)) + Vec2::from_x(track.size.x * fraction / 2.0),
+			size: Vec2::new(track.size.x * fraction, track.size.y),
+		};
+
+		let track_color = self.highlight(Color::grey(0.25), track);
+		self.rect(track, track_color, false);
+		self.rect(fill, Color::hsv(200.0, 0.5, 0.6), false);
+		self.text(format!("{:.2}", value), track.point(Vec2::from_x(1.0)) - Vec2::from_x(TEXT_INSET), Color::white(), gfx::TextAlign::Right);
+
+		changed
+	}
+
+	/// Click to flip `value`. Returns whether it changed.
+	pub fn toggle(&mut self, name: &str, value: &mut bool) -> bool {
+		let control = self.labelled_row(name);
+		let id = self.widget_id(name, 0);
+
+		let changed = self.clicked(id, control);
+		if changed {
+			*value = !*value;
+		}
+
+		let check = UiRect {
+			center: control.point(Vec2::from_x(-1.0)) + Vec2::from_x(control.size.y / 2.0),
+			size: Vec2::splat(control.size.y),
+		}.shrink(CONTROL_INSET * 2.0);
+
+		let check_color = match *value {
+			true => Color::hsv(140.0, 0.6, 0.8),
+			false => Color::grey(0.25),
+		};
+
+		let check_color = self.highlight(check_color, control);
+		self.rect(check, check_color, true);
+
+		let text = match *value {
+			true => "on",
+			false => "off",
+		};
+
+		self.text(text, check.point(Vec2::from_x(1.0)) + Vec2::from_x(TEXT_INSET), Color::grey(0.85), gfx::TextAlign::Left);
+
+		changed
+	}
+
+	/// A row of buttons sharing the space evenly. Returns the index of the one clicked, if any.
+	pub fn buttons(&mut self, name: &str, labels: &[&str]) -> Option<usize> {
+		let control = self.labelled_row(name);
+		let button_width = control.size.x / labels.len().max(1) as f32;
+
+		let mut clicked = None;
+
+		for (idx, label) in labels.iter().enumerate() {
+			let button = UiRect {
+				center: control.point(Vec2::from_x(-1.0)) + Vec2::from_x(button_width * (idx as f32 + 0.5)),
+				size: Vec2::new(button_width, control.size.y),
+			}.shrink(CONTROL_INSET);
+
+			let id = self.widget_id(name, idx + 1);
+			if self.clicked(id, button) {
+				clicked = Some(idx);
+			}
+
+			let color = self.highlight(Color::hsv(30.0, 0.4, 0.45), button);
+			self.rect(button, color, true);
+			self.text(*label, button.center, Color::white(), gfx::TextAlign::Center);
+		}
+
+		clicked
+	}
+
+	/// Bars stacked within the row, each filled to a fraction of its width
+	pub fn meters(&mut self, name: &str, meters: &[(f32, Color)], value: impl Display) {
+		let control = self.labelled_row(name);
+		let area = control.shrink(CONTROL_INSET);
+		let bar_height = area.size.y / meters.len().max(1) as f32;
+
+		self.rect(area, Color::grey(0.2), false);
+
+		for (idx, &(fraction, color)) in meters.iter().enumerate() {
+			let fraction = fraction.clamp(0.0, 1.0);
+
+			let bar = UiRect {
+				center: area.point(Vec2::new(-1.0, 1.0)) + Vec2::new(area.size.x * fraction / 2.0, -bar_height * (idx as f32 + 0.5)),
+				size: Vec2::new(area.size.x * fraction, bar_height),
+			};
+
+			self.rect(bar, color, false);
+		}
+
+		self.text(value.to_string(), area.point(Vec2::from_x(1.0)) - Vec2::from_x(TEXT_INSET), Color::white(), gfx::TextAlign::Right);
+	}
+
+
+	fn next_row(&mut self) -> UiRect {
+		let size = Vec2::new(DEBUG_INSPECTOR_WIDTH, DEBUG_INSPECTOR_ROW_HEIGHT);
+		let row = UiRect {
+			center: self.cursor + size * Vec2::new(0.5, -0.5),
+			size,
+		};
+
+		self.cursor.y -= size.y;
+		self.row += 1;
+		row
+	}
+
+	/// Adds a row with `name` on the left, and returns the space left for a control
+	fn labelled_row(&mut self, name: &str) -> UiRect {
+		let row = self.next_row();
+		self.rect(row, Color::grey(0.12), false);
+
+		let label_width = row.size.x * DEBUG_INSPECTOR_LABEL_FRACTION;
+
+		let control = UiRect {
+			center: row.point(Vec2::from_x(1.0)) - Vec2::from_x((row.size.x - label_width) / 2.0),
+			size: Vec2::new(row.size.x - label_width, row.size.y),
+		};
+
+		self.text(name, row.point(Vec2::from_x(-1.0)) + Vec2::from_x(TEXT_INSET), Color::grey(0.7), gfx::TextAlign::Left);
+
+		control
+	}
+
+	fn widget_id(&self, name: &str, index: usize) -> DebugWidgetId {
+		let mut hasher = DefaultHasher::new();
+		(&self.panel, self.row, name, index).hash(&mut hasher);
+		DebugWidgetId(hasher.finish())
+	}
+
+	fn hovered(&self, rect: UiRect) -> bool {
+		let (min, max) = (rect.min(), rect.max());
+
+		self.pointer.map_or(false, |pointer| {
+			pointer.x >= min.x && pointer.x <= max.x
+				&& pointer.y >= min.y && pointer.y <= max.y
+		})
+	}
+
+	/// Whether the mouse went down on `rect` this frame, in which case `id` takes hold of the mouse
+	fn clicked(&mut self, id: DebugWidgetId, rect: UiRect) -> bool {
+		let clicked = self.pressed
+			&& self.inspector.active_widget.is_none()
+			&& self.hovered(rect);
+
+		if clicked {
+			self.inspector.active_widget = Some(id);
+		}
+
+		clicked
+	}
+
+	/// Where the mouse is, for as long as it's held after going down on `rect` - even once it's moved off it
+	fn dragged(&mut self, id: DebugWidgetId, rect: UiRect) -> Option<Vec2> {
+		self.clicked(id, rect);
+
+		match self.held && self.inspector.active_widget == Some(id) {
+			true => self.pointer,
+			false => None,
+		}
+	}
+
+	fn highlight(&self, color: Color, rect: UiRect) -> Color {
+		match self.hovered(rect) {
+			true => Color { r: color.r + 0.1, g: color.g + 0.1, b: color.b + 0.1, ..color },
+			false => color,
+		}
+	}
+
+	fn rect(&mut self, rect: UiRect, color: Color, rounded: bool) {
+		self.inspector.draw_list.push(DebugDrawCommand::Rect {
+			center: rect.center,
+			size: rect.size,
+			color,
+			rounded,
+		});
+	}
+
+	fn text(&mut self, text: impl Into<String>, position: Vec2, color: Color, align: gfx::TextAlign) {
+		self.inspector.draw_list.push(DebugDrawCommand::Text {
+			text: text.into(),
+			position,
+			color,
+			align,
+		});
+	}
+}
@@ -1,45 +1,173 @@
 use crate::prelude::*;
+use std::collections::HashMap;
 
-pub const TRICK_TIME: f32 = 1.4;
 pub const FISH_PLAYER_DIST_THRESHOLD: f32 = 1.0;
 pub const BOAT_PLAYER_DIST_THRESHOLD: f32 = 2.0;
 
-pub const PLAYER_MEET_THRESHOLD: f32 = 4.0;
+/// Followers within this distance of a friend starting a trick join in
+pub const COMBO_RADIUS: f32 = 8.0;
+/// Delay between each friend joining a combo
+pub const COMBO_STAGGER: f32 = 0.3;
+
+/// Affinity rises while a boat stays within `AFFINITY_NEAR_DISTANCE`, and falls once it's beyond `AFFINITY_FAR_DISTANCE`
+pub const AFFINITY_NEAR_DISTANCE: f32 = 10.0;
+pub const AFFINITY_FAR_DISTANCE: f32 = 30.0;
+pub const AFFINITY_GAIN_RATE: f32 = 0.02;
+pub const AFFINITY_DECAY_RATE: f32 = 0.05;
+pub const AFFINITY_TRICK_BONUS: f32 = 0.05;
+
+/// Bringing a boat to a friend's home island cheers them up
+pub const AFFINITY_HOME_VISIT_DISTANCE: f32 = 12.0;
+pub const AFFINITY_HOME_VISIT_RATE: f32 = 0.1;
+
+/// How much affinity a homesick friend needs before following again
+pub const AFFINITY_REJOIN_THRESHOLD: f32 = 0.3;
+
+/// How long a boat must stay close and calm before a friend trusts it enough to meet
+pub const TRUST_BUILD_TIME: f32 = 2.5;
+pub const TRUST_DECAY_RATE: f32 = 0.5;
+
+/// Shy friends flee boats moving faster than this, or under full sail, within `SHY_NOTICE_DISTANCE`
+pub const SHY_SPOOK_SPEED: f32 = 1.6;
+pub const SHY_NOTICE_DISTANCE: f32 = 12.0;
+pub const FLEE_SPEED: f32 = 3.0;
+
+/// Curious friends swim over to anchored boats within this distance
+pub const CURIOUS_NOTICE_DISTANCE: f32 = 16.0;
+pub const CURIOUS_APPROACH_SPEED: f32 = 1.5;
+
+/// Followers within this distance of each other steer as a flock
+pub const FLOCK_NEIGHBOUR_RADIUS: f32 = 6.0;
+/// Followers closer than this push away from each other
+pub const FLOCK_SEPARATION_RADIUS: f32 = 2.5;
+
+pub const FLOCK_SEPARATION_WEIGHT: f32 = 2.0;
+pub const FLOCK_ALIGNMENT_WEIGHT: f32 = 0.5;
+pub const FLOCK_COHESION_WEIGHT: f32 = 0.3;
+
 
 pub struct FriendController {
+	/// Which boat each follower was slotted around, by friend index - formations are rebuilt when this changes
+	slotted_followers: Vec<(usize, usize)>,
 }
 
 impl FriendController {
 	pub fn new(_engine: &mut toybox::Engine) -> FriendController {
-		FriendController {}
+		FriendController {
+			slotted_followers: Vec::new(),
+		}
 	}
 
 	pub fn update(&mut self, model: &mut model::Model) {
-		use model::FriendState;
+		use model::{FriendState, NetRole, SailState, Temperament, Trick};
+
+		// Clients receive friend state from the host, and only animate them
+		if model.global.net_role == NetRole::Client {
+			for friend in model.world.friends.iter_mut() {
+				friend.bob_phase += (1.0 + friend.speed / 2.0) * PI / 60.0;
+			}
+
+			return
+		}
+
+		let boats: Vec<&model::Player> = std::iter::once(&model.player)
+			.chain(model.split_screen.as_ref().map(|split| &split.player))
+			.chain(model.remote_players.iter().map(|remote| &remote.player))
+			.collect();
+
+		// Friends stick with whichever boat is nearest
+		let nearest_boats: Vec<usize> = model.world.friends.iter()
+			.map(|friend| nearest_boat(&boats, friend.map_position))
+			.collect();
+
+		self.update_formations(&mut model.world.friends, &nearest_boats);
+
+		let flock: Vec<FlockMember> = model.world.friends.iter()
+			.map(|friend| FlockMember {
+				position: friend.map_position,
+				heading: friend.heading,
+				name: friend.name,
+				following: friend.follows_player(),
+			})
+			.collect();
+
+		// Followers that started a trick this frame, for others to join in with
+		let mut combo_leaders = Vec::new();
+
+		// Meeting a friend interrupts the game state, which would stall a race
+		let can_meet = model.global.game_mode == model::GameMode::FriendFinding;
 
 		for (idx, friend) in model.world.friends.iter_mut().enumerate() {
+			let was_doing_tricks = matches!(friend.state, FriendState::DoingTricks(_));
 			let friend_direction = (idx % 2) as f32 * 2.0 - 1.0;
 
-			let trick_rate = 1.0 / TRICK_TIME / 60.0;
+			if let Some(queued) = &mut friend.queued_trick {
+				queued.delay -= 1.0/60.0;
+
+				if queued.delay <= 0.0 {
+					if matches!(friend.state, FriendState::Following | FriendState::HangingOut) {
+						friend.state = FriendState::DoingTricks(queued.trick);
+					}
+
+					friend.queued_trick = None;
+				}
+			}
 
 			let dist_threshold = match friend.name.is_fish() {
 				true => FISH_PLAYER_DIST_THRESHOLD,
 				false => BOAT_PLAYER_DIST_THRESHOLD,
 			};
 
-			let player_dir = Vec2::from_angle(model.player.heading);
-			let target_position = model.player.map_position
-				+ player_dir * model.player.speed
-				+ player_dir.perp() * friend_direction * model.player.speed.min(1.0);
+			let player = boats[nearest_boats[idx]];
+
+			let player_dir = Vec2::from_angle(player.heading);
+			let target_position = match friend.formation_slot {
+				Some(slot) => player.map_position
+					+ player_dir * (player.speed + slot.x)
+					+ player_dir.perp() * slot.y,
+
+				None => player.map_position
+					+ player_dir * player.speed
+					+ player_dir.perp() * friend_direction * player.speed.min(1.0),
+			};
 
 			let player_diff = target_position - friend.map_position;
 			let player_dist = (player_diff.length() - dist_threshold).max(0.0);
 			let heading_towards_player = player_diff.to_angle();
 
-			if !friend.met_player && player_dist < PLAYER_MEET_THRESHOLD {
-				friend.met_player = true;
-				friend.state = FriendState::DoingTricks(0.0);
-				model.global.game_state.notify_got_friend();
+			let boat_dist = (player.map_position - friend.map_position).length();
+			let temperament = friend.name.temperament();
+
+			if !friend.met_player() {
+				let boat_is_rushing = player.speed > SHY_SPOOK_SPEED || player.sail_state.speed() >= model.tunables.max_sail_speed;
+
+				if temperament == Temperament::Shy && boat_is_rushing && boat_dist < SHY_NOTICE_DISTANCE {
+					friend.state = FriendState::Fleeing(model::FRIEND_FLEE_TIME);
+					friend.trust = 0.0;
+				}
+
+				let calm = !matches!(friend.state, FriendState::Fleeing(_));
+
+				if calm && can_meet && player_dist < model.tunables.player_meet_threshold {
+					friend.trust += 1.0 / TRUST_BUILD_TIME / 60.0;
+				} else {
+					friend.trust = (friend.trust - TRUST_DECAY_RATE / 60.0).max(0.0);
+				}
+
+				if friend.trust >= 1.0 {
+					friend.trust = 1.0;
+					friend.meet(model.stats.session.playtime);
+					friend.state = FriendState::DoingTricks(friend.name.pick_trick());
+					model.global.game_state.notify_got_friend(&model.tunables);
+					model.stats.record_friend_met(friend.name);
+				}
+			}
+
+			if friend.met_player() {
+				let home_visited = boats.iter()
+					.any(|boat| (boat.map_position - friend.home_position).length() < AFFINITY_HOME_VISIT_DISTANCE);
+
+				update_affinity(friend, boat_dist, home_visited);
 			}
 
 			friend.map_position += Vec2::from_angle(friend.heading) * friend.speed / 60.0;
@@ -53,14 +181,27 @@ impl FriendController {
 
 			match friend.state {
 				FriendState::HangingOut => {
-					friend.speed += -friend.speed.min(1.0) * 4.0 / 60.0;
+					let anchored = matches!(player.sail_state, SailState::Anchored);
+					let investigating = !friend.met_player()
+						&& temperament == Temperament::Curious
+						&& anchored
+						&& player_dist > 0.0
+						&& boat_dist < CURIOUS_NOTICE_DISTANCE;
+
+					if investigating {
+						// Come over for a closer look
+						friend.speed += (CURIOUS_APPROACH_SPEED.min(player_dist) - friend.speed) / 60.0;
+						friend.heading += angle_difference(heading_towards_player, friend.heading) * 2.0 / 60.0;
+					} else {
+						friend.speed += -friend.speed.min(1.0) * 4.0 / 60.0;
 
-					// Look towards player
-					friend.heading += angle_difference(heading_towards_player, friend.heading) / 60.0;
+						// Look towards player
+						friend.heading += angle_difference(heading_towards_player, friend.heading) / 60.0;
+					}
 
 					if decision_time {
 						if rand::random::<f32>() < 0.2 {
-							friend.state = FriendState::DoingTricks(0.0);
+							friend.state = FriendState::DoingTricks(friend.name.pick_trick());
 						}
 
 						friend.decision_timer = 1.0 + rand::random::<f32>() * 1.0;
@@ -68,17 +209,26 @@ impl FriendController {
 				}
 
 				FriendState::Following => {
+					// Steer with the other followers so they don't bunch up or clip through each other
+					let flock_steering = flock_steering(idx, &flock);
+					let flock_heading_diff = match flock_steering.length() > 0.01 {
+						true => angle_difference(flock_steering.to_angle(), friend.heading) * flock_steering.length().min(1.0),
+						false => 0.0,
+					};
+
 					if player_dist > 0.0 {
 						// Head towards player but also along player heading
 						let attraction_heading_diff = angle_difference(heading_towards_player, friend.heading);
-						let cohesion_heading_diff = angle_difference(model.player.heading, friend.heading);
+						let cohesion_heading_diff = angle_difference(player.heading, friend.heading);
 
-						let heading_diff = attraction_heading_diff + cohesion_heading_diff/player_dist.max(1.0) + friend.heading_wander;
+						let heading_diff = attraction_heading_diff + cohesion_heading_diff/player_dist.max(1.0)
+							+ flock_heading_diff + friend.heading_wander;
 
 						friend.heading += heading_diff / 60.0;
 					} else {
 						// Head around player if too close
-						friend.heading += angle_difference(heading_towards_player + PI/2.0*friend_direction, friend.heading) * 0.5 / 60.0;
+						let heading_diff = angle_difference(heading_towards_player + PI/2.0*friend_direction, friend.heading) * 0.5;
+						friend.heading += (heading_diff + flock_heading_diff) / 60.0;
 					}
 
 					if player_dist > 0.0 {
@@ -86,7 +236,9 @@ impl FriendController {
 
 						if decision_time {
 							if rand::random::<f32>() < 0.4 {
-								friend.state = FriendState::DoingTricks(0.0);
+								let trick = friend.name.pick_trick();
+								friend.state = FriendState::DoingTricks(trick);
+								combo_leaders.push((idx, trick.kind));
 							}
 						}
 
@@ -96,27 +248,254 @@ impl FriendController {
 					}
 				}
 
-				FriendState::DoingTricks(phase) => {
-					let new_phase = phase + trick_rate;
+				FriendState::Fleeing(timer) => {
+					friend.heading += angle_difference(heading_towards_player + PI, friend.heading) * 2.0 / 60.0;
+					friend.speed += (FLEE_SPEED - friend.speed) * 2.0 / 60.0;
+
+					let new_timer = timer - 1.0/60.0;
+					if new_timer > 0.0 {
+						friend.state = FriendState::Fleeing(new_timer);
+					} else {
+						friend.state = FriendState::HangingOut;
+						friend.decision_timer = 2.0 + rand::random::<f32>() * 2.0;
+					}
+				}
+
+				FriendState::ReturningHome => {
+					let home_diff = friend.home_position - friend.map_position;
+
+					if home_diff.length() > AFFINITY_HOME_VISIT_DISTANCE / 2.0 {
+						friend.heading += angle_difference(home_diff.to_angle(), friend.heading) * 2.0 / 60.0;
+						friend.speed += (2.0 - friend.speed) / 60.0;
+					} else {
+						friend.state = FriendState::HangingOut;
+						friend.decision_timer = 2.0 + rand::random::<f32>() * 2.0;
+					}
+				}
+
+				FriendState::DoingTricks(trick) => {
+					let new_phase = trick.phase + 1.0 / trick.duration / 60.0;
 					if new_phase < 1.0 {
-						friend.state = FriendState::DoingTricks(new_phase);
+						friend.state = FriendState::DoingTricks(Trick { phase: new_phase, ..trick });
 					} else {
 						friend.decision_timer = 2.0 + rand::random::<f32>() * 2.0;
-						friend.state = match friend.met_player && player_dist > 0.0 {
+						friend.state = match friend.follows_player() && player_dist > 0.0 {
 							false => FriendState::HangingOut,
 							true => FriendState::Following,
 						}
 					}
 				}
 			}
+
+			let started_trick = !was_doing_tricks && matches!(friend.state, FriendState::DoingTricks(_));
+			let distance_to_player = (friend.map_position - model.player.map_position).length();
+
+			if started_trick && distance_to_player < model::TRICK_SEEN_DISTANCE {
+				model.stats.record_trick_seen(friend.name);
+
+				// Showing off for someone who's watching is fun
+				if friend.met_player() {
+					friend.affinity = (friend.affinity + AFFINITY_TRICK_BONUS).min(1.0);
+				}
+			}
+		}
+
+		for (leader_idx, kind) in combo_leaders {
+			queue_combo(&mut model.world.friends, leader_idx, kind);
 		}
 	}
+
+
+	/// Gives each follower a slot around the boat it follows - swimmers in the wake, boats abeam.
+	/// Slots are only reassigned when someone joins, leaves or changes boat, so friends don't shuffle around.
+	fn update_formations(&mut self, friends: &mut [model::Friend], nearest_boats: &[usize]) {
+		let followers: Vec<(usize, usize)> = friends.iter()
+			.enumerate()
+			.filter(|(_, friend)| friend.follows_player())
+			.map(|(idx, _)| (idx, nearest_boats[idx]))
+			.collect();
+
+		if followers == self.slotted_followers {
+			return
+		}
+
+		// Well liked friends take the inner slots, so the ones others dislike end up on the outside
+		let popularity = |idx: usize| -> f32 {
+			followers.iter()
+				.filter(|&&(other, boat)| other != idx && boat == nearest_boats[idx])
+				.map(|&(other, _)| friends[other].name.relationship(friends[idx].name))
+				.sum()
+		};
+
+		let mut slot_order = followers.clone();
+		slot_order.sort_by(|a, b| popularity(b.0).total_cmp(&popularity(a.0)));
+
+		let mut ranks: HashMap<(usize, bool), usize> = HashMap::new();
+
+		for &(idx, boat) in slot_order.iter() {
+			let friend = &mut friends[idx];
+			let is_fish = friend.name.is_fish();
+
+			let rank = ranks.entry((boat, is_fish)).or_default();
+			friend.formation_slot = Some(formation_slot(is_fish, *rank));
+			*rank += 1;
+		}
+
+		self.slotted_followers = followers;
+	}
+}
+
+
+
+/// Cues nearby followers to join in with a trick one after another, nearest first.
+/// Each uses their own species' version of the trick where they know it.
+fn queue_combo(friends: &mut [model::Friend], leader_idx: usize, kind: model::TrickKind) {
+	use model::{FriendState, QueuedTrick};
+
+	let leader_position = friends[leader_idx].map_position;
+
+	let mut joiners: Vec<(usize, f32)> = friends.iter()
+		.enumerate()
+		.filter(|&(idx, friend)| idx != leader_idx && friend.follows_player() && friend.queued_trick.is_none())
+		.filter(|(_, friend)| matches!(friend.state, FriendState::Following))
+		.map(|(idx, friend)| (idx, (friend.map_position - leader_position).length()))
+		.filter(|&(_, dist)| dist < COMBO_RADIUS)
+		.collect();
+
+	joiners.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+	for (rank, (idx, _)) in joiners.into_iter().enumerate() {
+		let friend = &mut friends[idx];
+		let trick = friend.name.find_trick(kind)
+			.unwrap_or_else(|| friend.name.pick_trick());
+
+		friend.queued_trick = Some(QueuedTrick {
+			trick,
+			delay: (rank + 1) as f32 * COMBO_STAGGER,
+		});
+	}
+}
+
+
+fn formation_slot(is_fish: bool, rank: usize) -> Vec2 {
+	let side = match rank % 2 {
+		0 => 1.0,
+		_ => -1.0,
+	};
+
+	if is_fish {
+		// A V spreading out behind the boat
+		let row = (rank / 2 + 1) as f32;
+		Vec2::new(-2.0 - row * 2.0, side * row * 1.2)
+	} else {
+		let row = (rank / 2) as f32;
+		Vec2::new(-row * 2.5, side * (4.0 + row))
+	}
+}
+
+
+fn nearest_boat(boats: &[&model::Player], position: Vec2) -> usize {
+	boats.iter()
+		.enumerate()
+		.min_by(|(_, a), (_, b)| {
+			let dist_a = (a.map_position - position).length();
+			let dist_b = (b.map_position - position).length();
+			dist_a.total_cmp(&dist_b)
+		})
+		.map_or(0, |(idx, _)| idx)
+}
+
+
+
+fn update_affinity(friend: &mut model::Friend, boat_dist: f32, home_visited: bool) {
+	use model::FriendState;
+
+	let mut rate = 0.0;
+
+	if boat_dist < AFFINITY_NEAR_DISTANCE {
+		rate += AFFINITY_GAIN_RATE;
+	} else if boat_dist > AFFINITY_FAR_DISTANCE {
+		rate -= AFFINITY_DECAY_RATE;
+	}
+
+	if home_visited {
+		rate += AFFINITY_HOME_VISIT_RATE;
+	}
+
+	friend.affinity = (friend.affinity + rate / 60.0).clamp(0.0, 1.0);
+
+	if !friend.homesick && friend.affinity <= 0.0 {
+		friend.homesick = true;
+		friend.queued_trick = None;
+		friend.state = FriendState::ReturningHome;
+
+	} else if friend.homesick && !friend.said_goodbye && friend.affinity >= AFFINITY_REJOIN_THRESHOLD {
+		friend.homesick = false;
+		friend.state = FriendState::Following;
+	}
+}
+
+
+
+struct FlockMember {
+	name: model::FriendName,
+	position: Vec2,
+	heading: f32,
+	following: bool,
+}
+
+/// Boids style separation, alignment and cohesion with nearby followers
+fn flock_steering(idx: usize, flock: &[FlockMember]) -> Vec2 {
+	let this = &flock[idx];
+
+	let mut separation = Vec2::zero();
+	let mut alignment = Vec2::zero();
+	let mut center = Vec2::zero();
+	let mut total_weight = 0.0;
+
+	for (other_idx, other) in flock.iter().enumerate() {
+		if other_idx == idx || !other.following {
+			continue
+		}
+
+		let diff = this.position - other.position;
+		let dist = diff.length();
+
+		if dist > FLOCK_NEIGHBOUR_RADIUS {
+			continue
+		}
+
+		// Friends give those they dislike a wider berth, and stick closer to those they like
+		let relationship = this.name.relationship(other.name);
+		let separation_radius = FLOCK_SEPARATION_RADIUS * (1.0 - relationship * 0.5);
+
+		if dist < separation_radius && dist > 0.001 {
+			separation += diff / dist * (1.0 - dist / separation_radius);
+		}
+
+		let weight = 1.0 + relationship * 0.5;
+
+		alignment += Vec2::from_angle(other.heading) * weight;
+		center += other.position * weight;
+		total_weight += weight;
+	}
+
+	if total_weight <= 0.0 {
+		return Vec2::zero()
+	}
+
+	let alignment = alignment / total_weight;
+	let cohesion = (center / total_weight - this.position) / FLOCK_NEIGHBOUR_RADIUS;
+
+	separation * FLOCK_SEPARATION_WEIGHT
+		+ alignment * FLOCK_ALIGNMENT_WEIGHT
+		+ cohesion * FLOCK_COHESION_WEIGHT
 }
 
 
 
 
-fn angle_difference(a: f32, b: f32) -> f32 {
+pub fn angle_difference(a: f32, b: f32) -> f32 {
 	let mut angle_diff = (a - b) % TAU;
 
 	if angle_diff > PI {
@@ -2,7 +2,7 @@ use crate::prelude::*;
 
 toybox::declare_input_context! {
 	struct GlobalActions "Global" {
-		trigger quit { "Quit" [Scancode::Escape] }
+		trigger toggle_split_screen { "Toggle Split Screen" [Scancode::F2] }
 	}
 }
 
@@ -22,14 +22,28 @@ impl GlobalController {
 	pub fn update(&mut self, engine: &mut toybox::Engine, model: &mut model::Model) {
 		let frame_state = engine.input.frame_state();
 
-		if frame_state.active(self.actions.quit) {
-			model.global.wants_hard_quit = true;
+		// Split screen and networked play don't mix
+		if frame_state.active(self.actions.toggle_split_screen) && model.global.net_role == model::NetRole::Offline {
+			model.split_screen = match model.split_screen {
+				Some(_) => None,
+				None => Some(model::SplitScreen::new(&model.resources, &model.player)),
+			};
 		}
 
-		model.global.game_state.update();
+		if model.global.is_paused() {
+			return
+		}
+
+		// Networked sessions stay on the first level, since clients can't follow the host to another map
+		let has_next_level = model.global.net_role == model::NetRole::Offline
+			&& model.progression.next_level(&model.resources).is_some();
+
+		model.global.game_state.update(model.global.game_mode, has_next_level, &model.tunables);
+
+		let is_friend_finding = model.global.game_mode == model::GameMode::FriendFinding;
 
-		if !model.global.game_state.has_ended() && model.world.friends.iter().all(|f| f.met_player) {
-			model.global.game_state.notify_end_game();
+		if is_friend_finding && !model.global.game_state.has_ended() && model.world.friends.iter().all(|f| f.met_player()) {
+			model.global.game_state.notify_end_game(&model.tunables);
 		}
 	}
 }
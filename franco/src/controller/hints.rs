@@ -0,0 +1,72 @@
+use crate::prelude::*;
+use model::{GameState, HintKind};
+
+
+/// Runs the tutorial hint rules for each local player, remembering hints that have been followed
+pub struct HintController;
+
+impl HintController {
+	pub fn new(_engine: &mut toybox::Engine) -> HintController {
+		HintController
+	}
+
+	pub fn update(&mut self, model: &mut model::Model) {
+		// Rules only count time the player could be doing something about them
+		let in_play = matches!(model.global.game_state, GameState::Playing | GameState::Racing);
+
+		update_hints(&model.player, &mut model.ui, &mut model.settings, in_play);
+
+		if let Some(split) = &mut model.split_screen {
+			update_hints(&split.player, &mut split.ui, &mut model.settings, in_play);
+		}
+	}
+}
+
+
+fn update_hints(player: &model::Player, ui: &mut model::Ui, settings: &mut model::Settings, in_play: bool) {
+	let dt = 1.0/60.0;
+
+	for (idx, &kind) in HintKind::ALL.iter().enumerate() {
+		if settings.completed_hints.contains(&kind) {
+			continue
+		}
+
+		// Players who find their own way don't need telling
+		if kind.is_followed(player, ui) {
+			settings.completed_hints.push(kind);
+			continue
+		}
+
+		let rule_timer = &mut ui.hints.rule_timers[idx];
+
+		match in_play && kind.applies(player) {
+			true => *rule_timer += dt,
+			false => *rule_timer = 0.0,
+		}
+	}
+
+	let hints = &mut ui.hints;
+	hints.timer = (hints.timer - dt).max(0.0);
+
+	// Completions are shared between split screen players, so either can follow the other's hint
+	let active_completed = hints.active.map_or(false, |kind| settings.completed_hints.contains(&kind));
+
+	if hints.state.is_open() && (active_completed || hints.timer <= 0.0 || !in_play) {
+		hints.dismiss();
+	}
+
+	if matches!(hints.state, model::UiPanelState::Closed) && hints.timer <= 0.0 && in_play {
+		let ready = HintKind::ALL.iter()
+			.zip(hints.rule_timers.iter())
+			.position(|(kind, &rule_timer)| !settings.completed_hints.contains(kind) && rule_timer >= kind.delay());
+
+		// Ignored hints come back, but only once their rule has held for another full delay
+		if let Some(idx) = ready {
+			hints.rule_timers[idx] = 0.0;
+			hints.show(HintKind::ALL[idx]);
+		}
+	}
+
+	hints.state.update();
+	hints.pulse_phase = (hints.pulse_phase + 1.0/60.0) % 1.0;
+}
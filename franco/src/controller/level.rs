@@ -0,0 +1,81 @@
+use crate::prelude::*;
+use model::GameState;
+
+pub struct LevelController;
+
+impl LevelController {
+	pub fn new(_engine: &mut toybox::Engine) -> LevelController {
+		LevelController
+	}
+
+	pub fn update(&mut self, model: &mut model::Model) {
+		match model.global.game_state {
+			GameState::LeavingLevel(_) => {
+				let next_level = match model.progression.next_level(&model.resources) {
+					Some(next_level) => next_level,
+					None => return,
+				};
+
+				// Loading starts as the boat leaves, so it's usually ready by the time the fog has closed in
+				let next_info = &model.resources.levels[next_level];
+				if !next_info.in_main_project() && model.progression.loader.is_none() {
+					model.progression.loader = Some(model::ProjectLoader::start(&next_info.project));
+				}
+
+				model.player.sail_state = model::SailState::Sailing{speed: model.tunables.max_sail_speed};
+			}
+
+			GameState::LoadingLevel => {
+				if let Err(err) = self.try_enter_next_level(model) {
+					eprintln!("Failed to load next level: {}", err);
+					model.global.game_state = GameState::PostGame;
+				}
+			}
+
+			_ => {}
+		}
+	}
+
+	/// Swaps in the next level's world once its project is available
+	fn try_enter_next_level(&mut self, model: &mut model::Model) -> Result<()> {
+		let next_level = model.progression.next_level(&model.resources)
+			.ok_or("No level to load")?;
+
+		if !model.resources.levels[next_level].in_main_project() {
+			let loader = model.progression.loader.as_mut()
+				.ok_or("Next level's project was never requested")?;
+
+			match loader.poll() {
+				Some(project) => model.resources.level_project = Some(project?),
+				None => return Ok(()),
+			}
+		} else {
+			model.resources.level_project = None;
+		}
+
+		model.progression.loader = None;
+		model.progression.level = next_level;
+
+		let resources = &model.resources;
+		let new_world = model::World::new(resources.level_scene(next_level), resources.island_scenes())?;
+		let previous_world = std::mem::replace(&mut model.world, new_world);
+
+		// Boats arrive at the center of the new map, with their friends in tow
+		model.player.map_position = Vec2::zero();
+		model.player.speed = 0.0;
+		model.player.sail_state = model::SailState::Anchored;
+		model.player.harbour = model::HarbourState::AtSea;
+
+		if let Some(split) = &mut model.split_screen {
+			split.player.map_position = Vec2::new(3.0, 0.0);
+			split.player.speed = 0.0;
+			split.player.sail_state = model::SailState::Anchored;
+			split.player.harbour = model::HarbourState::AtSea;
+		}
+
+		model.world.carry_over_friends(previous_world, model.player.map_position);
+		model.global.game_state = GameState::ArrivingLevel(model.tunables.level_arrive_time);
+
+		Ok(())
+	}
+}
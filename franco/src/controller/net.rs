@@ -0,0 +1,380 @@
+use crate::prelude::*;
+use std::net::{SocketAddr, Ipv4Addr};
+
+use model::{NetRole, RemotePlayer, SailState};
+use net::{NetOptions, Host, HostEvent, Client, ClientEvent, Snapshot, BoatSnapshot, FriendSnapshot, BoatInput};
+use std::collections::VecDeque;
+
+
+enum Session {
+	Offline,
+	Host(Host),
+	Client(ClientSession),
+}
+
+
+struct ClientSession {
+	client: Client,
+
+	/// Host time that remote entities are currently being rendered at
+	render_time: f32,
+}
+
+
+/// A headless client that sails around in circles, for testing with `--loopback`
+struct LoopbackBot {
+	client: Client,
+	time: f32,
+}
+
+
+
+pub struct NetController {
+	session: Session,
+	bot: Option<LoopbackBot>,
+
+	frame: u32,
+}
+
+impl NetController {
+	pub fn new(_engine: &mut toybox::Engine, options: NetOptions) -> Result<NetController> {
+		let mut bot = None;
+
+		let session = match options {
+			NetOptions::Offline => Session::Offline,
+
+			NetOptions::Host { port } => {
+				let transport = net::UdpTransport::bind((Ipv4Addr::UNSPECIFIED, port))?;
+				println!("[net] hosting on port {}", port);
+				Session::Host(Host::new(Box::new(transport)))
+			}
+
+			NetOptions::Join { host_addr } => {
+				let transport = net::UdpTransport::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+				println!("[net] joining {}", host_addr);
+
+				Session::Client(ClientSession {
+					client: Client::new(Box::new(transport), host_addr),
+					render_time: 0.0,
+				})
+			}
+
+			NetOptions::Loopback => {
+				let network = net::LoopbackNetwork::new();
+				let host_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, net::DEFAULT_PORT));
+				let bot_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, net::DEFAULT_PORT + 1));
+
+				bot = Some(LoopbackBot {
+					client: Client::new(Box::new(network.bind(bot_addr)), host_addr),
+					time: 0.0,
+				});
+
+				Session::Host(Host::new(Box::new(network.bind(host_addr))))
+			}
+		};
+
+		Ok(NetController {
+			session,
+			bot,
+
+			frame: 0,
+		})
+	}
+
+	/// Applies everything received since last frame. Should run before any simulation
+	pub fn receive(&mut self, model: &mut model::Model) {
+		let dt = 1.0/60.0;
+
+		model.global.net_role = match self.session {
+			Session::Offline => NetRole::Offline,
+			Session::Host(_) => NetRole::Host,
+			Session::Client(_) => NetRole::Client,
+		};
+
+		match &mut self.session {
+			Session::Offline => {}
+
+			Session::Host(host) => {
+				for event in host.poll(dt) {
+					handle_host_event(model, event);
+				}
+			}
+
+			Session::Client(session) => {
+				let mut disconnected = false;
+				let mut new_snapshot = false;
+
+				for event in session.client.poll(dt) {
+					match event {
+						ClientEvent::Disconnected => disconnected = true,
+						ClientEvent::Snapshot => new_snapshot = true,
+						ClientEvent::Connected(_) => {}
+					}
+				}
+
+				if disconnected {
+					// Carry on alone with whatever state we last had
+					println!("[net] continuing offline");
+					model.remote_players.clear();
+					model.global.net_role = NetRole::Offline;
+					self.session = Session::Offline;
+				} else {
+					session.apply_snapshots(model, new_snapshot);
+				}
+			}
+		}
+	}
+
+	/// Sends the results of this frame's simulation. Should run after all other controllers
+	pub fn send(&mut self, model: &model::Model) {
+		self.frame += 1;
+
+		match &mut self.session {
+			Session::Offline => {}
+
+			Session::Host(host) => {
+				if self.frame % net::SNAPSHOT_INTERVAL == 0 && host.num_clients() > 0 {
+					let (boats, friends) = build_snapshot(model);
+					host.broadcast_snapshot(boats, friends);
+				}
+			}
+
+			Session::Client(session) => {
+				let sail_speed = model.player.sail_state.speed() as i8;
+				session.client.send_input(sail_speed, model.ui.wheel_angle(), model.player.paint);
+			}
+		}
+
+		if let Some(bot) = &mut self.bot {
+			bot.update();
+		}
+	}
+
+	pub fn shutdown(&mut self) {
+		match &mut self.session {
+			Session::Offline => {}
+			Session::Host(host) => host.shutdown(),
+			Session::Client(session) => session.client.shutdown(),
+		}
+	}
+}
+
+
+
+fn handle_host_event(model: &mut model::Model, event: HostEvent) {
+	match event {
+		HostEvent::ClientJoined(player_id) => {
+			// New boats start next to the host
+			let mut remote = RemotePlayer::new(player_id);
+			remote.player.map_position = model.player.map_position + Vec2::from_angle(model.player.heading).perp() * 3.0;
+			remote.player.heading = model.player.heading;
+			model.remote_players.push(remote);
+		}
+
+		HostEvent::ClientLeft(player_id) => {
+			model.remote_players.retain(|remote| remote.id != player_id);
+		}
+
+		HostEvent::Input(player_id, input) => {
+			if let Some(remote) = model.remote_players.iter_mut().find(|remote| remote.id == player_id) {
+				let sail_speed = (input.sail_speed as i32).clamp(0, model.tunables.max_sail_speed);
+				remote.player.sail_state = SailState::from_speed(sail_speed);
+				remote.wheel_angle = input.wheel_angle.clamp(-PI, PI);
+				remote.player.paint = input.paint;
+			}
+		}
+	}
+}
+
+
+fn build_snapshot(model: &model::Model) -> (Vec<BoatSnapshot>, Vec<FriendSnapshot>) {
+	let host_boat = boat_snapshot(0, &model.player);
+	let remote_boats = model.remote_players.iter()
+		.map(|remote| boat_snapshot(remote.id, &remote.player));
+
+	let boats = std::iter::once(host_boat)
+		.chain(remote_boats)
+		.collect();
+
+	let friends = model.world.friends.iter()
+		.map(|friend| FriendSnapshot {
+			map_position: friend.map_position,
+			heading: friend.heading,
+			speed: friend.speed,
+			state: friend.state,
+			met_player: friend.met_player(),
+			trust: friend.trust,
+		})
+		.collect();
+
+	(boats, friends)
+}
+
+fn boat_snapshot(player_id: u16, player: &model::Player) -> BoatSnapshot {
+	BoatSnapshot {
+		player_id,
+		map_position: player.map_position,
+		heading: player.heading,
+		speed: player.speed,
+		sail_speed: player.sail_state.speed() as i8,
+		paint: player.paint,
+	}
+}
+
+
+
+impl ClientSession {
+	fn apply_snapshots(&mut self, model: &mut model::Model, new_snapshot: bool) {
+		let dt = 1.0/60.0;
+
+		let player_id = match self.client.player_id() {
+			Some(player_id) => player_id,
+			None => return,
+		};
+
+		let snapshots = self.client.snapshots();
+		let latest = match snapshots.back() {
+			Some(latest) => latest,
+			None => return,
+		};
+
+		// Advance smoothly, but stay roughly INTERPOLATION_DELAY behind the newest snapshot
+		let target_time = snapshot_time(latest) - net::INTERPOLATION_DELAY;
+		self.render_time += dt;
+
+		if (target_time - self.render_time).abs() > 0.25 {
+			self.render_time = target_time;
+		} else {
+			self.render_time += (target_time - self.render_time) * 0.05;
+		}
+
+		let to = snapshots.iter()
+			.find(|s| snapshot_time(s) >= self.render_time)
+			.unwrap_or(latest);
+
+		let from = snapshots.iter()
+			.rev()
+			.find(|s| snapshot_time(s) <= self.render_time)
+			.unwrap_or(to);
+
+		let t = match to.sequence > from.sequence {
+			true => ((self.render_time - snapshot_time(from)) / (snapshot_time(to) - snapshot_time(from))).clamp(0.0, 1.0),
+			false => 1.0,
+		};
+
+		let map_size = model.world.map.size + Vec2::splat(50.0);
+
+
+		// Our own boat is simulated locally, ahead of the host. When a new snapshot arrives it's restarted from the hosts
+		// version and the inputs the host hadn't applied yet are replayed over it, so it only moves if the prediction was wrong
+		if new_snapshot {
+			if let Some(own_boat) = latest.boats.iter().find(|boat| boat.player_id == player_id) {
+				reconcile_own_boat(&mut model.player, own_boat, self.client.unacked_inputs(), map_size, &model.tunables);
+			}
+		}
+
+
+		model.remote_players.retain(|remote| to.boats.iter().any(|boat| boat.player_id == remote.id));
+
+		for boat in to.boats.iter().filter(|boat| boat.player_id != player_id) {
+			let remote_idx = match model.remote_players.iter().position(|remote| remote.id == boat.player_id) {
+				Some(idx) => idx,
+				None => {
+					model.remote_players.push(RemotePlayer::new(boat.player_id));
+					model.remote_players.len() - 1
+				}
+			};
+
+			let prev_boat = from.boats.iter()
+				.find(|prev| prev.player_id == boat.player_id)
+				.unwrap_or(boat);
+
+			let player = &mut model.remote_players[remote_idx].player;
+			player.map_position = lerp_map_position(prev_boat.map_position, boat.map_position, t, map_size);
+			player.heading = lerp_heading(prev_boat.heading, boat.heading, t);
+			player.speed = t.ease_linear(prev_boat.speed, boat.speed);
+			player.sail_state = SailState::from_speed(boat.sail_speed as i32);
+			player.paint = boat.paint;
+		}
+
+
+		for (idx, friend) in model.world.friends.iter_mut().enumerate() {
+			let (prev, next) = match (from.friends.get(idx), to.friends.get(idx)) {
+				(Some(prev), Some(next)) => (prev, next),
+				(None, Some(next)) => (next, next),
+				_ => continue,
+			};
+
+			friend.map_position = lerp_map_position(prev.map_position, next.map_position, t, map_size);
+			friend.heading = lerp_heading(prev.heading, next.heading, t);
+			friend.speed = t.ease_linear(prev.speed, next.speed);
+			friend.trust = t.ease_linear(prev.trust, next.trust);
+
+			friend.state = match (prev.state, next.state) {
+				(model::FriendState::DoingTricks(a), model::FriendState::DoingTricks(b)) if a.kind == b.kind && b.phase >= a.phase
+					=> model::FriendState::DoingTricks(model::Trick { phase: t.ease_linear(a.phase, b.phase), ..b }),
+				(_, state) => state,
+			};
+
+			if !friend.met_player() && latest.friends.get(idx).map_or(false, |f| f.met_player) {
+				friend.meet(model.stats.session.playtime);
+
+				if model.global.game_mode == model::GameMode::FriendFinding {
+					model.global.game_state.notify_got_friend(&model.tunables);
+				}
+
+				model.stats.record_friend_met(friend.name);
+			}
+		}
+	}
+}
+
+
+/// Resets `player` to the hosts version of it, then simulates it forward through `unacked_inputs` like the host will
+fn reconcile_own_boat(player: &mut model::Player, own_boat: &BoatSnapshot, unacked_inputs: &VecDeque<BoatInput>, map_size: Vec2, tunables: &model::Tunables) {
+	player.map_position = own_boat.map_position;
+	player.heading = own_boat.heading;
+	player.speed = own_boat.speed;
+
+	for input in unacked_inputs {
+		let sail_speed = (input.sail_speed as i32).clamp(0, tunables.max_sail_speed);
+		player.sail_state = SailState::from_speed(sail_speed);
+
+		// Map wraps were already counted when they were first predicted
+		controller::update_boat(player, input.wheel_angle.clamp(-PI, PI), map_size, tunables);
+	}
+}
+
+
+fn snapshot_time(snapshot: &Snapshot) -> f32 {
+	(snapshot.sequence * net::SNAPSHOT_INTERVAL) as f32 / 60.0
+}
+
+/// Positions that jumped across the map edge between snapshots aren't interpolated
+fn lerp_map_position(from: Vec2, to: Vec2, t: f32, map_size: Vec2) -> Vec2 {
+	let diff = to - from;
+
+	if diff.x.abs() > map_size.x/2.0 || diff.y.abs() > map_size.y/2.0 {
+		to
+	} else {
+		from + diff * t
+	}
+}
+
+fn lerp_heading(from: f32, to: f32, t: f32) -> f32 {
+	from + controller::angle_difference(to, from) * t
+}
+
+
+
+impl LoopbackBot {
+	fn update(&mut self) {
+		let dt = 1.0/60.0;
+
+		self.client.poll(dt);
+		self.time += dt;
+
+		let wheel_angle = (self.time * 0.2).sin() * PI / 2.0;
+		self.client.send_input(3, wheel_angle, 0);
+	}
+}
@@ -0,0 +1,150 @@
+use crate::prelude::*;
+
+use model::{PausePage, PauseItem, SettingItem, SettingValue};
+
+
+toybox::declare_input_context! {
+	struct PauseActions "Pause" {
+		trigger pause { "Pause" [Scancode::Escape] }
+	}
+}
+
+toybox::declare_input_context! {
+	struct PauseMenuActions "Pause Menu" {
+		priority [30]
+
+		trigger back { "Back" [Scancode::Escape] }
+		trigger confirm { "Confirm" [Scancode::Return] }
+
+		trigger up { "Up" [Scancode::Up] }
+		trigger down { "Down" [Scancode::Down] }
+		trigger left { "Decrease" [Scancode::Left] }
+		trigger right { "Increase" [Scancode::Right] }
+
+		trigger click { "Interact" [MouseButton::Left] }
+		pointer mouse { "Mouse" }
+	}
+}
+
+
+pub struct PauseController {
+	actions: PauseActions,
+	menu_actions: PauseMenuActions,
+}
+
+impl PauseController {
+	pub fn new(engine: &mut toybox::Engine) -> PauseController {
+		PauseController {
+			actions: PauseActions::new_active(&mut engine.input),
+			menu_actions: PauseMenuActions::new(&mut engine.input),
+		}
+	}
+
+	pub fn update(&mut self, engine: &mut toybox::Engine, model: &mut model::Model) {
+		let dpi = toybox::window::display_dpi(&engine.window);
+		model.pause_menu.metrics = model::UiMetrics::for_viewport(engine.gfx.backbuffer_size(), dpi, model.settings.ui_scale);
+
+		let menu_active = engine.input.is_context_active(self.menu_actions.context_id());
+
+		if menu_active {
+			self.update_menu(engine, model);
+		} else if engine.input.frame_state().active(self.actions.pause) {
+			self.open_menu(engine, model);
+		}
+
+		model.pause_menu.state.update();
+	}
+
+	fn update_menu(&mut self, engine: &mut toybox::Engine, model: &mut model::Model) {
+		let input = engine.input.frame_state();
+		let actions = &self.menu_actions;
+		let menu = &mut model.pause_menu;
+
+		if input.active(actions.back) {
+			match menu.page {
+				PausePage::Main => self.close_menu(engine, model),
+				PausePage::Settings => menu.return_to_main(),
+			}
+
+			return
+		}
+
+		if input.active(actions.up) {
+			menu.select_previous();
+		} else if input.active(actions.down) {
+			menu.select_next();
+		}
+
+		if let Some(setting) = menu.selected_setting() {
+			if input.active(actions.left) {
+				model.settings.adjust(setting, -1);
+			} else if input.active(actions.right) {
+				model.settings.adjust(setting, 1);
+			}
+		}
+
+		let mut confirmed = input.active(actions.confirm);
+
+		if let Some(pointer) = input.mouse(actions.mouse) {
+			let pos = menu.metrics.pointer_to_ui(pointer);
+
+			if let Some(row) = menu.row_at(pos) {
+				menu.selected = row;
+
+				if input.active(actions.click) {
+					confirmed = true;
+
+					// Clicking along a slider sets it directly
+					if let Some(setting) = menu.selected_setting() {
+						if let SettingValue::Slider(_) = model.settings.get(setting) {
+							let slider_start = model::PAUSE_MENU_SLIDER_CENTER - model::PAUSE_MENU_SLIDER_WIDTH / 2.0;
+							let ratio = (pos.x - slider_start) / model::PAUSE_MENU_SLIDER_WIDTH;
+							model.settings.set_ratio(setting, ratio);
+							confirmed = false;
+						}
+					}
+				}
+			}
+		}
+
+		if confirmed {
+			self.confirm_selection(engine, model);
+		}
+	}
+
+	fn confirm_selection(&mut self, engine: &mut toybox::Engine, model: &mut model::Model) {
+		let menu = &mut model.pause_menu;
+
+		match menu.page {
+			PausePage::Main => match menu.selected_pause_item() {
+				Some(PauseItem::Resume) => self.close_menu(engine, model),
+				Some(PauseItem::Settings) => menu.show_page(PausePage::Settings),
+				Some(PauseItem::Quit) => model.global.wants_hard_quit = true,
+				None => {}
+			}
+
+			PausePage::Settings => match menu.selected_setting() {
+				Some(SettingItem::Back) => menu.return_to_main(),
+
+				Some(setting) => model.settings.adjust(setting, 1),
+				None => {}
+			}
+		}
+	}
+
+	fn open_menu(&mut self, engine: &mut toybox::Engine, model: &mut model::Model) {
+		engine.input.enter_context(self.menu_actions.context_id());
+		model.pause_menu.open();
+
+		// Other players keep sailing in networked games, so only the menu is shown
+		if model.global.net_role == model::NetRole::Offline {
+			model.global.pause();
+		}
+	}
+
+	fn close_menu(&mut self, engine: &mut toybox::Engine, model: &mut model::Model) {
+		engine.input.leave_context(self.menu_actions.context_id());
+		model.pause_menu.close();
+		model.global.resume();
+	}
+}
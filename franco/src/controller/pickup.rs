@@ -0,0 +1,38 @@
+use crate::prelude::*;
+
+pub struct PickupController;
+
+impl PickupController {
+	pub fn new(_engine: &mut toybox::Engine) -> PickupController {
+		PickupController
+	}
+
+	pub fn update(&mut self, model: &mut model::Model) {
+		// Only boats on this screen collect pickups - remote players collect their own
+		let split_screen_boat = model.split_screen.as_ref().map(|split| split.player.map_position);
+		let local_boats: Vec<Vec2> = std::iter::once(model.player.map_position)
+			.chain(split_screen_boat)
+			.collect();
+
+		for pickup in model.world.pickups.iter_mut() {
+			pickup.bob_phase = (pickup.bob_phase + PI / 60.0) % TAU;
+
+			if pickup.collected {
+				continue
+			}
+
+			let boat_dist = local_boats.iter()
+				.map(|&boat| (boat - pickup.map_position).length())
+				.fold(f32::INFINITY, f32::min);
+
+			if boat_dist < model::PICKUP_DISCOVER_DISTANCE {
+				pickup.discovered = true;
+			}
+
+			if boat_dist < model::PICKUP_COLLECT_DISTANCE {
+				pickup.collected = true;
+				model.stats.record_pickup();
+			}
+		}
+	}
+}
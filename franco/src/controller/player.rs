@@ -1,5 +1,5 @@
 use crate::prelude::*;
-use model::SailState;
+use model::{Player, SailState, NetRole};
 
 pub struct PlayerController {
 }
@@ -10,41 +10,95 @@ impl PlayerController {
 	}
 
 	pub fn update(&mut self, model: &mut model::Model) {
+		// Wrap player position to within the map with a margin
+		let map_size = model.world.map.size + Vec2::splat(50.0);
 
-		let heading_factor = (1.0 / (1.0 + model.player.speed*0.5)).clamp(0.1, 1.0);
+		let tp_dist = update_boat(&mut model.player, model.ui.wheel_angle(), map_size, &model.tunables);
 
-		let (target_speed, acceleration) = match model.player.sail_state {
-			SailState::Anchored => (0.0, 2.0),
-			SailState::Sailing{speed} => (speed as f32 * 0.5, 1.0),
-		};
+		if let Some(tp_dist) = tp_dist {
+			model.stats.record_map_wrap();
 
-		model.player.speed += (target_speed - model.player.speed).min(0.005) * acceleration;
+			// On clients friends are owned by the host
+			if model.global.net_role != NetRole::Client {
+				teleport_friends(model, model.player.map_position, tp_dist);
+			}
+		}
 
-		model.player.heading += model.ui.wheel.angle/2.0 * heading_factor / 60.0;
+		if let Some(split) = &mut model.split_screen {
+			if let Some(tp_dist) = update_boat(&mut split.player, split.ui.wheel_angle(), map_size, &model.tunables) {
+				let position = split.player.map_position;
+				teleport_friends(model, position, tp_dist);
+			}
+		}
 
-		let map_velocity = Vec2::from_angle(model.player.heading) * model.player.speed / 60.0;
-		model.player.map_position += map_velocity;
+		// Remote boats are simulated by the host, and interpolated from snapshots on clients
+		if model.global.net_role != NetRole::Host {
+			return
+		}
 
+		for idx in 0..model.remote_players.len() {
+			let remote = &mut model.remote_players[idx];
 
-		// Wrap player position to within the map with a margin
-		let map_size = model.world.map.size + Vec2::splat(50.0);
-		let player_pos = &mut model.player.map_position;
-		let prev_player_pos = *player_pos;
+			if let Some(tp_dist) = update_boat(&mut remote.player, remote.wheel_angle, map_size, &model.tunables) {
+				let position = remote.player.map_position;
+				teleport_friends(model, position, tp_dist);
+			}
+		}
+	}
+}
 
-		player_pos.x = (player_pos.x + map_size.x/2.0).rem_euclid(map_size.x) - map_size.x/2.0;
-		player_pos.y = (player_pos.y + map_size.y/2.0).rem_euclid(map_size.y) - map_size.y/2.0;
 
+/// Moves a boat according to its sail state and wheel, returning how far it was teleported if it wrapped around the map
+pub fn update_boat(player: &mut Player, wheel_angle: f32, map_size: Vec2, tunables: &model::Tunables) -> Option<Vec2> {
+	let heading_factor = (1.0 / (1.0 + player.speed*tunables.turn_falloff)).clamp(tunables.min_turn_factor, 1.0);
 
-		// If we've warped, teleport friends
-		let diff = (*player_pos - prev_player_pos) / map_size;
-		let tp_dist = Vec2::new(diff.x.trunc() * map_size.x, diff.y.trunc() * map_size.y);
+	let (target_speed, acceleration) = match player.sail_state {
+		SailState::Anchored => (0.0, 2.0),
+		SailState::Sailing{speed} => (speed as f32 * tunables.speed_per_sail, 1.0),
+	};
 
-		if tp_dist.length() > 0.0 {
-			for friend in model.world.friends.iter_mut() {
-				if friend.met_player {
-					friend.map_position += tp_dist;
-				}
-			}
+	player.speed += (target_speed - player.speed).min(0.005) * acceleration;
+
+	player.heading += wheel_angle/2.0 * heading_factor / 60.0;
+
+	let map_velocity = Vec2::from_angle(player.heading) * player.speed / 60.0;
+	player.map_position += map_velocity;
+
+
+	let player_pos = &mut player.map_position;
+	let prev_player_pos = *player_pos;
+
+	player_pos.x = (player_pos.x + map_size.x/2.0).rem_euclid(map_size.x) - map_size.x/2.0;
+	player_pos.y = (player_pos.y + map_size.y/2.0).rem_euclid(map_size.y) - map_size.y/2.0;
+
+	let diff = (*player_pos - prev_player_pos) / map_size;
+	let tp_dist = Vec2::new(diff.x.trunc() * map_size.x, diff.y.trunc() * map_size.y);
+
+	(tp_dist.length() > 0.0).then(|| tp_dist)
+}
+
+
+/// When a boat warps, the met friends following it warp with it. With several boats around,
+/// friends go with whichever boat they were closest to before the warp.
+fn teleport_friends(model: &mut model::Model, boat_position: Vec2, tp_dist: Vec2) {
+	let prev_boat_position = boat_position - tp_dist;
+
+	let boat_positions: Vec<Vec2> = model.boats()
+		.map(|boat| boat.map_position)
+		.collect();
+
+	for friend in model.world.friends.iter_mut() {
+		if !friend.follows_player() {
+			continue
+		}
+
+		let dist_to_boat = (friend.map_position - prev_boat_position).length();
+		let closer_to_another_boat = boat_positions.iter()
+			.filter(|&&pos| (pos - boat_position).length() > 0.001)
+			.any(|&pos| (friend.map_position - pos).length() < dist_to_boat);
+
+		if !closer_to_another_boat {
+			friend.map_position += tp_dist;
 		}
 	}
-}
\ No newline at end of file
+}
@@ -0,0 +1,372 @@
+use crate::prelude::*;
+use model::{HarbourState, PortPanel, PortRow, SailState, SettingItem};
+
+
+// Each player's panel is driven by that player's own sailing keys, which are free while their boat is moored
+toybox::declare_input_context! {
+	struct PortPanelActions "Port Panel" {
+		priority [25]
+
+		trigger confirm { "Confirm" [Scancode::Return] }
+
+		trigger up { "Up" [Scancode::W] }
+		trigger down { "Down" [Scancode::S] }
+		trigger left { "Previous" [Scancode::A] }
+		trigger right { "Next" [Scancode::D] }
+	}
+}
+
+// Also drives the first player's panel when there's no second player, so the arrow keys work when playing alone
+toybox::declare_input_context! {
+	struct SplitScreenPortPanelActions "Split Screen Port Panel" {
+		priority [25]
+
+		trigger confirm { "Confirm" [Scancode::RShift] }
+
+		trigger up { "Up" [Scancode::Up] }
+		trigger down { "Down" [Scancode::Down] }
+		trigger left { "Previous" [Scancode::Left] }
+		trigger right { "Next" [Scancode::Right] }
+	}
+}
+
+// Either player's panel can be clicked, as long as it's open
+toybox::declare_input_context! {
+	struct PortPanelMouseActions "Port Panel Mouse" {
+		priority [25]
+
+		trigger click { "Interact" [MouseButton::Left] }
+		pointer mouse { "Mouse" }
+	}
+}
+
+
+#[derive(Copy, Clone, Debug)]
+enum PanelKey {
+	Up,
+	Down,
+	Left,
+	Right,
+	Confirm,
+}
+
+
+pub struct PortController {
+	actions: PortPanelActions,
+	split_screen_actions: SplitScreenPortPanelActions,
+	mouse_actions: PortPanelMouseActions,
+}
+
+impl PortController {
+	pub fn new(engine: &mut toybox::Engine) -> PortController {
+		PortController {
+			actions: PortPanelActions::new(&mut engine.input),
+			split_screen_actions: SplitScreenPortPanelActions::new(&mut engine.input),
+			mouse_actions: PortPanelMouseActions::new(&mut engine.input),
+		}
+	}
+
+	pub fn update(&mut self, engine: &mut toybox::Engine, model: &mut model::Model) {
+		// Races shouldn't be interrupted by harbours
+		if model.global.game_mode != model::GameMode::FriendFinding {
+			return
+		}
+
+		let can_save = model.global.net_role != model::NetRole::Client;
+
+		update_harbour(&mut model.player, &mut model.ui.port_panel, &model.world, can_save);
+
+		if let Some(split) = &mut model.split_screen {
+			update_harbour(&mut split.player, &mut split.ui.port_panel, &model.world, can_save);
+		}
+
+		let first_panel_open = model.ui.port_panel.state.is_open();
+		let split_panel_open = model.split_screen.as_ref()
+			.map_or(false, |split| split.ui.port_panel.state.is_open());
+
+		// Which player's panel the arrow keys drive, if any
+		let arrow_keys_target = match (model.split_screen.is_some(), first_panel_open, split_panel_open) {
+			(true, _, true) => Some(true),
+			(false, true, _) => Some(false),
+			_ => None,
+		};
+
+		set_context_active(engine, self.actions.context_id(), first_panel_open);
+		set_context_active(engine, self.split_screen_actions.context_id(), arrow_keys_target.is_some());
+		set_context_active(engine, self.mouse_actions.context_id(), first_panel_open || split_panel_open);
+
+		let input = engine.input.frame_state();
+
+		let first_key = match first_panel_open {
+			true => pressed_key(input, [
+				(self.actions.up, PanelKey::Up),
+				(self.actions.down, PanelKey::Down),
+				(self.actions.left, PanelKey::Left),
+				(self.actions.right, PanelKey::Right),
+				(self.actions.confirm, PanelKey::Confirm),
+			]),
+
+			false => None,
+		};
+
+		let arrow_key = pressed_key(input, [
+			(self.split_screen_actions.up, PanelKey::Up),
+			(self.split_screen_actions.down, PanelKey::Down),
+			(self.split_screen_actions.left, PanelKey::Left),
+			(self.split_screen_actions.right, PanelKey::Right),
+			(self.split_screen_actions.confirm, PanelKey::Confirm),
+		]);
+
+		if let Some(key) = first_key {
+			press_panel_key(model, false, key);
+		}
+
+		if let (Some(split), Some(key)) = (arrow_keys_target, arrow_key) {
+			press_panel_key(model, split, key);
+		}
+
+		if first_panel_open || split_panel_open {
+			self.update_panel_mouse(engine, model);
+		}
+	}
+
+	fn update_panel_mouse(&mut self, engine: &mut toybox::Engine, model: &mut model::Model) {
+		let input = engine.input.frame_state();
+		let actions = &self.mouse_actions;
+
+		let pointer = match input.mouse(actions.mouse) {
+			Some(pointer) => pointer,
+			None => return,
+		};
+
+		let window_aspect = engine.gfx.aspect();
+		let clicked = input.active(actions.click);
+
+		for split in [false, true] {
+			let clicked_row = match local_boat(model, split) {
+				Some((_, ui)) if ui.port_panel.state.is_open() => {
+					let pos = ui.window_to_ui(pointer, window_aspect);
+					let row = ui.port_panel.row_at(pos).filter(|_| ui.contains(pos));
+
+					if let Some(row) = row {
+						ui.port_panel.selected = row;
+					}
+
+					row.filter(|_| clicked)
+				}
+
+				_ => None,
+			};
+
+			if let Some(row) = clicked_row {
+				confirm_row(model, split, row);
+			}
+		}
+	}
+}
+
+
+
+fn set_context_active(engine: &mut toybox::Engine, context: toybox::input::ContextID, active: bool) {
+	let currently_active = engine.input.is_context_active(context);
+
+	match (active, currently_active) {
+		(true, false) => engine.input.enter_context(context),
+		(false, true) => engine.input.leave_context(context),
+		_ => {}
+	}
+}
+
+fn pressed_key(input: &toybox::input::FrameState, keys: [(toybox::input::ActionID, PanelKey); 5]) -> Option<PanelKey> {
+	keys.iter()
+		.find(|&&(action, _)| input.active(action))
+		.map(|&(_, key)| key)
+}
+
+/// Moves the selection of a player's panel, or acts on its selected row
+fn press_panel_key(model: &mut model::Model, split: bool, key: PanelKey) {
+	let selected = match local_boat(model, split) {
+		Some((_, ui)) => {
+			let panel = &mut ui.port_panel;
+
+			match key {
+				PanelKey::Up => panel.select_previous(),
+				PanelKey::Down => panel.select_next(),
+				_ => {}
+			}
+
+			panel.selected
+		}
+
+		None => return,
+	};
+
+	match key {
+		PanelKey::Left => adjust_row(model, split, selected, -1),
+		PanelKey::Right => adjust_row(model, split, selected, 1),
+		PanelKey::Confirm => confirm_row(model, split, selected),
+		PanelKey::Up | PanelKey::Down => {}
+	}
+}
+
+
+
+/// The local boat and its ui - the first player's, or the split screen player's if `split` is set
+fn local_boat(model: &mut model::Model, split: bool) -> Option<(&mut model::Player, &mut model::Ui)> {
+	match split {
+		false => Some((&mut model.player, &mut model.ui)),
+		true => model.split_screen.as_mut().map(|split| (&mut split.player, &mut split.ui)),
+	}
+}
+
+
+/// Takes a slow boat into any harbour it sails into, holds it at its mooring, and lets it go again once it's clear
+fn update_harbour(player: &mut model::Player, panel: &mut PortPanel, world: &model::World, can_save: bool) {
+	let dt = 1.0/60.0;
+
+	match player.harbour {
+		HarbourState::AtSea => {
+			let sailing_slowly = matches!(player.sail_state, SailState::Sailing{..})
+				&& player.speed <= model::PORT_DOCK_MAX_SPEED;
+
+			let port = world.ports.iter()
+				.position(|port| (port.map_position - player.map_position).length() < model::PORT_ZONE_RADIUS);
+
+			if let Some(port) = port.filter(|_| sailing_slowly) {
+				player.harbour = HarbourState::Mooring {
+					port,
+					from_position: player.map_position,
+					from_heading: player.heading,
+					phase: 0.0,
+				};
+			}
+		}
+
+		HarbourState::Mooring{port, from_position, from_heading, phase} => {
+			let phase = (phase + dt / model::PORT_MOORING_TIME).min(1.0);
+			let eased = phase.ease_quad_inout(0.0, 1.0);
+			let mooring = &world.ports[port];
+
+			let heading_diff = (mooring.heading - from_heading + PI).rem_euclid(TAU) - PI;
+
+			player.map_position = from_position + (mooring.map_position - from_position) * eased;
+			player.heading = from_heading + heading_diff * eased;
+
+			player.harbour = match phase >= 1.0 {
+				true => HarbourState::Moored{port},
+				false => HarbourState::Mooring{port, from_position, from_heading, phase},
+			};
+		}
+
+		HarbourState::Moored{port} => {
+			let mooring = &world.ports[port];
+			player.map_position = mooring.map_position;
+			player.heading = mooring.heading;
+
+			if panel.state.is_closed() {
+				panel.open(&world.friends, can_save);
+			}
+		}
+
+		HarbourState::Departing{port} => {
+			if (world.ports[port].map_position - player.map_position).length() > model::PORT_ZONE_RADIUS {
+				player.harbour = HarbourState::AtSea;
+			}
+		}
+	}
+
+	// Whatever the sails are set to, a boat in port stays put
+	if player.harbour.is_in_port() {
+		player.sail_state = SailState::Anchored;
+		player.speed = 0.0;
+	}
+
+	if !matches!(player.harbour, HarbourState::Moored{..}) && panel.state.is_open() {
+		panel.close();
+	}
+
+	panel.state.update();
+	panel.saved_timer = (panel.saved_timer - dt).max(0.0);
+}
+
+
+/// Left and right step through paints, and mark friends to keep or leave behind
+fn adjust_row(model: &mut model::Model, split: bool, row: usize, steps: i32) {
+	let port_row = match local_boat(model, split) {
+		Some((_, ui)) => ui.port_panel.rows.get(row).copied(),
+		None => return,
+	};
+
+	match port_row {
+		Some(PortRow::Paint) if split => {
+			let num_paints = model.settings.num_boat_paints() as i32;
+
+			if let Some((player, _)) = local_boat(model, split) {
+				player.paint = (player.paint as i32 + steps).rem_euclid(num_paints) as u8;
+			}
+		}
+
+		Some(PortRow::Paint) => model.settings.adjust(SettingItem::BoatPaint, steps),
+
+		Some(PortRow::Friend{..}) => {
+			if let Some((_, ui)) = local_boat(model, split) {
+				ui.port_panel.toggle_keep(row);
+			}
+		}
+
+		_ => {}
+	}
+}
+
+
+fn confirm_row(model: &mut model::Model, split: bool, row: usize) {
+	let (port_row, port) = match local_boat(model, split) {
+		Some((player, ui)) => match (ui.port_panel.rows.get(row), player.harbour) {
+			(Some(&port_row), HarbourState::Moored{port}) => (port_row, port),
+			_ => return,
+		},
+
+		None => return,
+	};
+
+	match port_row {
+		PortRow::Save => {
+			let save_game = model::SaveGame::capture(model, port);
+
+			match save_game.save().and_then(|_| model.stats.save()) {
+				Ok(()) => {
+					if let Some((_, ui)) = local_boat(model, split) {
+						ui.port_panel.saved_timer = model::PORT_PANEL_SAVED_TIME;
+					}
+				}
+
+				Err(err) => eprintln!("Failed to save game: {}", err),
+			}
+		}
+
+		PortRow::Paint | PortRow::Friend{..} => adjust_row(model, split, row, 1),
+
+		PortRow::Leave => leave_port(model, split, port),
+	}
+}
+
+
+/// Says goodbye to the friends marked to stay, and pushes the boat back out to sea
+fn leave_port(model: &mut model::Model, split: bool, port: usize) {
+	let departing: Vec<usize> = match local_boat(model, split) {
+		Some((_, ui)) => ui.port_panel.departing_friends().collect(),
+		None => return,
+	};
+
+	for friend in departing {
+		if let Some(friend) = model.world.friends.get_mut(friend) {
+			friend.say_goodbye();
+		}
+	}
+
+	if let Some((player, ui)) = local_boat(model, split) {
+		player.harbour = HarbourState::Departing{port};
+		player.sail_state = SailState::Sailing{speed: 1};
+		ui.port_panel.close();
+	}
+}
@@ -0,0 +1,58 @@
+use crate::prelude::*;
+use model::{GameState, GhostFrame};
+
+pub struct RegattaController;
+
+impl RegattaController {
+	pub fn new(_engine: &mut toybox::Engine) -> RegattaController {
+		RegattaController
+	}
+
+	pub fn update(&mut self, model: &mut model::Model) {
+		let regatta = match &mut model.regatta {
+			Some(regatta) => regatta,
+			None => return,
+		};
+
+		let player = &mut model.player;
+
+		match model.global.game_state {
+			GameState::PreGame(_) | GameState::RaceCountdown(_) => {
+				let start = regatta.start_position();
+				player.map_position = start.map_position;
+				player.heading = start.heading;
+				player.speed = 0.0;
+				player.sail_state = model::SailState::Anchored;
+
+				regatta.reset();
+			}
+
+			GameState::Racing => {
+				regatta.timer += 1.0/60.0;
+				regatta.recording.push(GhostFrame {
+					map_position: player.map_position,
+					heading: player.heading,
+				});
+
+				let reached_checkpoint = regatta.next_checkpoint()
+					.map_or(false, |checkpoint| (checkpoint - player.map_position).length() < model::CHECKPOINT_RADIUS);
+
+				if reached_checkpoint {
+					regatta.checkpoints_passed += 1;
+				}
+
+				if regatta.is_finished() {
+					if regatta.finish_run() {
+						if let Err(err) = regatta.save() {
+							eprintln!("Failed to save regatta best time: {}", err);
+						}
+					}
+
+					model.global.game_state.notify_race_finished(&model.tunables);
+				}
+			}
+
+			_ => {}
+		}
+	}
+}
@@ -0,0 +1,73 @@
+use crate::prelude::*;
+
+
+/// Pushes changes to `model.settings` out to the engine, and saves them once the user is done editing
+pub struct SettingsController {
+	/// What has been applied to the engine so far - None until the first update
+	applied: Option<model::Settings>,
+	saved: Option<model::Settings>,
+}
+
+impl SettingsController {
+	pub fn new(_engine: &mut toybox::Engine) -> SettingsController {
+		SettingsController {
+			applied: None,
+			saved: None,
+		}
+	}
+
+	pub fn update(&mut self, engine: &mut toybox::Engine, model: &mut model::Model) {
+		if self.applied.as_ref() != Some(&model.settings) {
+			self.apply(engine, &model.audio_buses, &model.settings);
+			self.applied = Some(model.settings.clone());
+
+			// Only the first boat's paint is a setting - a split screen boat picks its own in port
+			model.player.paint = model.settings.boat_paint_index();
+		}
+
+		let settings = &model.settings;
+
+		// Settings loaded at startup don't need saving
+		let saved = self.saved.get_or_insert_with(|| settings.clone());
+
+		let editing = model.pause_menu.page == model::PausePage::Settings && model.pause_menu.state.is_open();
+		if !editing && saved != settings {
+			if let Err(err) = settings.save() {
+				eprintln!("Failed to save settings: {}", err);
+			}
+
+			*saved = settings.clone();
+		}
+	}
+
+	fn apply(&self, engine: &mut toybox::Engine, buses: &model::AudioBuses, settings: &model::Settings) {
+		let previous = self.applied.as_ref();
+
+		let bus_gains = [
+			(None, settings.master_volume),
+			(Some(buses.music), settings.music_volume),
+			(Some(buses.sfx), settings.sfx_volume),
+		];
+
+		for (bus_id, gain) in bus_gains {
+			if let Some(bus) = engine.audio.get_bus_mut(bus_id) {
+				bus.set_gain(gain);
+			}
+		}
+
+		engine.input.set_mouse_sensitivity_scale(settings.mouse_sensitivity);
+
+		// Only touch the window when these actually change, since doing so can be slow and may cause flicker
+		if previous.map(|p| p.fullscreen) != Some(settings.fullscreen) {
+			if let Err(err) = toybox::window::set_fullscreen(&mut engine.window, settings.fullscreen) {
+				eprintln!("Failed to set fullscreen: {}", err);
+			}
+		}
+
+		if previous.map(|p| p.vsync) != Some(settings.vsync) {
+			if let Err(err) = toybox::window::set_vsync(&engine.window, settings.vsync) {
+				eprintln!("Failed to set vsync: {}", err);
+			}
+		}
+	}
+}
@@ -0,0 +1,131 @@
+use crate::prelude::*;
+use model::{SonarPing, SonarContact};
+
+/// Pings further away than this are too faint to hear
+const PING_AUDIBLE_DISTANCE: f32 = 60.0;
+
+
+toybox::declare_input_context! {
+	struct SonarActions "Sonar" {
+		trigger ping { "Sonar" [Scancode::Space] }
+		trigger split_screen_ping { "Sonar (Second Player)" [Scancode::RAlt] }
+	}
+}
+
+
+pub struct SonarController {
+	actions: SonarActions,
+	ping_sound: audio::SoundAssetID,
+}
+
+impl SonarController {
+	pub fn new(engine: &mut toybox::Engine) -> SonarController {
+		SonarController {
+			actions: SonarActions::new_active(&mut engine.input),
+			ping_sound: engine.audio.register_buffer(synthesize_ping()),
+		}
+	}
+
+	pub fn update(&mut self, engine: &mut toybox::Engine, model: &mut model::Model) {
+		let dt = 1.0/60.0;
+		let input = engine.input.frame_state();
+
+		if input.active(self.actions.ping) {
+			try_ping(&mut model.player, &mut model.world.sonar_pings);
+		}
+
+		if let Some(split) = &mut model.split_screen {
+			if input.active(self.actions.split_screen_ping) {
+				try_ping(&mut split.player, &mut model.world.sonar_pings);
+			}
+
+			split.player.sonar_cooldown = (split.player.sonar_cooldown - dt).max(0.0);
+		}
+
+		model.player.sonar_cooldown = (model.player.sonar_cooldown - dt).max(0.0);
+
+		for friend in model.world.friends.iter_mut() {
+			if let Some(contact) = &mut friend.sonar_contact {
+				contact.reveal_timer = (contact.reveal_timer - dt).max(0.0);
+			}
+		}
+
+		let mut hits = Vec::new();
+
+		for ping in model.world.sonar_pings.iter_mut() {
+			let prev_radius = ping.radius;
+			ping.radius += model::SONAR_RING_SPEED * dt;
+
+			// Friends are hit as the ring passes over them
+			for friend in model.world.friends.iter_mut().filter(|friend| !friend.met_player()) {
+				let dist = (friend.map_position - ping.origin).length();
+
+				if dist > prev_radius && dist <= ping.radius {
+					friend.sonar_contact = Some(SonarContact {
+						map_position: friend.map_position,
+						reveal_timer: model::SONAR_REVEAL_TIME,
+					});
+
+					hits.push(friend.map_position);
+				}
+			}
+		}
+
+		model.world.sonar_pings.retain(|ping| !ping.is_finished());
+
+		for hit_position in hits {
+			self.play_ping(engine, model, hit_position);
+		}
+	}
+
+	/// Plays the ping sound panned and attenuated according to where the hit is relative to the first player's camera
+	fn play_ping(&self, engine: &mut toybox::Engine, model: &model::Model, hit_position: Vec2) {
+		let diff_map = hit_position - model.player.map_position;
+		let distance = diff_map.length();
+
+		if distance > PING_AUDIBLE_DISTANCE {
+			return
+		}
+
+		let camera_right = Quat::from_yaw(model.camera.yaw).right();
+		let diff_world = model::map_to_world(diff_map).to_x0z();
+
+		let pan = match distance > 0.001 {
+			true => diff_world.normalize().dot(camera_right),
+			false => 0.0,
+		};
+
+		let gain = 1.0 - distance / PING_AUDIBLE_DISTANCE;
+
+		let instance = engine.audio.start_sound(model.audio_buses.sfx, self.ping_sound);
+		engine.audio.set_gain_pan(instance, gain, pan);
+	}
+}
+
+
+fn try_ping(player: &mut model::Player, pings: &mut Vec<SonarPing>) {
+	if player.sonar_cooldown > 0.0 {
+		return
+	}
+
+	player.sonar_cooldown = model::SONAR_COOLDOWN;
+	pings.push(SonarPing::new(player.map_position));
+}
+
+
+/// A short falling sine blip
+fn synthesize_ping() -> audio::Buffer {
+	let sample_rate = 44100.0;
+	let duration = 0.6;
+	let num_samples = (sample_rate * duration) as usize;
+
+	let samples = (0..num_samples)
+		.map(move |idx| {
+			let t = idx as f32 / sample_rate;
+			let frequency = 1400.0 - t * 400.0;
+			let envelope = (1.0 - t / duration).powi(3) * (t * 200.0).min(1.0);
+			(t * frequency * TAU).sin() * envelope * 0.5
+		});
+
+	audio::Buffer::from_mono_samples(samples)
+}
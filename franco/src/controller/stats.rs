@@ -0,0 +1,40 @@
+use crate::prelude::*;
+use model::GameState;
+
+pub struct StatsController {
+	saved_post_game: bool,
+}
+
+impl StatsController {
+	pub fn new(_engine: &mut toybox::Engine) -> StatsController {
+		StatsController {
+			saved_post_game: false,
+		}
+	}
+
+	pub fn update(&mut self, model: &mut model::Model) {
+		let dt = 1.0/60.0;
+
+		match model.global.game_state {
+			GameState::PostGame => {
+				if !self.saved_post_game {
+					self.saved_post_game = true;
+
+					if let Err(err) = model.stats.save() {
+						eprintln!("Failed to save stats: {}", err);
+					}
+				}
+
+				return
+			}
+
+			GameState::PreGame(_) | GameState::Paused => return,
+
+			_ => {}
+		}
+
+		let speed = model.player.speed;
+		model.stats.record_travel(speed * dt, speed, dt);
+		model.stats.update_achievements();
+	}
+}
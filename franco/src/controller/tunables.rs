@@ -0,0 +1,55 @@
+use crate::prelude::*;
+use std::time::SystemTime;
+
+/// Frames between checks of the tunables file
+const POLL_INTERVAL: u32 = 60;
+
+
+/// Watches the tunables file, and swaps in new values whenever it's saved
+pub struct TunablesController {
+	/// The modification time of the file when it was last loaded - None if it couldn't be read
+	loaded_modified_time: Option<SystemTime>,
+	poll_timer: u32,
+}
+
+impl TunablesController {
+	pub fn new(_engine: &mut toybox::Engine) -> TunablesController {
+		TunablesController {
+			loaded_modified_time: modified_time(),
+			poll_timer: 0,
+		}
+	}
+
+	pub fn update(&mut self, model: &mut model::Model) {
+		self.poll_timer += 1;
+		if self.poll_timer < POLL_INTERVAL {
+			return
+		}
+
+		self.poll_timer = 0;
+
+		let modified_time = modified_time();
+		if modified_time == self.loaded_modified_time {
+			return
+		}
+
+		self.loaded_modified_time = modified_time;
+
+		// Keep playing with the previous values if the file is mid save or otherwise unreadable
+		match model::Tunables::load() {
+			Ok(tunables) => {
+				model.tunables = tunables;
+				println!("Reloaded {}", model::TUNABLES_PATH);
+			}
+
+			Err(err) => eprintln!("Failed to reload {}: {}", model::TUNABLES_PATH, err),
+		}
+	}
+}
+
+
+fn modified_time() -> Option<SystemTime> {
+	std::fs::metadata(model::TUNABLES_PATH)
+		.and_then(|metadata| metadata.modified())
+		.ok()
+}
@@ -1,7 +1,6 @@
 use crate::prelude::*;
 
 
-const BUTTON_INTERACT_DIST: f32 = 1.2;
 
 
 toybox::declare_input_context! {
@@ -17,6 +16,28 @@ toybox::declare_input_context! {
 
 		trigger zoom_out { "Zoom Out" [Scancode::Minus] }
 		trigger zoom_in { "Zoom In" [Scancode::Equals] }
+
+		trigger toggle_journal { "Journal" [Scancode::J] }
+
+		trigger focus_next { "Next Button" [Scancode::Tab] }
+		trigger activate { "Press Button" [Scancode::Return] }
+	}
+}
+
+// Keyboard only controls for the second player while split screen is active
+toybox::declare_input_context! {
+	struct SplitScreenUiActions "Split Screen Ui" {
+		state wheel_left { "Wheel Left" [Scancode::Left] }
+		state wheel_right { "Wheel Right" [Scancode::Right] }
+
+		trigger increase_speed { "Faster" [Scancode::Up] }
+		trigger decrease_speed { "Slower" [Scancode::Down] }
+
+		trigger toggle_map { "Map" [Scancode::RShift] }
+		trigger toggle_journal { "Journal" [Scancode::RCtrl] }
+
+		trigger zoom_out { "Zoom Out" [Scancode::PageDown] }
+		trigger zoom_in { "Zoom In" [Scancode::PageUp] }
 	}
 }
 
@@ -29,6 +50,7 @@ toybox::declare_input_context! {
 
 pub struct UiController {
 	actions: UiActions,
+	split_screen_actions: SplitScreenUiActions,
 	wheel_actions: WheelActions,
 	dragging_wheel: bool,
 }
@@ -37,173 +59,244 @@ impl UiController {
 	pub fn new(engine: &mut toybox::Engine) -> UiController {
 		UiController {
 			actions: UiActions::new_active(&mut engine.input),
+			split_screen_actions: SplitScreenUiActions::new(&mut engine.input),
 			wheel_actions: WheelActions::new(&mut engine.input),
 			dragging_wheel: false,
 		}
 	}
 
 	pub fn update(&mut self, engine: &mut toybox::Engine, model: &mut model::Model) {
-		model.ui.aspect = engine.gfx.aspect();
+		self.update_viewports(engine, model);
 
 		let input = engine.input.frame_state();
+		let window_aspect = engine.gfx.aspect();
+		let metrics = model.ui.metrics;
+		let mouse_pos = input.mouse(self.actions.mouse)
+			.map(|pos| model.ui.window_to_ui(pos, window_aspect));
 
 		if input.left(self.actions.left_mouse) {
-			self.process_mouse_up(engine, model);
+			model.ui.widgets.pointer_up(mouse_pos, metrics);
 
 		} else if self.dragging_wheel {
 			if let Some(mouse_delta) = input.mouse(self.wheel_actions.mouse) {
-				self.process_drag_wheel(model, mouse_delta);
+				model.ui.widgets.pointer_dragged(mouse_delta);
 			}
-		} else if input.active(self.actions.wheel_left) {
-			model.ui.wheel.angle += (PI - model.ui.wheel.angle) / 20.0;
-		} else if input.active(self.actions.wheel_right) {
-			model.ui.wheel.angle += (-PI - model.ui.wheel.angle) / 20.0;
-		} else {
-			// Slowly shift wheel back to zero
-			let angle = &mut model.ui.wheel.angle;
-			*angle -= *angle / angle.abs().max(1.0) / 60.0;
+		} else if let Some(angle) = model.ui.hud.wheel_angle_mut(&mut model.ui.widgets) {
+			let steer_left = input.active(self.actions.wheel_left);
+			let steer_right = input.active(self.actions.wheel_right);
+			steer_wheel(angle, steer_left, steer_right);
 		}
 
-		let input = engine.input.frame_state();
-		
-		if input.active(self.actions.increase_speed) {
-			self.on_sail_click(model);
+		if input.active(self.actions.focus_next) {
+			model.ui.widgets.focus_next();
+		} else if input.active(self.actions.activate) {
+			model.ui.widgets.activate_focused();
+		}
+
+		let key_action = if input.active(self.actions.increase_speed) {
+			Some(model::HudAction::RaiseSail)
 		} else if input.active(self.actions.decrease_speed) {
-			self.on_anchor_click(model);
+			Some(model::HudAction::LowerSail)
 		} else if input.active(self.actions.zoom_out) {
-			self.on_zoom_out_click(model);
+			Some(model::HudAction::ZoomOut)
 		} else if input.active(self.actions.zoom_in) {
-			self.on_zoom_in_click(model);
+			Some(model::HudAction::ZoomIn)
+		} else if input.active(self.actions.toggle_journal) {
+			Some(model::HudAction::ToggleJournal)
+		} else {
+			None
+		};
+
+		if let Some(action) = key_action {
+			perform_hud_action(action, &mut model.player, &mut model.camera, &mut model.ui, &model.tunables);
 		}
 
-		if let Some(mouse_pos) = input.mouse(self.actions.mouse) {
-			let mouse_pos = mouse_pos * model::UI_SAFE_REGION;
-			
+		if let Some(mouse_pos) = mouse_pos {
 			if input.entered(self.actions.left_mouse) {
-				self.process_mouse_down(engine, model, mouse_pos);
+				// Clicks in the other player's half of the screen aren't ours to handle
+				if model.ui.contains(mouse_pos) {
+					model.ui.widgets.pointer_down(mouse_pos, metrics);
+				}
 			} else {
-				self.process_hover(model, mouse_pos);
+				model.ui.widgets.pointer_moved(mouse_pos, metrics);
 			}
 		}
 
-		model.ui.zoom_in_button.state.update();
-		model.ui.zoom_out_button.state.update();
-		model.ui.map_button.state.update();
-		model.ui.sail_button.state.update();
-		model.ui.anchor_button.state.update();
-
-		model.ui.map.state.update();
-		model.ui.wheel.state.update();
-	}
-
-	pub fn process_mouse_down(&mut self, engine: &mut toybox::Engine, model: &mut model::Model, mouse_pos: Vec2) {
-		type Callback = fn(&mut UiController, &mut model::Model);
-
-		let buttons = [
-			(model.ui.map_button.position, UiController::on_map_click as Callback),
-			(model.ui.sail_button.position, UiController::on_sail_click as Callback),
-			(model.ui.anchor_button.position, UiController::on_anchor_click as Callback),
+		// The wheel is turned by relative mouse motion, so it needs its own context while held
+		let dragging_wheel = model.ui.widgets.captured() == Some(model.ui.hud.wheel);
+		if dragging_wheel != self.dragging_wheel {
+			match dragging_wheel {
+				true => engine.input.enter_context(self.wheel_actions.context_id()),
+				false => engine.input.leave_context(self.wheel_actions.context_id()),
+			}
 
-			(model.ui.zoom_in_button.position, UiController::on_zoom_in_click as Callback),
-			(model.ui.zoom_out_button.position, UiController::on_zoom_out_click as Callback),
-		];
+			self.dragging_wheel = dragging_wheel;
+		}
 
-		for (button_position, action) in buttons {
-			if button_position.distance_to(mouse_pos, model.ui.aspect) < BUTTON_INTERACT_DIST {
-				action(self, model);
-				return;
+		for event in model.ui.widgets.drain_events() {
+			if let model::WidgetEvent::Clicked(id) = event {
+				if let Some(action) = model.ui.hud.action(id) {
+					perform_hud_action(action, &mut model.player, &mut model.camera, &mut model.ui, &model.tunables);
+				}
 			}
 		}
 
+		update_widgets(&mut model.ui, &model.tunables);
 
-		let wheel_pos = model.ui.wheel.position();
-		if wheel_pos.distance_to(mouse_pos, model.ui.aspect) < 4.0 {
-			self.dragging_wheel = true;
-			engine.input.enter_context(self.wheel_actions.context_id());
-			return;
+		if model.split_screen.is_some() {
+			self.update_split_screen(engine, model);
 		}
 
-		model.ui.dragging_unclaimed_area = true;
-	}
+		self.update_toast(model);
 
-	pub fn process_drag_wheel(&mut self, model: &mut model::Model, mouse_delta: Vec2) {
-		model.ui.wheel.angle += -mouse_delta.x;
-		model.ui.wheel.angle = model.ui.wheel.angle.clamp(-PI, PI);
-	}
+		model.ui.trust_meter.track(&model.world.friends, model.player.map_position);
+		model.ui.pickup_counter.track(&model.world);
+
+		// Gauges make way for the port panel, and for the end of the voyage
+		let instruments_visible = !model.global.game_state.has_ended() && model.ui.port_panel.state.is_closed();
+		model.ui.instruments.track(&model.player, &model.tunables, instruments_visible);
 
-	pub fn process_mouse_up(&mut self, engine: &mut toybox::Engine, model: &mut model::Model) {
-		if self.dragging_wheel {
-			engine.input.leave_context(self.wheel_actions.context_id());
+		if matches!(model.global.game_state, model::GameState::PostGame) {
+			model.ui.stats_screen.state.open(1.0);
 		}
 
-		self.dragging_wheel = false;
-		model.ui.dragging_unclaimed_area = false;
+		model.ui.stats_screen.state.update();
 	}
 
-	pub fn process_hover(&mut self, model: &mut model::Model, mouse_pos: Vec2) {
-		let wheel_pos = model.ui.wheel.position();
-		let wheel_state = &mut model.ui.wheel.state;
+	fn update_viewports(&mut self, engine: &mut toybox::Engine, model: &mut model::Model) {
+		let backbuffer_size = engine.gfx.backbuffer_size();
+		let ui_scale = model.settings.ui_scale;
+		let num_viewports = match model.split_screen {
+			Some(_) => 2,
+			None => 1,
+		};
 
-		if wheel_pos.distance_to(mouse_pos, model.ui.aspect) < 4.0 {
-			wheel_state.open(0.2);
-		} else {
-			wheel_state.close(1.0);
-		}
+		let dpi = toybox::window::display_dpi(&engine.window);
 
-		let buttons = [
-			&mut model.ui.map_button,
-			&mut model.ui.sail_button,
-			&mut model.ui.anchor_button,
-			&mut model.ui.zoom_in_button,
-			&mut model.ui.zoom_out_button,
-		];
-
-		for button in buttons {
-			if button.position.distance_to(mouse_pos, model.ui.aspect) < BUTTON_INTERACT_DIST {
-				button.state.open(0.1);
-			} else {
-				button.state.close(0.1);
+		model.ui.viewport = gfx::Viewport::column(0, num_viewports);
+		model.ui.metrics = model::UiMetrics::for_viewport(model.ui.viewport.to_pixels(backbuffer_size).1, dpi, ui_scale);
+		update_layout(&mut model.ui);
+
+		let split_screen_active = engine.input.is_context_active(self.split_screen_actions.context_id());
+
+		if let Some(split) = &mut model.split_screen {
+			split.ui.viewport = gfx::Viewport::column(1, num_viewports);
+			split.ui.metrics = model::UiMetrics::for_viewport(split.ui.viewport.to_pixels(backbuffer_size).1, dpi, ui_scale);
+			update_layout(&mut split.ui);
+
+			if !split_screen_active {
+				engine.input.enter_context(self.split_screen_actions.context_id());
 			}
+		} else if split_screen_active {
+			engine.input.leave_context(self.split_screen_actions.context_id());
 		}
 	}
 
+	fn update_split_screen(&mut self, engine: &mut toybox::Engine, model: &mut model::Model) {
+		let input = engine.input.frame_state();
+		let actions = &self.split_screen_actions;
+		let split = model.split_screen.as_mut().unwrap();
+		let ui = &mut split.ui;
+
+		let steer_left = input.active(actions.wheel_left);
+		let steer_right = input.active(actions.wheel_right);
+
+		if let Some(angle) = ui.hud.wheel_angle_mut(&mut ui.widgets) {
+			steer_wheel(angle, steer_left, steer_right);
+		}
+
+		// There's no pointer to hover with, so show the wheel while it's being turned
+		if steer_left || steer_right {
+			ui.widgets.pulse(ui.hud.wheel);
+		}
 
-	pub fn on_map_click(&mut self, model: &mut model::Model) {
-		let map_state = &mut model.ui.map.state;
-		if map_state.is_open() {
-			map_state.close(0.3);
+		let key_action = if input.active(actions.increase_speed) {
+			Some(model::HudAction::RaiseSail)
+		} else if input.active(actions.decrease_speed) {
+			Some(model::HudAction::LowerSail)
+		} else if input.active(actions.zoom_out) {
+			Some(model::HudAction::ZoomOut)
+		} else if input.active(actions.zoom_in) {
+			Some(model::HudAction::ZoomIn)
+		} else if input.active(actions.toggle_map) {
+			Some(model::HudAction::ToggleMap)
+		} else if input.active(actions.toggle_journal) {
+			Some(model::HudAction::ToggleJournal)
 		} else {
-			map_state.open(0.4);
+			None
+		};
+
+		// Pop the button for the key that was pressed, then let it settle back
+		if let Some(action) = key_action {
+			perform_hud_action(action, &mut split.player, &mut split.camera, ui, &model.tunables);
+			ui.widgets.pulse(ui.hud.button(action));
 		}
+
+		update_widgets(ui, &model.tunables);
+
+		ui.trust_meter.track(&model.world.friends, split.player.map_position);
+		ui.pickup_counter.track(&model.world);
+
+		let instruments_visible = !model.global.game_state.has_ended() && ui.port_panel.state.is_closed();
+		ui.instruments.track(&split.player, &model.tunables, instruments_visible);
 	}
 
-	pub fn on_anchor_click(&mut self, model: &mut model::Model) {
-		use model::SailState;
+	fn update_toast(&mut self, model: &mut model::Model) {
+		let toast = &mut model.ui.toast;
 
-		model.player.sail_state = match model.player.sail_state {
-			SailState::Anchored => return,
-			SailState::Sailing{speed: 1} => SailState::Anchored,
-			SailState::Sailing{speed} => SailState::Sailing{speed: speed-1},
+		if toast.state.is_closed() {
+			if matches!(toast.state, model::UiPanelState::Closed) && !model.stats.pending_unlocks.is_empty() {
+				toast.achievement = Some(model.stats.pending_unlocks.remove(0));
+				toast.timer = model::TOAST_DISPLAY_TIME;
+				toast.state.open(0.5);
+			}
+		} else {
+			toast.timer -= 1.0/60.0;
+
+			if toast.timer < 0.0 {
+				toast.state.close(0.5);
+			}
 		}
+
+		toast.state.update();
 	}
+}
 
-	pub fn on_sail_click(&mut self, model: &mut model::Model) {
-		use model::SailState;
 
-		model.player.sail_state = match model.player.sail_state {
-			SailState::Anchored => SailState::Sailing{speed: 1},
-			SailState::Sailing{speed: model::MAX_SAIL_SPEED} => return,
-			SailState::Sailing{speed} => SailState::Sailing{speed: speed+1},
-		}
+
+fn perform_hud_action(action: model::HudAction, player: &mut model::Player, camera: &mut model::Camera, ui: &mut model::Ui, tunables: &model::Tunables) {
+	match action {
+		model::HudAction::ToggleMap => ui.toggle_map(),
+		model::HudAction::ToggleJournal => ui.toggle_journal(),
+		model::HudAction::RaiseSail => player.raise_sail(tunables.max_sail_speed),
+		model::HudAction::LowerSail => player.lower_sail(),
+		model::HudAction::ZoomIn => camera.zoom_in(tunables),
+		model::HudAction::ZoomOut => camera.zoom_out(tunables),
 	}
+}
 
-	pub fn on_zoom_in_click(&mut self, model: &mut model::Model) {
-		model.camera.orbit_zoom /= 1.2;
-		model.camera.orbit_zoom = model.camera.orbit_zoom.max(2.0);
+/// Only does anything when the viewport has been resized or rescaled
+fn update_layout(ui: &mut model::Ui) {
+	if ui.layout.compute(ui.metrics) {
+		ui.hud.apply_layout(&ui.layout, &mut ui.widgets);
 	}
+}
+
+fn update_widgets(ui: &mut model::Ui, tunables: &model::Tunables) {
+	ui.hud.update_shapes(&mut ui.widgets, tunables);
+	ui.widgets.update();
+	ui.hud.update_wheel(&mut ui.widgets);
 
-	pub fn on_zoom_out_click(&mut self, model: &mut model::Model) {
-		model.camera.orbit_zoom *= 1.2;
-		model.camera.orbit_zoom = model.camera.orbit_zoom.min(100.0);
+	ui.journal.state.update();
+}
+
+fn steer_wheel(angle: &mut f32, left: bool, right: bool) {
+	if left {
+		*angle += (PI - *angle) / 20.0;
+	} else if right {
+		*angle += (-PI - *angle) / 20.0;
+	} else {
+		// Slowly shift wheel back to zero
+		*angle -= *angle / angle.abs().max(1.0) / 60.0;
 	}
-}
\ No newline at end of file
+}
@@ -4,6 +4,8 @@ pub mod model;
 pub mod view;
 pub mod shaders;
 pub mod debug;
+pub mod persist;
+pub mod net;
 
 use prelude::*;
 
@@ -26,15 +28,31 @@ fn main() -> Result<()> {
 	let mut camera_ctl = controller::CameraController::new(&mut engine);
 	let mut player_ctl = controller::PlayerController::new(&mut engine);
 	let mut friend_ctl = controller::FriendController::new(&mut engine);
+	let mut pickup_ctl = controller::PickupController::new(&mut engine);
+	let mut regatta_ctl = controller::RegattaController::new(&mut engine);
+	let mut level_ctl = controller::LevelController::new(&mut engine);
+	let mut ambient_ctl = controller::AmbientController::new(&mut engine);
+	let mut sonar_ctl = controller::SonarController::new(&mut engine);
+	let mut port_ctl = controller::PortController::new(&mut engine);
+	let mut hint_ctl = controller::HintController::new(&mut engine);
 	let mut ui_ctl = controller::UiController::new(&mut engine);
+	let mut stats_ctl = controller::StatsController::new(&mut engine);
+	let mut pause_ctl = controller::PauseController::new(&mut engine);
+	let mut settings_ctl = controller::SettingsController::new(&mut engine);
+	let mut tunables_ctl = controller::TunablesController::new(&mut engine);
 
-	let mut model = model::Model::new()?;
+	let net_options = net::NetOptions::from_args(std::env::args().skip(1))?;
+	let game_mode = model::GameMode::from_args(std::env::args().skip(1));
+	let mut model = model::Model::new(&mut engine.audio, game_mode, &net_options)?;
 
-	let mut boat_view = view::BoatView::new(&mut engine.gfx, &model.resources)?;
-	let mut water_view = view::WaterView::new(&mut engine.gfx, &model.resources)?;
-	let mut island_view = view::IslandView::new(&mut engine.gfx, &model.resources)?;
-	let mut friend_view = view::FriendView::new(&mut engine.gfx, &model.resources)?;
-	let mut ui_view = view::UiView::new(&mut engine.gfx, &model.resources)?;
+	let mut net_ctl = controller::NetController::new(&mut engine, net_options)?;
+
+	let font = gfx::FontAtlas::new_default(&mut engine.gfx)?;
+
+	// One set of views per local player, created as players join
+	let mut player_views: Vec<view::PlayerViews> = Vec::new();
+	let mut pause_menu_view = view::PauseMenuView::new(&mut engine.gfx)?;
+	let mut debug_inspector_view = view::DebugInspectorView::new(&mut engine.gfx, &font)?;
 
 	let main_fbo = engine.gfx.new_framebuffer(
 		gfx::FramebufferSettings::new(gfx::TextureSize::Backbuffer)
@@ -59,33 +77,58 @@ fn main() -> Result<()> {
 			break 'main
 		}
 
+		engine.instrumenter.start_section("update");
+
+		net_ctl.receive(&mut model);
+
+		tunables_ctl.update(&mut model);
 		debug_ctl.update(&mut engine, &mut model);
+		pause_ctl.update(&mut engine, &mut model);
+		settings_ctl.update(&mut engine, &mut model);
 		global_ctl.update(&mut engine, &mut model);
-		camera_ctl.update(&mut engine, &mut model);
-		player_ctl.update(&mut model);
-		friend_ctl.update(&mut model);
-		ui_ctl.update(&mut engine, &mut model);
 
-		boat_view.update(&model);
-		water_view.update(&model);
-		island_view.update(&model);
-		friend_view.update(&model);
-		ui_view.update(&model);
- 
+		if !model.global.is_paused() {
+			camera_ctl.update(&mut engine, &mut model);
+			player_ctl.update(&mut model);
+			friend_ctl.update(&mut model);
+			pickup_ctl.update(&mut model);
+			regatta_ctl.update(&mut model);
+			level_ctl.update(&mut model);
+			ambient_ctl.update(&mut model);
+			sonar_ctl.update(&mut engine, &mut model);
+			port_ctl.update(&mut engine, &mut model);
+			hint_ctl.update(&mut model);
+		}
 
-		let camera_uniforms = build_camera_uniforms(&model.camera, engine.gfx.aspect());
-		main_camera_ubo.upload(&[camera_uniforms]);
+		// The pause menu takes over input while it's open, even when the game isn't frozen
+		if !model.pause_menu.state.is_open() {
+			ui_ctl.update(&mut engine, &mut model);
+		}
 
-		let camera_uniforms = build_ui_camera_uniforms(engine.gfx.aspect());
-		ui_camera_ubo.upload(&[camera_uniforms]);
+		stats_ctl.update(&mut model);
 
-		let world_uniforms = build_world_uniforms(&model);
-		main_world_ubo.upload(&[world_uniforms]);
+		net_ctl.send(&model);
 
+		let viewers = model.viewers();
+
+		while player_views.len() < viewers.len() {
+			player_views.push(view::PlayerViews::new(&mut engine.gfx, &model.resources, &font)?);
+		}
+
+		player_views.truncate(viewers.len());
+
+		for (views, viewer) in player_views.iter_mut().zip(&viewers) {
+			views.update(&model, viewer);
+		}
 
+		pause_menu_view.update(&model);
+		debug_inspector_view.update(&mut engine.gfx, &model);
+
+		engine.instrumenter.start_section("draw world");
+
+		let backbuffer_size = engine.gfx.backbuffer_size();
 		let mut view_ctx = view::ViewContext::new(engine.gfx.render_state());
 
-		view_ctx.gfx.bind_uniform_buffer(0, main_camera_ubo);
 		view_ctx.gfx.bind_uniform_buffer(1, main_world_ubo);
 
 		view_ctx.gfx.set_wireframe(model.global.wireframe_enabled);
@@ -94,15 +137,36 @@ fn main() -> Result<()> {
 		view_ctx.gfx.set_clear_color(Color{a: 0.0, ..model.world.sky_color});
 		view_ctx.gfx.clear(gfx::ClearMode::ALL);
 
-		boat_view.draw(&mut view_ctx);
-		island_view.draw(&mut view_ctx);
-		water_view.draw(&mut view_ctx);
-
-		// Draw friends into separate fbo so we can draw them underwater
 		view_ctx.gfx.bind_framebuffer(friend_fbo);
 		view_ctx.gfx.set_clear_color(Color::grey_a(0.0, 0.0));
 		view_ctx.gfx.clear(gfx::ClearMode::ALL);
-		friend_view.draw(&mut view_ctx);
+
+		// Each player's view is drawn into their own region of the same framebuffers, so they can be composited together
+		for (views, viewer) in player_views.iter().zip(&viewers) {
+			let viewport = viewer.ui.viewport;
+
+			let camera_uniforms = build_camera_uniforms(viewer.camera, viewport.aspect(backbuffer_size));
+			main_camera_ubo.upload(&[camera_uniforms]);
+
+			let world_uniforms = build_world_uniforms(&model, viewer);
+			main_world_ubo.upload(&[world_uniforms]);
+
+			view_ctx.gfx.bind_uniform_buffer(0, main_camera_ubo);
+
+			view_ctx.gfx.bind_framebuffer_viewport(main_fbo, viewport);
+			views.boat.draw(&mut view_ctx);
+			views.island.draw(&mut view_ctx);
+			views.pickup.draw(&mut view_ctx);
+			views.regatta.draw(&mut view_ctx);
+			views.ambient.draw(&mut view_ctx);
+			views.water.draw(&mut view_ctx);
+			views.sonar.draw(&mut view_ctx);
+
+			// Draw friends into separate fbo so we can draw them underwater
+			view_ctx.gfx.bind_framebuffer_viewport(friend_fbo, viewport);
+			views.friend.draw(&mut view_ctx);
+			views.ambient.draw_underwater(&mut view_ctx);
+		}
 
 		view_ctx.gfx.bind_framebuffer(None);
 
@@ -128,18 +192,60 @@ fn main() -> Result<()> {
 			view_ctx.gfx.draw_arrays(gfx::DrawMode::Triangles, 6);
 		}
 
-		view_ctx.gfx.set_wireframe(model.global.wireframe_enabled);
+		engine.instrumenter.start_section("draw ui");
+
+		for (views, viewer) in player_views.iter().zip(&viewers) {
+			let viewport = viewer.ui.viewport;
+
+			view_ctx.gfx.bind_framebuffer_viewport(None, viewport);
+			view_ctx.gfx.set_wireframe(model.global.wireframe_enabled);
+
+			let camera_uniforms = build_camera_uniforms(viewer.camera, viewport.aspect(backbuffer_size));
+			main_camera_ubo.upload(&[camera_uniforms]);
+			view_ctx.gfx.bind_uniform_buffer(0, main_camera_ubo);
+
+			debug::draw(&mut view_ctx.gfx);
+
+			view_ctx.gfx.clear(gfx::ClearMode::DEPTH);
+
+			let camera_uniforms = build_ui_camera_uniforms(viewer.ui.metrics);
+			ui_camera_ubo.upload(&[camera_uniforms]);
+			view_ctx.gfx.bind_uniform_buffer(0, ui_camera_ubo);
+
+			views.ui.draw(&mut view_ctx);
+		}
+
+		// The pause menu covers the whole window
+		{
+			view_ctx.gfx.bind_framebuffer(None);
+			view_ctx.gfx.set_wireframe(false);
+			view_ctx.gfx.clear(gfx::ClearMode::DEPTH);
+
+			let camera_uniforms = build_ui_camera_uniforms(model.pause_menu.metrics);
+			ui_camera_ubo.upload(&[camera_uniforms]);
+			view_ctx.gfx.bind_uniform_buffer(0, ui_camera_ubo);
+
+			pause_menu_view.draw(&mut view_ctx);
+		}
 
-		debug::draw(&mut view_ctx.gfx);
+		// The debug inspector goes over everything, pause menu included
+		if model.debug_inspector.visible {
+			view_ctx.gfx.clear(gfx::ClearMode::DEPTH);
 
-		view_ctx.gfx.clear(gfx::ClearMode::DEPTH);
-		view_ctx.gfx.bind_uniform_buffer(0, ui_camera_ubo);
+			let camera_uniforms = build_ui_camera_uniforms(model.debug_inspector.metrics);
+			ui_camera_ubo.upload(&[camera_uniforms]);
+			view_ctx.gfx.bind_uniform_buffer(0, ui_camera_ubo);
 
-		ui_view.draw(&mut view_ctx);
+			debug_inspector_view.draw(&mut view_ctx);
+		}
 
 		engine.end_frame();
 	}
 
+	net_ctl.shutdown();
+	model.stats.save()?;
+	model.settings.save()?;
+
 	Ok(())
 }
 
@@ -168,10 +274,10 @@ fn build_camera_uniforms(camera: &model::Camera, aspect: f32) -> CameraUniforms
 }
 
 
-fn build_ui_camera_uniforms(aspect: f32) -> CameraUniforms {
+fn build_ui_camera_uniforms(metrics: model::UiMetrics) -> CameraUniforms {
 	CameraUniforms {
 		projection_view: {
-			Mat4::ortho_aspect(model::UI_SAFE_REGION, aspect, -10.0, 10.0)
+			Mat4::ortho_aspect(metrics.safe_region(), metrics.aspect, -10.0, 10.0)
 			// Mat4::perspective(PI/3.0, aspect, 1.0, 20.0)
 			// 	* Mat4::scale_translate(Vec3::splat(0.5 / model::UI_SAFE_REGION), Vec3::from_z(-1.0))
 		}
@@ -188,17 +294,30 @@ struct WorldUniforms {
 	player_position: Vec2,
 	fog_start: f32,
 	fog_distance: f32,
-	// _pad: [f32; 2],
+	color_transform: [Vec4; 3],
+	high_contrast: f32,
+	_pad: [f32; 3],
 	// NOTE: align to Vec4s
 }
 
-fn build_world_uniforms(model: &model::Model) -> WorldUniforms {
+fn build_world_uniforms(model: &model::Model, viewer: &model::Viewer<'_>) -> WorldUniforms {
+	let tunables = &model.tunables;
+	let horizon_fade = model.global.game_state.horizon_fade(tunables);
+
+	let color_transform = view::ColorTransform::new(model.settings.color_mode, model.global.simulated_color_blindness);
+	let [row_x, row_y, row_z] = color_transform.matrix.rows;
+
 	WorldUniforms {
 		sky_color: model.world.sky_color,
 		water_obscure_color: Color::hsv(220.0, 0.6, 0.7),
-		player_position: model.player.map_position,
+		player_position: viewer.player.map_position,
+
+		// Fog closes in on the boat as it sails between levels
+		fog_start: horizon_fade.ease_linear(tunables.fog_start, tunables.horizon_fog_start),
+		fog_distance: tunables.fog_distance * (1.0 - horizon_fade) + 1.0,
 
-		fog_start: 80.0,
-		fog_distance: 200.0,
+		color_transform: [row_x.extend(0.0), row_y.extend(0.0), row_z.extend(0.0)],
+		high_contrast: color_transform.high_contrast as u32 as f32,
+		_pad: [0.0; 3],
 	}
 }
\ No newline at end of file
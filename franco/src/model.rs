@@ -18,34 +18,169 @@ pub use player::*;
 pub mod ui;
 pub use ui::*;
 
+pub mod stats;
+pub use stats::*;
+
+pub mod split_screen;
+pub use split_screen::*;
+
+pub mod settings;
+pub use settings::*;
+
+pub mod accessibility;
+pub use accessibility::*;
+
+pub mod audio_buses;
+pub use audio_buses::*;
+
+pub mod regatta;
+pub use regatta::*;
+
+pub mod progression;
+pub use progression::*;
+
+pub mod save_game;
+pub use save_game::*;
+
+pub mod tunables;
+pub use tunables::*;
+
+pub mod debug_inspector;
+pub use debug_inspector::*;
+
 
 pub struct Model {
 	pub resources: Resources,
 	pub global: Global,
+	pub settings: Settings,
+	pub tunables: Tunables,
+	pub audio_buses: AudioBuses,
 
 	pub camera: Camera,
 
 	pub world: World,
+	pub progression: Progression,
 	pub player: Player,
+	pub remote_players: Vec<RemotePlayer>,
+
+	/// Present while a second player is sharing the screen
+	pub split_screen: Option<SplitScreen>,
 
 	pub ui: Ui,
+	pub pause_menu: PauseMenu,
+	pub debug_inspector: DebugInspector,
+
+	pub stats: Stats,
+
+	/// Only present in regatta mode
+	pub regatta: Option<Regatta>,
 }
 
 impl Model {
-	pub fn new() -> Result<Model> {
-		let resources = Resources::new()?;
-		let world = World::new(&resources)?;
+	pub fn new(audio: &mut audio::AudioSystem, game_mode: GameMode, net_options: &net::NetOptions) -> Result<Model> {
+		// Races always start from scratch, so only friend finding resumes from a save point.
+		// Networked sessions all start on the first level, so everyone's world matches the host's.
+		let mut save = match (game_mode, net_options) {
+			(GameMode::FriendFinding, net::NetOptions::Offline) => SaveGame::load()?,
+			_ => None,
+		};
+
+		let start_level = save.as_ref().map_or(0, |save| save.level);
+
+		let resources = match Resources::new(start_level) {
+			Ok(resources) => resources,
+
+			Err(err) if start_level != 0 => {
+				eprintln!("Failed to resume saved game, starting over: {}", err);
+				save = None;
+				Resources::new(0)?
+			}
+
+			Err(err) => return Err(err),
+		};
+
+		let level = save.as_ref().map_or(0, |save| save.level);
+
+		let mut world = World::new(resources.level_scene(level), resources.island_scenes())?;
 		let ui = Ui::new(&resources);
+		let settings = Settings::load(resources.boat_paints())?;
+		let tunables = Tunables::load()?;
+
+		let mut player = Player::new();
+
+		if let Some(save) = &save {
+			if let Some(port) = world.ports.get(save.port) {
+				player.map_position = port.map_position;
+				player.heading = port.heading;
+				player.harbour = HarbourState::Moored{port: save.port};
+			}
+
+			world.restore_friends(&save.friends, player.map_position);
+		}
+
+		let regatta = match game_mode {
+			GameMode::Regatta => {
+				let regatta = Regatta::new(resources.level_scene(level), &world.map)?;
+				let start = regatta.start_position();
+				player.map_position = start.map_position;
+				player.heading = start.heading;
+				Some(regatta)
+			}
+
+			GameMode::FriendFinding => None,
+		};
 
 		Ok(Model {
 			resources,
-			global: Global::new(),
+			global: Global::new(game_mode, &tunables),
+			settings,
+			tunables,
+			audio_buses: AudioBuses::new(audio),
 			camera: Camera::new(),
 
 			world,
-			player: Player::new(),
+			progression: Progression::new(level),
+			player,
+			remote_players: Vec::new(),
+			split_screen: None,
 
 			ui,
+			pause_menu: PauseMenu::new(),
+			debug_inspector: DebugInspector::new(),
+
+			stats: Stats::new()?,
+
+			regatta,
 		})
 	}
-}
\ No newline at end of file
+
+	/// Every boat in the world - local, split screen and remote
+	pub fn boats(&self) -> impl Iterator<Item=&Player> + '_ {
+		let split_screen_boat = self.split_screen.as_ref().map(|split| &split.player);
+		let remote_boats = self.remote_players.iter().map(|remote| &remote.player);
+
+		std::iter::once(&self.player)
+			.chain(split_screen_boat)
+			.chain(remote_boats)
+	}
+
+	/// The players sharing this screen, each drawn into their own viewport
+	pub fn viewers(&self) -> Vec<Viewer<'_>> {
+		let first = Viewer {
+			player: &self.player,
+			camera: &self.camera,
+			ui: &self.ui,
+		};
+
+		let second = self.split_screen.as_ref()
+			.map(|split| Viewer {
+				player: &split.player,
+				camera: &split.camera,
+				ui: &split.ui,
+			});
+
+		std::iter::once(first)
+			.chain(second)
+			.collect()
+	}
+}
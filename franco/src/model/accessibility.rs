@@ -0,0 +1,56 @@
+/// How colours are adjusted as the world is composited, chosen in settings
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ColorMode {
+	Normal,
+
+	/// Shifts colours that are hard to tell apart with a kind of colour blindness towards ones that aren't
+	Corrected(ColorBlindness),
+
+	/// Makes friends stand out from the world around them
+	HighContrast,
+}
+
+impl ColorMode {
+	pub const ALL: [ColorMode; 5] = [
+		ColorMode::Normal,
+		ColorMode::Corrected(ColorBlindness::Protanopia),
+		ColorMode::Corrected(ColorBlindness::Deuteranopia),
+		ColorMode::Corrected(ColorBlindness::Tritanopia),
+		ColorMode::HighContrast,
+	];
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			ColorMode::Normal => "normal",
+			ColorMode::Corrected(ColorBlindness::Protanopia) => "protanopia",
+			ColorMode::Corrected(ColorBlindness::Deuteranopia) => "deuteranopia",
+			ColorMode::Corrected(ColorBlindness::Tritanopia) => "tritanopia",
+			ColorMode::HighContrast => "high_contrast",
+		}
+	}
+
+	pub fn from_name(name: &str) -> Option<ColorMode> {
+		ColorMode::ALL.iter().copied().find(|mode| mode.name() == name)
+	}
+}
+
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ColorBlindness {
+	/// Missing red cones
+	Protanopia,
+
+	/// Missing green cones - the most common
+	Deuteranopia,
+
+	/// Missing blue cones
+	Tritanopia,
+}
+
+impl ColorBlindness {
+	pub const ALL: [ColorBlindness; 3] = [
+		ColorBlindness::Protanopia,
+		ColorBlindness::Deuteranopia,
+		ColorBlindness::Tritanopia,
+	];
+}
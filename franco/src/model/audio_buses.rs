@@ -0,0 +1,18 @@
+use crate::prelude::*;
+
+/// The buses game sounds are played on, so each kind of sound can have its own volume.
+/// Both mix into the master bus.
+#[derive(Debug)]
+pub struct AudioBuses {
+	pub music: audio::BusID,
+	pub sfx: audio::BusID,
+}
+
+impl AudioBuses {
+	pub fn new(audio: &mut audio::AudioSystem) -> AudioBuses {
+		AudioBuses {
+			music: audio.new_bus("Music"),
+			sfx: audio.new_bus("SFX"),
+		}
+	}
+}
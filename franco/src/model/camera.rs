@@ -8,6 +8,9 @@ pub struct Camera {
 	pub yaw: f32,
 
 	pub orbit_zoom: f32,
+
+	/// How far the camera has swung round to the harbour view, from 0 at sea to 1 when moored
+	pub harbour_blend: f32,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
@@ -25,7 +28,17 @@ impl Camera {
 			yaw: 0.0,
 
 			orbit_zoom: 20.0,
+			harbour_blend: 0.0,
 		}
 	}
-}
 
+	pub fn zoom_in(&mut self, tunables: &super::Tunables) {
+		self.orbit_zoom /= tunables.orbit_zoom_step;
+		self.orbit_zoom = self.orbit_zoom.max(tunables.min_orbit_zoom);
+	}
+
+	pub fn zoom_out(&mut self, tunables: &super::Tunables) {
+		self.orbit_zoom *= tunables.orbit_zoom_step;
+		self.orbit_zoom = self.orbit_zoom.min(tunables.max_orbit_zoom);
+	}
+}
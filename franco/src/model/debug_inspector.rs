@@ -0,0 +1,77 @@
+use crate::prelude::*;
+use std::collections::HashSet;
+
+/// Sizes of the inspector column, in ui units
+pub const DEBUG_INSPECTOR_MARGIN: f32 = 0.5;
+pub const DEBUG_INSPECTOR_WIDTH: f32 = 11.0;
+pub const DEBUG_INSPECTOR_ROW_HEIGHT: f32 = 0.6;
+pub const DEBUG_INSPECTOR_TEXT_SIZE: f32 = 0.38;
+
+/// How much of each row goes to the name on the left, with the value or control taking the rest
+pub const DEBUG_INSPECTOR_LABEL_FRACTION: f32 = 0.35;
+
+
+/// Identifies a widget from one frame to the next, from the panel it's in and its label
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DebugWidgetId(pub u64);
+
+
+/// Something for the view to draw, in ui units over the whole window. Later commands are drawn over earlier ones.
+#[derive(Clone, Debug)]
+pub enum DebugDrawCommand {
+	Rect {
+		center: Vec2,
+		size: Vec2,
+		color: Color,
+		rounded: bool,
+	},
+
+	/// Vertically centered on `position`
+	Text {
+		text: String,
+		position: Vec2,
+		color: Color,
+		align: gfx::TextAlign,
+	},
+}
+
+
+/// State for the debug inspector, an immediate mode ui rebuilt every frame by the debug controller while it's active.
+/// Only what has to outlive a frame is kept here - everything else is in the draw list.
+#[derive(Debug)]
+pub struct DebugInspector {
+	pub visible: bool,
+
+	/// Covers the whole window, like the pause menu
+	pub metrics: model::UiMetrics,
+
+	/// Titles of the panels that have been expanded
+	pub expanded_panels: HashSet<String>,
+
+	/// The widget the mouse went down on, which keeps hold of the mouse until it's released
+	pub active_widget: Option<DebugWidgetId>,
+
+	pub draw_list: Vec<DebugDrawCommand>,
+}
+
+impl DebugInspector {
+	pub fn new() -> DebugInspector {
+		DebugInspector {
+			visible: false,
+			metrics: model::UiMetrics::new(1.0, 1.0),
+			expanded_panels: HashSet::new(),
+			active_widget: None,
+			draw_list: Vec::new(),
+		}
+	}
+
+	pub fn is_expanded(&self, panel: &str) -> bool {
+		self.expanded_panels.contains(panel)
+	}
+
+	pub fn toggle_panel(&mut self, panel: &str) {
+		if !self.expanded_panels.remove(panel) {
+			self.expanded_panels.insert(panel.to_owned());
+		}
+	}
+}
@@ -5,15 +5,44 @@ pub struct Global {
 	pub wants_hard_quit: bool,
 	pub wireframe_enabled: bool,
 
+	/// Debug preview of how the world looks with a kind of colour blindness, on top of the chosen colour mode
+	pub simulated_color_blindness: Option<super::ColorBlindness>,
+
+	pub game_mode: GameMode,
 	pub game_state: GameState,
+	pub net_role: NetRole,
+
+	/// The state to return to when unpausing
+	resume_state: Option<GameState>,
 }
 
 impl Global {
-	pub fn new() -> Global {
+	pub fn new(game_mode: GameMode, tunables: &super::Tunables) -> Global {
 		Global {
 			wants_hard_quit: false,
 			wireframe_enabled: false,
-			game_state: GameState::PreGame(1.0),
+			simulated_color_blindness: None,
+			game_mode,
+			game_state: GameState::PreGame(tunables.pre_game_time),
+			net_role: NetRole::Offline,
+			resume_state: None,
+		}
+	}
+
+	pub fn is_paused(&self) -> bool {
+		matches!(self.game_state, GameState::Paused)
+	}
+
+	pub fn pause(&mut self) {
+		if !self.is_paused() {
+			self.resume_state = Some(self.game_state);
+			self.game_state = GameState::Paused;
+		}
+	}
+
+	pub fn resume(&mut self) {
+		if let Some(state) = self.resume_state.take() {
+			self.game_state = state;
 		}
 	}
 }
@@ -21,6 +50,42 @@ impl Global {
 
 
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GameMode {
+	/// Sail around meeting all the friends
+	FriendFinding,
+
+	/// Race around a course of buoys against the best time
+	Regatta,
+}
+
+impl GameMode {
+	/// Parses `--regatta` from the command line, otherwise defaulting to friend finding
+	pub fn from_args(mut args: impl Iterator<Item=String>) -> GameMode {
+		match args.any(|arg| arg == "--regatta") {
+			true => GameMode::Regatta,
+			false => GameMode::FriendFinding,
+		}
+	}
+}
+
+
+
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NetRole {
+	Offline,
+
+	/// Simulates the world and sends it to clients
+	Host,
+
+	/// Only simulates its own boat - everything else comes from the host
+	Client,
+}
+
+
+
+
 #[derive(Copy, Clone, Debug)]
 pub enum GameState {
 	PreGame(f32),
@@ -29,10 +94,23 @@ pub enum GameState {
 	GotFriend(f32),
 	Ending(f32),
 	PostGame,
+
+	/// Regatta only - the boat is held on the start line until the timer runs out
+	RaceCountdown(f32),
+	Racing,
+	RaceFinished(f32),
+
+	/// All friends on this map have been met and there's another to sail on to
+	LeavingLevel(f32),
+	LoadingLevel,
+	ArrivingLevel(f32),
+
+	/// Simulation is frozen while the pause menu is open
+	Paused,
 }
 
 impl GameState {
-	pub fn update(&mut self) {
+	pub fn update(&mut self, mode: GameMode, has_next_level: bool, tunables: &super::Tunables) {
 		use GameState::*;
 
 		let dt = 1.0/60.0;
@@ -41,7 +119,10 @@ impl GameState {
 			PreGame(timer) => {
 				let new_timer = timer - dt;
 				if new_timer < 0.0 {
-					Starting(3.0)
+					match mode {
+						GameMode::FriendFinding => Starting(tunables.start_time),
+						GameMode::Regatta => RaceCountdown(tunables.race_countdown_time),
+					}
 				} else {
 					PreGame(new_timer)
 				}
@@ -67,26 +148,83 @@ impl GameState {
 
 			Ending(timer) => {
 				let new_timer = timer - dt;
-				if new_timer < 0.0 {
+				if new_timer < 0.0 && has_next_level {
+					LeavingLevel(tunables.level_leave_time)
+				} else if new_timer < 0.0 {
 					PostGame
 				} else {
 					Ending(new_timer)
 				}
 			}
 
+			LeavingLevel(timer) => {
+				let new_timer = timer - dt;
+				if new_timer < 0.0 {
+					LoadingLevel
+				} else {
+					LeavingLevel(new_timer)
+				}
+			}
+
+			// Moving on from loading is up to whoever is loading the level
+			ArrivingLevel(timer) => {
+				let new_timer = timer - dt;
+				if new_timer < 0.0 {
+					Starting(tunables.start_time)
+				} else {
+					ArrivingLevel(new_timer)
+				}
+			}
+
+			RaceCountdown(timer) => {
+				let new_timer = timer - dt;
+				if new_timer < 0.0 {
+					Racing
+				} else {
+					RaceCountdown(new_timer)
+				}
+			}
+
+			RaceFinished(timer) => {
+				let new_timer = timer - dt;
+				if new_timer < 0.0 {
+					PostGame
+				} else {
+					RaceFinished(new_timer)
+				}
+			}
+
 			_ => return
 		};
 	}
 
 	pub fn has_ended(&self) -> bool {
-		matches!(self, GameState::Ending(_) | GameState::PostGame)
+		matches!(self, GameState::Ending(_) | GameState::RaceFinished(_) | GameState::PostGame
+			| GameState::LeavingLevel(_) | GameState::LoadingLevel)
+	}
+
+	/// How far the world has faded into the horizon during a level transition, from 0 to 1
+	pub fn horizon_fade(&self, tunables: &super::Tunables) -> f32 {
+		let fade = match *self {
+			GameState::LeavingLevel(timer) => 1.0 - timer / tunables.level_leave_time,
+			GameState::LoadingLevel => 1.0,
+			GameState::ArrivingLevel(timer) => timer / tunables.level_arrive_time,
+			_ => 0.0,
+		};
+
+		// Timers can be retuned mid transition
+		fade.clamp(0.0, 1.0)
+	}
+
+	pub fn notify_end_game(&mut self, tunables: &super::Tunables) {
+		*self = GameState::Ending(tunables.ending_time);
 	}
 
-	pub fn notify_end_game(&mut self) {
-		*self = GameState::Ending(3.0);
+	pub fn notify_got_friend(&mut self, tunables: &super::Tunables) {
+		*self = GameState::GotFriend(tunables.got_friend_time);
 	}
 
-	pub fn notify_got_friend(&mut self) {
-		*self = GameState::GotFriend(2.0);
+	pub fn notify_race_finished(&mut self, tunables: &super::Tunables) {
+		*self = GameState::RaceFinished(tunables.race_finished_time);
 	}
 }
\ No newline at end of file
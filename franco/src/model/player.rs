@@ -7,6 +7,13 @@ pub struct Player {
 	pub speed: f32,
 
 	pub sail_state: SailState,
+	pub harbour: model::HarbourState,
+
+	/// Which of the boat's paints it's drawn with - 0 for its default colors, then each of `Resources::boat_paints`
+	pub paint: u8,
+
+	/// Counts down to when this boat can ping its sonar again
+	pub sonar_cooldown: f32,
 }
 
 impl Player {
@@ -17,12 +24,52 @@ impl Player {
 			speed: 0.0,
 
 			sail_state: SailState::Anchored,
+			harbour: model::HarbourState::AtSea,
+
+			paint: 0,
+
+			sonar_cooldown: 0.0,
+		}
+	}
+
+	pub fn raise_sail(&mut self, max_sail_speed: i32) {
+		self.sail_state = match self.sail_state {
+			SailState::Anchored => SailState::Sailing{speed: 1},
+			SailState::Sailing{speed} if speed >= max_sail_speed => return,
+			SailState::Sailing{speed} => SailState::Sailing{speed: speed+1},
+		}
+	}
+
+	pub fn lower_sail(&mut self) {
+		self.sail_state = match self.sail_state {
+			SailState::Anchored => return,
+			SailState::Sailing{speed: 1} => SailState::Anchored,
+			SailState::Sailing{speed} => SailState::Sailing{speed: speed-1},
 		}
 	}
 }
 
 
-pub const MAX_SAIL_SPEED: i32 = 5;
+/// Another player's boat in a networked session
+#[derive(Debug)]
+pub struct RemotePlayer {
+	pub id: u16,
+	pub player: Player,
+
+	/// The wheel angle last received from this player - only meaningful on the host
+	pub wheel_angle: f32,
+}
+
+impl RemotePlayer {
+	pub fn new(id: u16) -> RemotePlayer {
+		RemotePlayer {
+			id,
+			player: Player::new(),
+			wheel_angle: 0.0,
+		}
+	}
+}
+
 
 #[derive(Copy, Clone, Debug)]
 pub enum SailState {
@@ -34,3 +81,19 @@ pub enum SailState {
 }
 
 
+
+impl SailState {
+	pub fn from_speed(speed: i32) -> SailState {
+		match speed {
+			0 => SailState::Anchored,
+			speed => SailState::Sailing { speed },
+		}
+	}
+
+	pub fn speed(&self) -> i32 {
+		match *self {
+			SailState::Anchored => 0,
+			SailState::Sailing { speed } => speed,
+		}
+	}
+}
@@ -0,0 +1,23 @@
+use crate::prelude::*;
+
+
+/// Which level is being played, and the next level's project while it loads
+#[derive(Debug)]
+pub struct Progression {
+	pub level: usize,
+	pub loader: Option<model::ProjectLoader>,
+}
+
+impl Progression {
+	pub fn new(level: usize) -> Progression {
+		Progression {
+			level,
+			loader: None,
+		}
+	}
+
+	pub fn next_level(&self, resources: &model::Resources) -> Option<usize> {
+		let next = self.level + 1;
+		(next < resources.levels.len()).then(|| next)
+	}
+}
@@ -0,0 +1,184 @@
+use crate::prelude::*;
+use persist::KeyValues;
+
+pub const REGATTA_PATH: &str = "regatta.txt";
+pub const REGATTA_GHOST_PATH: &str = "regatta_ghost.txt";
+
+/// How close a boat must pass to a buoy for it to count
+pub const CHECKPOINT_RADIUS: f32 = 4.0;
+
+/// Used to lay out a course when the map scene doesn't place any `CHECKPOINT_` entities
+const GENERATED_CHECKPOINT_COUNT: usize = 8;
+const GENERATED_COURSE_EXTENT: f32 = 0.35;
+const GENERATED_ISLAND_CLEARANCE: f32 = 8.0;
+
+
+#[derive(Copy, Clone, Debug)]
+pub struct GhostFrame {
+	pub map_position: Vec2,
+	pub heading: f32,
+}
+
+
+/// A loop of buoys, starting and finishing at the first.
+#[derive(Debug)]
+pub struct Regatta {
+	pub checkpoints: Vec<Vec2>,
+
+	/// How many buoys have been passed in the current run - the race is over once all of them have,
+	/// including a return to the first
+	pub checkpoints_passed: usize,
+	pub timer: f32,
+	pub best_time: Option<f32>,
+
+	/// One frame per tick of the run in progress
+	pub recording: Vec<GhostFrame>,
+
+	/// The best run so far, replayed alongside the player. Empty until a run has been finished
+	pub ghost: Vec<GhostFrame>,
+}
+
+impl Regatta {
+	pub fn new(map_scene: toy::SceneRef<'_>, map: &model::world::Map) -> Result<Regatta> {
+		let mut placed: Vec<_> = map_scene.entities_with_prefix("CHECKPOINT_")
+			.map(|entity| (entity.name.clone(), entity.position.to_xz() * Vec2::new(1.0, -1.0)))
+			.collect();
+
+		placed.sort_by(|a, b| a.0.cmp(&b.0));
+
+		let checkpoints = match placed.len() {
+			0 | 1 => generate_course(map),
+			_ => placed.into_iter().map(|(_, pos)| pos).collect(),
+		};
+
+		let saved = KeyValues::load(REGATTA_PATH)?;
+
+		Ok(Regatta {
+			checkpoints,
+			checkpoints_passed: 0,
+			timer: 0.0,
+			best_time: saved.get("best_time"),
+
+			recording: Vec::new(),
+			ghost: load_ghost(REGATTA_GHOST_PATH)?,
+		})
+	}
+
+	pub fn start_position(&self) -> GhostFrame {
+		let start = self.checkpoints[0];
+		let first_leg = self.checkpoints[1] - start;
+
+		GhostFrame {
+			map_position: start,
+			heading: first_leg.to_angle(),
+		}
+	}
+
+	/// The index of the buoy the player is currently racing towards, which is the first again for the final leg
+	pub fn next_checkpoint_index(&self) -> Option<usize> {
+		match self.is_finished() {
+			true => None,
+			false => Some((self.checkpoints_passed + 1) % self.checkpoints.len()),
+		}
+	}
+
+	pub fn next_checkpoint(&self) -> Option<Vec2> {
+		self.next_checkpoint_index().map(|idx| self.checkpoints[idx])
+	}
+
+	pub fn is_finished(&self) -> bool {
+		self.checkpoints_passed >= self.checkpoints.len()
+	}
+
+	pub fn reset(&mut self) {
+		self.checkpoints_passed = 0;
+		self.timer = 0.0;
+		self.recording.clear();
+	}
+
+	/// Where the best run was at the same point into the race as the run in progress
+	pub fn ghost_frame(&self) -> Option<GhostFrame> {
+		self.ghost.get(self.recording.len())
+			.or_else(|| self.ghost.last())
+			.copied()
+	}
+
+	/// Records the finished run as the new best if it beat the last one, returning whether it did
+	pub fn finish_run(&mut self) -> bool {
+		let is_best = self.best_time.map_or(true, |best| self.timer < best);
+
+		if is_best {
+			self.best_time = Some(self.timer);
+			self.ghost = std::mem::take(&mut self.recording);
+		}
+
+		is_best
+	}
+
+	pub fn save(&self) -> Result<()> {
+		let mut kv = KeyValues::new();
+
+		if let Some(best_time) = self.best_time {
+			kv.set("best_time", best_time);
+		}
+
+		kv.save(REGATTA_PATH)?;
+
+		let ghost_source: String = self.ghost.iter()
+			.map(|frame| format!("{} {} {}\n", frame.map_position.x, frame.map_position.y, frame.heading))
+			.collect();
+
+		std::fs::write(REGATTA_GHOST_PATH, ghost_source)?;
+		Ok(())
+	}
+}
+
+
+/// Ghost runs are stored one frame per line, as `x y heading`. A missing file means no run has been finished yet.
+fn load_ghost(path: &str) -> Result<Vec<GhostFrame>> {
+	let source = match std::fs::read_to_string(path) {
+		Ok(source) => source,
+		Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+		Err(err) => return Err(err.into()),
+	};
+
+	let frames = source.lines()
+		.filter_map(|line| {
+			let mut values = line.split_whitespace().map(str::parse::<f32>);
+			let x = values.next()?.ok()?;
+			let y = values.next()?.ok()?;
+			let heading = values.next()?.ok()?;
+
+			Some(GhostFrame {
+				map_position: Vec2::new(x, y),
+				heading,
+			})
+		})
+		.collect();
+
+	Ok(frames)
+}
+
+
+/// Spaces buoys evenly around an ellipse, pushing any that land on an island further out
+fn generate_course(map: &model::world::Map) -> Vec<Vec2> {
+	(0..GENERATED_CHECKPOINT_COUNT)
+		.map(|idx| {
+			let angle = idx as f32 * TAU / GENERATED_CHECKPOINT_COUNT as f32;
+			let direction = Vec2::from_angle(angle);
+			let mut pos = direction * map.size * GENERATED_COURSE_EXTENT;
+
+			for _ in 0..4 {
+				match map.nearest_island(pos) {
+					Some(island) if (pos - island).length() < GENERATED_ISLAND_CLEARANCE => {
+						pos += direction * GENERATED_ISLAND_CLEARANCE;
+					}
+
+					_ => break
+				}
+			}
+
+			pos
+		})
+		.collect()
+}
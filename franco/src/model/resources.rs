@@ -1,17 +1,188 @@
 use crate::prelude::*;
+use std::thread::JoinHandle;
+
+pub const ASSETS_PATH: &str = "assets";
+pub const MAIN_PROJECT_NAME: &str = "main.toy";
+pub const LEVELS_PATH: &str = "assets/levels.txt";
 
 
 pub struct Resources {
 	pub main_project: toy::Project,
+	pub levels: Vec<LevelInfo>,
+
+	/// The project the current level's map comes from, when that isn't the main project
+	pub level_project: Option<toy::Project>,
 }
 
 impl Resources {
-	pub fn new() -> Result<Resources> {
-		let main_project_data = std::fs::read("assets/main.toy")?;
+	/// Loads the main project, and the project for `start_level` if it lives elsewhere
+	pub fn new(start_level: usize) -> Result<Resources> {
+		let main_project_data = std::fs::read(format!("{}/{}", ASSETS_PATH, MAIN_PROJECT_NAME))?;
 		let main_project = toy::load(&main_project_data)?;
 
+		let levels_src = std::fs::read_to_string(LEVELS_PATH)?;
+		let levels = LevelInfo::parse_list(&levels_src)?;
+
+		if levels.is_empty() {
+			return Err(format!("No levels listed in {}", LEVELS_PATH).into())
+		}
+
+		let start_info = levels.get(start_level)
+			.ok_or_else(|| format!("No level {} in {}", start_level, LEVELS_PATH))?;
+
+		// Only the starting level is loaded up front - later ones are loaded as they're reached
+		let level_project = match start_info.in_main_project() {
+			true => None,
+			false => Some(load_project(&start_info.project)?),
+		};
+
 		Ok(Resources {
-			main_project
+			main_project,
+			levels,
+			level_project,
 		})
 	}
-}
\ No newline at end of file
+
+	/// The map scene of `level`, which must be the level `level_project` was loaded for
+	pub fn level_scene(&self, level: usize) -> toy::SceneRef<'_> {
+		let info = &self.levels[level];
+		let project = self.level_project.as_ref().unwrap_or(&self.main_project);
+
+		project.find_scene(&info.scene)
+			.unwrap_or_else(|| panic!("Missing scene '{}' for level '{}'", info.scene, info.name))
+	}
+
+	/// The scenes islands are built from - each is placed wherever the map has an object of the same name
+	pub fn island_scenes(&self) -> impl Iterator<Item=toy::SceneRef<'_>> {
+		self.main_project.scenes()
+			.filter(|scene| scene.name.starts_with("ISLAND_"))
+	}
+
+	/// The names of the boat's alternative color layers, which can be picked in the settings
+	pub fn boat_paints(&self) -> Vec<String> {
+		self.main_project.find_entity("Boat")
+			.and_then(|entity| entity.mesh_data())
+			.map_or_else(Vec::new, |mesh| mesh.color_variants().map(String::from).collect())
+	}
+}
+
+
+
+#[derive(Debug, Clone)]
+pub struct LevelInfo {
+	pub name: String,
+	pub project: String,
+	pub scene: String,
+}
+
+impl LevelInfo {
+	/// Parses one level per line, in the form `name | project | scene`
+	pub fn parse_list(source: &str) -> Result<Vec<LevelInfo>> {
+		source.lines()
+			.map(str::trim)
+			.filter(|line| !line.is_empty() && !line.starts_with('#'))
+			.map(LevelInfo::parse)
+			.collect()
+	}
+
+	fn parse(line: &str) -> Result<LevelInfo> {
+		let fields: Vec<_> = line.split('|').map(str::trim).collect();
+
+		match fields[..] {
+			[name, project, scene] => Ok(LevelInfo {
+				name: name.to_owned(),
+				project: project.to_owned(),
+				scene: scene.to_owned(),
+			}),
+
+			_ => Err(format!("Malformed level '{}'", line).into()),
+		}
+	}
+
+	pub fn in_main_project(&self) -> bool {
+		self.project == MAIN_PROJECT_NAME
+	}
+}
+
+
+
+/// Loads a project on a background thread, so that reading and parsing it doesn't stall the frame
+#[derive(Debug)]
+pub struct ProjectLoader {
+	handle: Option<JoinHandle<std::result::Result<toy::Project, String>>>,
+}
+
+impl ProjectLoader {
+	pub fn start(project_name: &str) -> ProjectLoader {
+		let project_name = project_name.to_owned();
+
+		let handle = std::thread::spawn(move || {
+			load_project(&project_name)
+				.map_err(|err| format!("Failed to load '{}': {}", project_name, err))
+		});
+
+		ProjectLoader {
+			handle: Some(handle),
+		}
+	}
+
+	/// Returns the loaded project once the background thread has finished, without blocking
+	pub fn poll(&mut self) -> Option<Result<toy::Project>> {
+		if !self.handle.as_ref()?.is_finished() {
+			return None
+		}
+
+		let result = self.handle.take()?.join()
+			.map_err(|_| "Project loader thread panicked".to_owned())
+			.and_then(|result| result);
+
+		Some(result.map_err(Into::into))
+	}
+}
+
+
+fn load_project(project_name: &str) -> Result<toy::Project> {
+	let data = std::fs::read(format!("{}/{}", ASSETS_PATH, project_name))?;
+	Ok(toy::load(&data)?)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_list() {
+		let levels = LevelInfo::parse_list("
+			# name | project | scene
+			Bay | main.toy | map
+
+			Reef|reef.toy|reef_map
+		").unwrap();
+
+		assert_eq!(levels.len(), 2);
+
+		assert_eq!(levels[0].name, "Bay");
+		assert!(levels[0].in_main_project());
+
+		assert_eq!(levels[1].project, "reef.toy");
+		assert_eq!(levels[1].scene, "reef_map");
+		assert!(!levels[1].in_main_project());
+	}
+
+	#[test]
+	fn test_parse_list_malformed_lines() {
+		for line in ["Bay | main.toy", "Bay | main.toy | map | extra", "Bay"] {
+			let source = format!("Reef | reef.toy | reef_map\n{}", line);
+			let err = LevelInfo::parse_list(&source).unwrap_err();
+			assert_eq!(err.to_string(), format!("Malformed level '{}'", line));
+		}
+	}
+
+	#[test]
+	fn test_parse_list_empty() {
+		let levels = LevelInfo::parse_list("\n  # nothing here\n\n").unwrap();
+		assert!(levels.is_empty());
+	}
+}
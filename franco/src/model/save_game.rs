@@ -0,0 +1,164 @@
+use crate::prelude::*;
+
+use crate::persist::KeyValues;
+use model::FriendName;
+
+pub const SAVE_GAME_PATH: &str = "savegame.txt";
+
+
+/// Written at port save points, so a voyage can be picked back up from the last harbour visited
+#[derive(Debug, Clone)]
+pub struct SaveGame {
+	pub level: usize,
+	pub port: usize,
+
+	/// Every friend met by the time the game was saved, whether or not they're still keeping the boat company
+	pub friends: Vec<SavedFriend>,
+}
+
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SavedFriend {
+	pub name: FriendName,
+	pub said_goodbye: bool,
+	pub homesick: bool,
+}
+
+impl SavedFriend {
+	/// Parses a name, optionally followed by `:goodbye` or `:homesick`
+	fn parse(token: &str) -> Option<SavedFriend> {
+		let (name, flag) = match token.split_once(':') {
+			Some((name, flag)) => (name, Some(flag)),
+			None => (token, None),
+		};
+
+		let (said_goodbye, homesick) = match flag {
+			None => (false, false),
+			Some("goodbye") => (true, true),
+			Some("homesick") => (false, true),
+			Some(_) => return None,
+		};
+
+		Some(SavedFriend {
+			name: FriendName::try_from_name(name)?,
+			said_goodbye,
+			homesick,
+		})
+	}
+
+	fn to_token(self) -> String {
+		match (self.said_goodbye, self.homesick) {
+			(true, _) => format!("{}:goodbye", self.name.as_str()),
+			(false, true) => format!("{}:homesick", self.name.as_str()),
+			(false, false) => self.name.as_str().to_owned(),
+		}
+	}
+}
+
+impl SaveGame {
+	pub fn capture(model: &model::Model, port: usize) -> SaveGame {
+		let friends = model.world.friends.iter()
+			.filter(|friend| friend.met_player())
+			.map(|friend| SavedFriend {
+				name: friend.name,
+				said_goodbye: friend.said_goodbye,
+				homesick: friend.homesick,
+			})
+			.collect();
+
+		SaveGame {
+			level: model.progression.level,
+			port,
+			friends,
+		}
+	}
+
+	/// Returns `None` if nothing has been saved yet
+	pub fn load() -> Result<Option<SaveGame>> {
+		let kv = KeyValues::load(SAVE_GAME_PATH)?;
+		Ok(SaveGame::from_key_values(&kv))
+	}
+
+	pub fn save(&self) -> Result<()> {
+		self.to_key_values().save(SAVE_GAME_PATH)
+	}
+
+	fn from_key_values(kv: &KeyValues) -> Option<SaveGame> {
+		let (level, port) = match (kv.get("level"), kv.get("port")) {
+			(Some(level), Some(port)) => (level, port),
+			_ => return None,
+		};
+
+		let friends = kv.get::<String>("friends").unwrap_or_default()
+			.split_whitespace()
+			.filter_map(|token| {
+				let friend = SavedFriend::parse(token);
+				if friend.is_none() {
+					eprintln!("Skipping unknown friend '{}' in saved game", token);
+				}
+
+				friend
+			})
+			.collect();
+
+		Some(SaveGame { level, port, friends })
+	}
+
+	fn to_key_values(&self) -> KeyValues {
+		let mut kv = KeyValues::new();
+		kv.set("level", self.level);
+		kv.set("port", self.port);
+
+		let friends: Vec<_> = self.friends.iter().map(|friend| friend.to_token()).collect();
+		kv.set("friends", friends.join(" "));
+
+		kv
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_round_trip() {
+		let save_game = SaveGame {
+			level: 1,
+			port: 2,
+			friends: vec![
+				SavedFriend { name: FriendName::Dolphin, said_goodbye: false, homesick: false },
+				SavedFriend { name: FriendName::Fish, said_goodbye: true, homesick: true },
+				SavedFriend { name: FriendName::BoatBoy, said_goodbye: false, homesick: true },
+			],
+		};
+
+		let kv = KeyValues::parse(&save_game.to_key_values().to_string());
+		let loaded = SaveGame::from_key_values(&kv).unwrap();
+
+		assert_eq!(loaded.level, 1);
+		assert_eq!(loaded.port, 2);
+		assert_eq!(loaded.friends, save_game.friends);
+	}
+
+	#[test]
+	fn test_unknown_friends() {
+		let kv = KeyValues::parse("
+			level = 0
+			port = 1
+			friends = dolphin kraken fish:sulking boat_boy:goodbye
+		");
+
+		let loaded = SaveGame::from_key_values(&kv).unwrap();
+		let names: Vec<_> = loaded.friends.iter().map(|friend| friend.name).collect();
+		assert_eq!(names, [FriendName::Dolphin, FriendName::BoatBoy]);
+		assert!(loaded.friends[1].said_goodbye);
+	}
+
+	#[test]
+	fn test_nothing_saved() {
+		assert!(SaveGame::from_key_values(&KeyValues::new()).is_none());
+		assert!(SaveGame::from_key_values(&KeyValues::parse("level = 0")).is_none());
+	}
+}
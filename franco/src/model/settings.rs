@@ -0,0 +1,321 @@
+use crate::prelude::*;
+
+use crate::persist::KeyValues;
+
+pub const SETTINGS_PATH: &str = "settings.txt";
+
+/// Not a menu item, so saved alongside them rather than as one
+const COMPLETED_HINTS_KEY: &str = "tutorial.completed_hints";
+
+
+/// User preferences, edited from the pause menu and persisted between sessions
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+	pub master_volume: f32,
+	pub music_volume: f32,
+	pub sfx_volume: f32,
+
+	/// Scales the sensitivity of every mouse driven action
+	pub mouse_sensitivity: f32,
+	pub invert_camera: bool,
+
+	pub fullscreen: bool,
+	pub vsync: bool,
+
+	pub ui_scale: f32,
+	pub color_mode: model::ColorMode,
+
+	/// The boat's alternative color layer, or empty for its default colors
+	pub boat_paint: String,
+
+	/// Every paint that can be chosen, starting with the default - depends on the boat mesh, so isn't saved
+	boat_paints: Vec<String>,
+
+	/// Tutorial hints that have been followed, and so never need showing again
+	pub completed_hints: Vec<model::HintKind>,
+}
+
+impl Default for Settings {
+	fn default() -> Settings {
+		Settings {
+			master_volume: 1.0,
+			music_volume: 0.8,
+			sfx_volume: 0.8,
+
+			mouse_sensitivity: 1.0,
+			invert_camera: false,
+
+			fullscreen: false,
+			vsync: true,
+
+			ui_scale: 1.0,
+			color_mode: model::ColorMode::Normal,
+
+			boat_paint: String::new(),
+			boat_paints: vec![String::new()],
+
+			completed_hints: Vec::new(),
+		}
+	}
+}
+
+impl Settings {
+	/// `boat_paints` lists the alternative color layers the boat can be painted with
+	pub fn load(boat_paints: Vec<String>) -> Result<Settings> {
+		let kv = KeyValues::load(SETTINGS_PATH)?;
+		let mut settings = Settings::default();
+		settings.boat_paints.extend(boat_paints);
+
+		for item in SettingItem::ALL {
+			let key = match item.key() {
+				Some(key) => key,
+				None => continue,
+			};
+
+			match settings.get(item) {
+				SettingValue::Slider(value) => {
+					let (min, max, _) = item.range();
+					settings.set_slider(item, kv.get_or(key, value).clamp(min, max));
+				}
+
+				SettingValue::Toggle(value) => {
+					settings.set_toggle(item, kv.get_or(key, value));
+				}
+
+				// Saved by name, so that adding options doesn't change which one is chosen
+				SettingValue::Choice{..} => {
+					let name: String = kv.get_or(key, String::new());
+					if let Some(index) = settings.choice_names(item).iter().position(|option| *option == name) {
+						settings.set_choice(item, index);
+					}
+				}
+
+				SettingValue::None => {}
+			}
+		}
+
+		settings.completed_hints = kv.get_or(COMPLETED_HINTS_KEY, String::new())
+			.split_whitespace()
+			.filter_map(model::HintKind::from_name)
+			.collect();
+
+		Ok(settings)
+	}
+
+	pub fn save(&self) -> Result<()> {
+		let mut kv = KeyValues::new();
+
+		for item in SettingItem::ALL {
+			let key = match item.key() {
+				Some(key) => key,
+				None => continue,
+			};
+
+			match self.get(item) {
+				SettingValue::Slider(value) => kv.set(key, value),
+				SettingValue::Toggle(value) => kv.set(key, value),
+				SettingValue::Choice{index, ..} => kv.set(key, &self.choice_names(item)[index]),
+				SettingValue::None => {}
+			}
+		}
+
+		let completed_hints: Vec<_> = self.completed_hints.iter().map(model::HintKind::name).collect();
+		kv.set(COMPLETED_HINTS_KEY, completed_hints.join(" "));
+
+		kv.save(SETTINGS_PATH)
+	}
+
+
+	pub fn get(&self, item: SettingItem) -> SettingValue {
+		use SettingItem::*;
+
+		match item {
+			MasterVolume => SettingValue::Slider(self.master_volume),
+			MusicVolume => SettingValue::Slider(self.music_volume),
+			SfxVolume => SettingValue::Slider(self.sfx_volume),
+			MouseSensitivity => SettingValue::Slider(self.mouse_sensitivity),
+			UiScale => SettingValue::Slider(self.ui_scale),
+
+			InvertCamera => SettingValue::Toggle(self.invert_camera),
+			Fullscreen => SettingValue::Toggle(self.fullscreen),
+			Vsync => SettingValue::Toggle(self.vsync),
+
+			BoatPaint => SettingValue::Choice {
+				index: self.boat_paint_index() as usize,
+				count: self.boat_paints.len(),
+			},
+
+			ColorMode => SettingValue::Choice {
+				index: model::ColorMode::ALL.iter().position(|&mode| mode == self.color_mode).unwrap_or(0),
+				count: model::ColorMode::ALL.len(),
+			},
+
+			Back => SettingValue::None,
+		}
+	}
+
+	/// The chosen paint, indexed the same way as `Player::paint`
+	pub fn boat_paint_index(&self) -> u8 {
+		self.boat_paints.iter().position(|paint| *paint == self.boat_paint).unwrap_or(0) as u8
+	}
+
+	/// How many paints a boat can be given, including its default colors
+	pub fn num_boat_paints(&self) -> usize {
+		self.boat_paints.len()
+	}
+
+	/// Steps a slider by `steps` increments, cycles through choices, or flips a toggle
+	pub fn adjust(&mut self, item: SettingItem, steps: i32) {
+		match self.get(item) {
+			SettingValue::Slider(value) => {
+				let (min, max, step) = item.range();
+				self.set_slider(item, (value + step * steps as f32).clamp(min, max));
+			}
+
+			SettingValue::Choice{index, count} => {
+				let index = (index as i32 + steps).rem_euclid(count as i32);
+				self.set_choice(item, index as usize);
+			}
+
+			SettingValue::Toggle(value) => self.set_toggle(item, !value),
+			SettingValue::None => {}
+		}
+	}
+
+	/// Sets a slider from a position along its range, snapped to its step size
+	pub fn set_ratio(&mut self, item: SettingItem, ratio: f32) {
+		let (min, max, step) = item.range();
+		let value = ratio.clamp(0.0, 1.0).ease_linear(min, max);
+		let value = min + ((value - min) / step).round() * step;
+		self.set_slider(item, value.clamp(min, max));
+	}
+
+	/// The position of a slider along its range
+	pub fn ratio(&self, item: SettingItem) -> f32 {
+		match self.get(item) {
+			SettingValue::Slider(value) => {
+				let (min, max, _) = item.range();
+				(value - min) / (max - min)
+			}
+
+			_ => 0.0,
+		}
+	}
+
+	fn set_slider(&mut self, item: SettingItem, value: f32) {
+		use SettingItem::*;
+
+		match item {
+			MasterVolume => self.master_volume = value,
+			MusicVolume => self.music_volume = value,
+			SfxVolume => self.sfx_volume = value,
+			MouseSensitivity => self.mouse_sensitivity = value,
+			UiScale => self.ui_scale = value,
+			_ => {}
+		}
+	}
+
+	fn set_choice(&mut self, item: SettingItem, index: usize) {
+		match item {
+			SettingItem::BoatPaint => self.boat_paint = self.boat_paints[index].clone(),
+			SettingItem::ColorMode => self.color_mode = model::ColorMode::ALL[index],
+			_ => {}
+		}
+	}
+
+	/// The names choices are saved by, in the same order as their indices
+	fn choice_names(&self, item: SettingItem) -> Vec<String> {
+		match item {
+			SettingItem::BoatPaint => self.boat_paints.clone(),
+			SettingItem::ColorMode => model::ColorMode::ALL.iter().map(|mode| mode.name().to_owned()).collect(),
+			_ => Vec::new(),
+		}
+	}
+
+	fn set_toggle(&mut self, item: SettingItem, value: bool) {
+		use SettingItem::*;
+
+		match item {
+			InvertCamera => self.invert_camera = value,
+			Fullscreen => self.fullscreen = value,
+			Vsync => self.vsync = value,
+			_ => {}
+		}
+	}
+}
+
+
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SettingValue {
+	/// A value within the range given by `SettingItem::range`
+	Slider(f32),
+	Toggle(bool),
+
+	/// One of `count` options, which wraps around when stepped past either end
+	Choice{index: usize, count: usize},
+	None,
+}
+
+
+/// A row of the settings page of the pause menu
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SettingItem {
+	MasterVolume,
+	MusicVolume,
+	SfxVolume,
+	MouseSensitivity,
+	InvertCamera,
+	Fullscreen,
+	Vsync,
+	UiScale,
+	ColorMode,
+	BoatPaint,
+	Back,
+}
+
+impl SettingItem {
+	pub const ALL: [SettingItem; 11] = [
+		SettingItem::MasterVolume,
+		SettingItem::MusicVolume,
+		SettingItem::SfxVolume,
+		SettingItem::MouseSensitivity,
+		SettingItem::InvertCamera,
+		SettingItem::Fullscreen,
+		SettingItem::Vsync,
+		SettingItem::UiScale,
+		SettingItem::ColorMode,
+		SettingItem::BoatPaint,
+		SettingItem::Back,
+	];
+
+	fn key(&self) -> Option<&'static str> {
+		use SettingItem::*;
+
+		match self {
+			MasterVolume => Some("audio.master_volume"),
+			MusicVolume => Some("audio.music_volume"),
+			SfxVolume => Some("audio.sfx_volume"),
+			MouseSensitivity => Some("input.mouse_sensitivity"),
+			InvertCamera => Some("input.invert_camera"),
+			Fullscreen => Some("video.fullscreen"),
+			Vsync => Some("video.vsync"),
+			UiScale => Some("video.ui_scale"),
+			ColorMode => Some("video.color_mode"),
+			BoatPaint => Some("boat.paint"),
+			Back => None,
+		}
+	}
+
+	/// (min, max, step) for sliders
+	pub fn range(&self) -> (f32, f32, f32) {
+		use SettingItem::*;
+
+		match self {
+			MasterVolume | MusicVolume | SfxVolume => (0.0, 1.0, 0.1),
+			MouseSensitivity => (0.25, 3.0, 0.25),
+			UiScale => (0.5, 2.0, 0.1),
+			_ => (0.0, 1.0, 1.0),
+		}
+	}
+}
@@ -0,0 +1,37 @@
+use crate::prelude::*;
+use model::{Player, Camera, Ui};
+
+
+/// The second boat, along with its own camera and ui, while two players share the screen
+#[derive(Debug)]
+pub struct SplitScreen {
+	pub player: Player,
+	pub camera: Camera,
+	pub ui: Ui,
+}
+
+impl SplitScreen {
+	/// Starts the second boat alongside `first_player`
+	pub fn new(resources: &model::Resources, first_player: &Player) -> SplitScreen {
+		let mut player = Player::new();
+		player.heading = first_player.heading;
+		player.map_position = first_player.map_position
+			+ Vec2::from_angle(first_player.heading).perp() * 3.0;
+
+		SplitScreen {
+			player,
+			camera: Camera::new(),
+			ui: Ui::new(resources),
+		}
+	}
+}
+
+
+
+/// One local player's view of the world - their boat, camera and ui
+#[derive(Copy, Clone)]
+pub struct Viewer<'m> {
+	pub player: &'m Player,
+	pub camera: &'m Camera,
+	pub ui: &'m Ui,
+}
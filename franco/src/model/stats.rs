@@ -0,0 +1,264 @@
+use crate::prelude::*;
+use std::collections::HashMap;
+
+use crate::persist::KeyValues;
+use model::FriendName;
+
+pub const STATS_PATH: &str = "stats.txt";
+pub const ACHIEVEMENTS_PATH: &str = "assets/achievements.txt";
+
+/// How close a friend must be for one of its tricks to count as 'seen'
+pub const TRICK_SEEN_DISTANCE: f32 = 10.0;
+
+
+#[derive(Debug)]
+pub struct Stats {
+	pub session: VoyageStats,
+	pub lifetime: VoyageStats,
+
+	pub achievements: Vec<Achievement>,
+
+	/// Indices into `achievements`, in the order they were unlocked - waiting to be shown
+	pub pending_unlocks: Vec<usize>,
+}
+
+impl Stats {
+	pub fn new() -> Result<Stats> {
+		let achievements_src = std::fs::read_to_string(ACHIEVEMENTS_PATH)?;
+		let mut achievements = Achievement::parse_list(&achievements_src)?;
+
+		let saved = KeyValues::load(STATS_PATH)?;
+		let lifetime = VoyageStats::from_key_values(&saved);
+
+		for achievement in achievements.iter_mut() {
+			achievement.unlocked = saved.get_or(&format!("achievement.{}", achievement.id), false);
+		}
+
+		Ok(Stats {
+			session: VoyageStats::default(),
+			lifetime,
+
+			achievements,
+			pending_unlocks: Vec::new(),
+		})
+	}
+
+	pub fn save(&self) -> Result<()> {
+		let mut kv = KeyValues::new();
+		self.lifetime.write_key_values(&mut kv);
+
+		for achievement in self.achievements.iter().filter(|a| a.unlocked) {
+			kv.set(format!("achievement.{}", achievement.id), true);
+		}
+
+		kv.save(STATS_PATH)
+	}
+
+
+	pub fn record_travel(&mut self, distance: f32, speed: f32, dt: f32) {
+		for stats in [&mut self.session, &mut self.lifetime] {
+			stats.distance_sailed += distance;
+			stats.top_speed = stats.top_speed.max(speed);
+			stats.playtime += dt;
+		}
+	}
+
+	pub fn record_map_wrap(&mut self) {
+		self.session.map_wraps += 1;
+		self.lifetime.map_wraps += 1;
+	}
+
+	pub fn record_trick_seen(&mut self, name: FriendName) {
+		*self.session.tricks_seen.entry(name).or_default() += 1;
+		*self.lifetime.tricks_seen.entry(name).or_default() += 1;
+	}
+
+	pub fn record_pickup(&mut self) {
+		self.session.pickups_collected += 1;
+		self.lifetime.pickups_collected += 1;
+	}
+
+	pub fn record_friend_met(&mut self, name: FriendName) {
+		let time = self.session.playtime;
+		self.session.time_to_meet.entry(name).or_insert(time);
+
+		let best_time = self.lifetime.time_to_meet.entry(name).or_insert(time);
+		*best_time = best_time.min(time);
+	}
+
+
+	/// Unlocks any achievements whose conditions are now met, queueing them in `pending_unlocks`
+	pub fn update_achievements(&mut self) {
+		for (idx, achievement) in self.achievements.iter_mut().enumerate() {
+			if achievement.unlocked {
+				continue
+			}
+
+			let stats = match achievement.scope {
+				StatScope::Session => &self.session,
+				StatScope::Lifetime => &self.lifetime,
+			};
+
+			if stats.value(achievement.stat) >= achievement.threshold {
+				achievement.unlocked = true;
+				self.pending_unlocks.push(idx);
+			}
+		}
+	}
+}
+
+
+
+#[derive(Debug, Default, Clone)]
+pub struct VoyageStats {
+	pub distance_sailed: f32,
+	pub top_speed: f32,
+	pub playtime: f32,
+	pub map_wraps: u32,
+	pub pickups_collected: u32,
+
+	/// Seconds of play before each friend was met. For lifetime stats this is the best time
+	pub time_to_meet: HashMap<FriendName, f32>,
+	pub tricks_seen: HashMap<FriendName, u32>,
+}
+
+impl VoyageStats {
+	pub fn value(&self, stat: StatKey) -> f32 {
+		match stat {
+			StatKey::DistanceSailed => self.distance_sailed,
+			StatKey::TopSpeed => self.top_speed,
+			StatKey::Playtime => self.playtime,
+			StatKey::MapWraps => self.map_wraps as f32,
+			StatKey::PickupsCollected => self.pickups_collected as f32,
+			StatKey::FriendsMet => self.time_to_meet.len() as f32,
+			StatKey::TricksSeen => self.tricks_seen.values().sum::<u32>() as f32,
+		}
+	}
+
+	/// Keys for unknown friends, e.g., from an older version, are dropped along with anything that doesn't parse
+	fn from_key_values(kv: &KeyValues) -> VoyageStats {
+		let time_to_meet = kv.with_prefix("time_to_meet.")
+			.filter_map(|(name, value)| Some((FriendName::try_from_name(name)?, value.parse().ok()?)))
+			.collect();
+
+		let tricks_seen = kv.with_prefix("tricks_seen.")
+			.filter_map(|(name, value)| Some((FriendName::try_from_name(name)?, value.parse().ok()?)))
+			.collect();
+
+		VoyageStats {
+			distance_sailed: kv.get_or("distance_sailed", 0.0),
+			top_speed: kv.get_or("top_speed", 0.0),
+			playtime: kv.get_or("playtime", 0.0),
+			map_wraps: kv.get_or("map_wraps", 0),
+			pickups_collected: kv.get_or("pickups_collected", 0),
+
+			time_to_meet,
+			tricks_seen,
+		}
+	}
+
+	fn write_key_values(&self, kv: &mut KeyValues) {
+		kv.set("distance_sailed", self.distance_sailed);
+		kv.set("top_speed", self.top_speed);
+		kv.set("playtime", self.playtime);
+		kv.set("map_wraps", self.map_wraps);
+		kv.set("pickups_collected", self.pickups_collected);
+
+		for (name, time) in self.time_to_meet.iter() {
+			kv.set(format!("time_to_meet.{}", name.as_str()), time);
+		}
+
+		for (name, count) in self.tricks_seen.iter() {
+			kv.set(format!("tricks_seen.{}", name.as_str()), count);
+		}
+	}
+}
+
+
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StatKey {
+	DistanceSailed,
+	TopSpeed,
+	Playtime,
+	MapWraps,
+	PickupsCollected,
+	FriendsMet,
+	TricksSeen,
+}
+
+impl StatKey {
+	pub fn from_name(name: &str) -> Option<StatKey> {
+		match name {
+			"distance_sailed" => Some(StatKey::DistanceSailed),
+			"top_speed" => Some(StatKey::TopSpeed),
+			"playtime" => Some(StatKey::Playtime),
+			"map_wraps" => Some(StatKey::MapWraps),
+			"pickups_collected" => Some(StatKey::PickupsCollected),
+			"friends_met" => Some(StatKey::FriendsMet),
+			"tricks_seen" => Some(StatKey::TricksSeen),
+			_ => None,
+		}
+	}
+}
+
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StatScope {
+	Session,
+	Lifetime,
+}
+
+
+
+#[derive(Debug)]
+pub struct Achievement {
+	pub id: String,
+	pub title: String,
+
+	pub scope: StatScope,
+	pub stat: StatKey,
+	pub threshold: f32,
+
+	pub unlocked: bool,
+}
+
+impl Achievement {
+	/// Parses one achievement per line, in the form `id | title | scope | stat | threshold`
+	pub fn parse_list(source: &str) -> Result<Vec<Achievement>> {
+		source.lines()
+			.map(str::trim)
+			.filter(|line| !line.is_empty() && !line.starts_with('#'))
+			.map(Achievement::parse)
+			.collect()
+	}
+
+	fn parse(line: &str) -> Result<Achievement> {
+		let fields: Vec<_> = line.split('|').map(str::trim).collect();
+
+		let (id, title, scope, stat, threshold) = match fields[..] {
+			[id, title, scope, stat, threshold] => (id, title, scope, stat, threshold),
+			_ => return Err(format!("Malformed achievement '{}'", line).into()),
+		};
+
+		let scope = match scope {
+			"session" => StatScope::Session,
+			"lifetime" => StatScope::Lifetime,
+			_ => return Err(format!("Unknown stat scope '{}' in achievement '{}'", scope, id).into()),
+		};
+
+		let stat = StatKey::from_name(stat)
+			.ok_or_else(|| format!("Unknown stat '{}' in achievement '{}'", stat, id))?;
+
+		Ok(Achievement {
+			id: id.to_owned(),
+			title: title.to_owned(),
+
+			scope,
+			stat,
+			threshold: threshold.parse()?,
+
+			unlocked: false,
+		})
+	}
+}
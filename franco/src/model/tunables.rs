@@ -0,0 +1,226 @@
+use crate::prelude::*;
+
+use crate::persist::KeyValues;
+
+pub const TUNABLES_PATH: &str = "tunables.txt";
+
+
+/// Gameplay balance, loaded from `tunables.txt` and reloaded whenever it changes on disk.
+/// Anything missing from the file keeps its default.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tunables {
+	/// The number of sail settings above anchored
+	pub max_sail_speed: i32,
+
+	/// The speed a boat settles at for each sail setting, in map units per second
+	pub speed_per_sail: f32,
+
+	/// Turning slows as a boat speeds up - the wheel's effect is divided by `1 + speed * turn_falloff`,
+	/// but never drops below `min_turn_factor`
+	pub turn_falloff: f32,
+	pub min_turn_factor: f32,
+
+	/// How close a boat has to get to a friend to meet them
+	pub player_meet_threshold: f32,
+
+	/// How close to a button a click has to be to press it, in ui units
+	pub button_interact_dist: f32,
+
+	pub min_orbit_zoom: f32,
+	pub max_orbit_zoom: f32,
+	pub orbit_zoom_step: f32,
+
+	pub fog_start: f32,
+	pub fog_distance: f32,
+
+	/// Where fog starts once the world has fully faded into the horizon between levels
+	pub horizon_fog_start: f32,
+
+	/// Ripples wrap around the boat once they're this far away, in world units
+	pub ripple_wrap_distance: f32,
+
+	/// Game state timers, in seconds
+	pub pre_game_time: f32,
+	pub start_time: f32,
+	pub got_friend_time: f32,
+	pub ending_time: f32,
+	pub race_countdown_time: f32,
+	pub race_finished_time: f32,
+
+	/// How long the boat sails into the horizon before the next level loads, and how long the fog takes to clear after
+	pub level_leave_time: f32,
+	pub level_arrive_time: f32,
+}
+
+impl Default for Tunables {
+	fn default() -> Tunables {
+		Tunables {
+			max_sail_speed: 5,
+			speed_per_sail: 0.5,
+
+			turn_falloff: 0.5,
+			min_turn_factor: 0.1,
+
+			player_meet_threshold: 4.0,
+			button_interact_dist: 1.2,
+
+			min_orbit_zoom: 2.0,
+			max_orbit_zoom: 100.0,
+			orbit_zoom_step: 1.2,
+
+			fog_start: 80.0,
+			fog_distance: 200.0,
+			horizon_fog_start: -20.0,
+
+			ripple_wrap_distance: 47.0,
+
+			pre_game_time: 1.0,
+			start_time: 3.0,
+			got_friend_time: 2.0,
+			ending_time: 3.0,
+			race_countdown_time: 3.0,
+			race_finished_time: 3.0,
+
+			level_leave_time: 4.0,
+			level_arrive_time: 2.5,
+		}
+	}
+}
+
+impl Tunables {
+	/// Values that don't parse, and keys that aren't recognised, are reported and otherwise ignored
+	/// so that a typo doesn't stop the game
+	pub fn load() -> Result<Tunables> {
+		let kv = KeyValues::load(TUNABLES_PATH)?;
+		Ok(Tunables::from_key_values(&kv))
+	}
+
+	fn from_key_values(kv: &KeyValues) -> Tunables {
+		let mut tunables = Tunables::default();
+		let mut fields = tunables.fields();
+
+		for (key, field) in fields.iter_mut() {
+			let value: String = match kv.get(key) {
+				Some(value) => value,
+				None => continue,
+			};
+
+			let parsed = match field {
+				TunableField::Int(field) => value.parse().map(|value| **field = value).is_ok(),
+				TunableField::Float(field) => value.parse().map(|value| **field = value).is_ok(),
+			};
+
+			if !parsed {
+				eprintln!("Tunable '{}' has invalid value '{}'", key, value);
+			}
+		}
+
+		for (key, _) in kv.with_prefix("") {
+			if !fields.iter().any(|(known, _)| *known == key) {
+				eprintln!("Unknown tunable '{}'", key);
+			}
+		}
+
+		tunables
+	}
+
+	fn fields(&mut self) -> [(&'static str, TunableField<'_>); 21] {
+		use TunableField::*;
+
+		[
+			("sailing.max_sail_speed", Int(&mut self.max_sail_speed)),
+			("sailing.speed_per_sail", Float(&mut self.speed_per_sail)),
+			("sailing.turn_falloff", Float(&mut self.turn_falloff)),
+			("sailing.min_turn_factor", Float(&mut self.min_turn_factor)),
+
+			("friends.meet_threshold", Float(&mut self.player_meet_threshold)),
+			("ui.button_interact_dist", Float(&mut self.button_interact_dist)),
+
+			("camera.min_orbit_zoom", Float(&mut self.min_orbit_zoom)),
+			("camera.max_orbit_zoom", Float(&mut self.max_orbit_zoom)),
+			("camera.orbit_zoom_step", Float(&mut self.orbit_zoom_step)),
+
+			("world.fog_start", Float(&mut self.fog_start)),
+			("world.fog_distance", Float(&mut self.fog_distance)),
+			("world.horizon_fog_start", Float(&mut self.horizon_fog_start)),
+			("world.ripple_wrap_distance", Float(&mut self.ripple_wrap_distance)),
+
+			("timers.pre_game", Float(&mut self.pre_game_time)),
+			("timers.start", Float(&mut self.start_time)),
+			("timers.got_friend", Float(&mut self.got_friend_time)),
+			("timers.ending", Float(&mut self.ending_time)),
+			("timers.race_countdown", Float(&mut self.race_countdown_time)),
+			("timers.race_finished", Float(&mut self.race_finished_time)),
+			("timers.level_leave", Float(&mut self.level_leave_time)),
+			("timers.level_arrive", Float(&mut self.level_arrive_time)),
+		]
+	}
+}
+
+
+enum TunableField<'t> {
+	Int(&'t mut i32),
+	Float(&'t mut f32),
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_from_key_values() {
+		let kv = KeyValues::parse("
+			sailing.max_sail_speed = 7
+			sailing.speed_per_sail = 2.5
+			timers.ending = 10
+		");
+
+		let tunables = Tunables::from_key_values(&kv);
+		let defaults = Tunables::default();
+
+		// Every value differs from its default, so each is known to have been parsed
+		assert_ne!(defaults.max_sail_speed, 7);
+		assert_ne!(defaults.speed_per_sail, 2.5);
+		assert_ne!(defaults.ending_time, 10.0);
+
+		assert_eq!(tunables.max_sail_speed, 7);
+		assert_eq!(tunables.speed_per_sail, 2.5);
+		assert_eq!(tunables.ending_time, 10.0);
+		assert_eq!(tunables.start_time, defaults.start_time);
+	}
+
+	#[test]
+	fn test_from_key_values_bad_entries() {
+		let kv = KeyValues::parse("
+			sailing.max_sail_speed = 2.5
+			sailing.turn_falloff = fast
+			sailing.not_a_tunable = 1
+			timers.start = 4
+		");
+
+		// Invalid values keep their defaults, and unknown keys don't stop the rest from loading
+		let tunables = Tunables::from_key_values(&kv);
+		let defaults = Tunables::default();
+
+		assert_eq!(tunables.max_sail_speed, defaults.max_sail_speed);
+		assert_eq!(tunables.turn_falloff, defaults.turn_falloff);
+		assert_eq!(tunables.start_time, 4.0);
+	}
+
+	#[test]
+	fn test_empty_is_default() {
+		assert_eq!(Tunables::from_key_values(&KeyValues::new()), Tunables::default());
+	}
+
+	#[test]
+	fn test_field_keys_unique() {
+		let mut tunables = Tunables::default();
+		let fields = tunables.fields();
+
+		for (idx, (key, _)) in fields.iter().enumerate() {
+			assert!(fields[idx+1..].iter().all(|(other, _)| other != key), "Duplicate tunable '{}'", key);
+		}
+	}
+}
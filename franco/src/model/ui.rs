@@ -1,74 +1,197 @@
 use crate::prelude::*;
 
-pub mod wheel;
-pub use wheel::*;
+pub mod layout;
+pub use layout::*;
 
-pub mod map;
-pub use map::*;
+pub mod widget;
+pub use widget::*;
+
+pub mod hud;
+pub use hud::*;
+
+pub mod toast;
+pub use toast::*;
+
+pub mod stats_screen;
+pub use stats_screen::*;
+
+pub mod pause_menu;
+pub use pause_menu::*;
+
+pub mod journal;
+pub use journal::*;
+
+pub mod trust_meter;
+pub use trust_meter::*;
+
+pub mod pickup_counter;
+pub use pickup_counter::*;
+
+pub mod port_panel;
+pub use port_panel::*;
+
+pub mod instruments;
+pub use instruments::*;
+
+pub mod hints;
+pub use hints::*;
 
 pub const UI_SAFE_REGION: f32 = 10.0;
 
+/// Ui units scale with the viewport, so on a physically small display they'd get too small to read or hit.
+/// Viewports shorter than this many inches scale the ui up to make up the difference.
+pub const UI_MIN_PHYSICAL_SIZE: f32 = 5.0;
+pub const UI_MAX_DPI_SCALE: f32 = 2.0;
+
 
 #[derive(Debug)]
 pub struct Ui {
-	pub aspect: f32,
-	pub map_button: Button,
-	pub sail_button: Button,
-	pub zoom_in_button: Button,
-	pub zoom_out_button: Button,
-	pub anchor_button: Button,
-	pub wheel: Wheel,
-	pub map: Map,
-
-	pub dragging_unclaimed_area: bool,
+	/// The region of the window this ui is drawn into, and how ui units map onto it
+	pub viewport: gfx::Viewport,
+	pub metrics: UiMetrics,
+
+	pub layout: Layout,
+	pub widgets: WidgetTree,
+	pub hud: HudWidgets,
+
+	pub journal: Journal,
+	pub toast: Toast,
+	pub trust_meter: TrustMeter,
+	pub pickup_counter: PickupCounter,
+	pub port_panel: PortPanel,
+	pub instruments: Instruments,
+	pub hints: Hints,
+	pub stats_screen: StatsScreen,
 }
 
 impl Ui {
 	pub fn new(resources: &model::Resources) -> Ui {
+		// The open map blocks the area it's drawn into - the reference entity's scale is half its size
+		let ui_scene = resources.main_project.find_scene("ui").unwrap();
+		let map_size = ui_scene.find_entity("REF_usable_area").unwrap().scale.to_xy() * 2.0;
+
+		let mut layout = Layout::new();
+		let mut widgets = WidgetTree::new();
+		let hud = HudWidgets::build(&mut widgets, &mut layout, map_size);
+
 		Ui {
-			aspect: 1.0,
-			map_button: Button {
-				position: UiPosition::TopLeft(Vec2::splat(2.0)),
-				state: UiPanelState::Closed,
-			},
-
-			sail_button: Button {
-				position: UiPosition::BottomRight(Vec2::new(2.0, 4.0)),
-				state: UiPanelState::Closed,
-			},
-
-			anchor_button: Button {
-				position: UiPosition::BottomRight(Vec2::new(2.0, 1.5)),
-				state: UiPanelState::Closed,
-			},
-
-			zoom_in_button: Button {
-				position: UiPosition::TopRight(Vec2::new(3.0, 1.5)),
-				state: UiPanelState::Closed,
-			},
-
-			zoom_out_button: Button {
-				position: UiPosition::TopRight(Vec2::new(1.5, 1.5)),
-				state: UiPanelState::Closed,
-			},
-
-			wheel: Wheel::new(),
-			map: Map::new(),
-
-			dragging_unclaimed_area: false,
+			viewport: gfx::Viewport::full(),
+			metrics: UiMetrics::new(1.0, 1.0),
+
+			layout,
+			widgets,
+			hud,
+
+			journal: Journal::new(),
+			toast: Toast::new(),
+			trust_meter: TrustMeter::new(),
+			pickup_counter: PickupCounter::new(),
+			port_panel: PortPanel::new(),
+			instruments: Instruments::new(),
+			hints: Hints::new(),
+			stats_screen: StatsScreen::new(),
 		}
 	}
+
+	/// The map and journal share the middle of the screen, so opening one closes the other
+	pub fn toggle_map(&mut self) {
+		self.hud.toggle_map(&mut self.widgets);
+
+		if self.map_state().is_open() {
+			self.journal.close();
+		}
+	}
+
+	pub fn toggle_journal(&mut self) {
+		self.journal.toggle();
+
+		if self.journal.state.is_open() {
+			self.widgets[self.hud.map].state.close(0.3);
+		}
+	}
+
+	pub fn map_state(&self) -> UiPanelState {
+		self.hud.map_state(&self.widgets)
+	}
+
+	pub fn wheel_angle(&self) -> f32 {
+		self.hud.wheel_angle(&self.widgets)
+	}
+
+	/// Maps a pointer position from whole window space into ui units within this ui's viewport.
+	/// Pointer positions use the input system convention, where the shortest axis spans [-1, 1].
+	pub fn window_to_ui(&self, pos: Vec2, window_aspect: f32) -> Vec2 {
+		let normalised = (pos / screen_extents(window_aspect) + Vec2::splat(1.0)) / 2.0;
+
+		let gfx::Viewport{min, max} = self.viewport;
+		let local = (normalised - min) / (max - min);
+
+		(local * 2.0 - Vec2::splat(1.0)) * self.metrics.extents()
+	}
+
+	/// Whether `pos`, in ui units, lies within this ui's viewport
+	pub fn contains(&self, pos: Vec2) -> bool {
+		let extents = self.metrics.extents();
+		pos.x.abs() <= extents.x && pos.y.abs() <= extents.y
+	}
 }
 
 
 
+/// How ui units map onto a viewport
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct UiMetrics {
+	pub aspect: f32,
 
-#[derive(Debug)]
-pub struct Button {
-	pub position: UiPosition,
-	pub state: UiPanelState,
+	/// Larger values make the ui larger
+	pub scale: f32,
 }
 
+impl UiMetrics {
+	pub fn new(aspect: f32, scale: f32) -> UiMetrics {
+		UiMetrics { aspect, scale }
+	}
+
+	/// Metrics for a viewport `size` pixels big, with the user's `scale` adjusted for the density of the display
+	pub fn for_viewport(size: Vec2i, dpi: Option<f32>, scale: f32) -> UiMetrics {
+		let aspect = size.x as f32 / size.y.max(1) as f32;
+
+		let dpi_scale = dpi.map_or(1.0, |dpi| {
+			let physical_size = size.x.min(size.y).max(1) as f32 / dpi;
+			(UI_MIN_PHYSICAL_SIZE / physical_size).clamp(1.0, UI_MAX_DPI_SCALE)
+		});
+
+		UiMetrics::new(aspect, scale * dpi_scale)
+	}
+
+	/// The size of the shortest axis of the viewport, from the center to the edge, in ui units
+	pub fn safe_region(&self) -> f32 {
+		UI_SAFE_REGION / self.scale
+	}
+
+	/// The size of the viewport from the center to the edges in ui units
+	pub fn extents(&self) -> Vec2 {
+		screen_extents(self.aspect) * self.safe_region()
+	}
+
+	/// Maps a pointer position over the whole window into ui units, for ui that covers the whole window
+	pub fn pointer_to_ui(&self, pos: Vec2) -> Vec2 {
+		pos * self.safe_region()
+	}
+}
+
+
+/// The extents of the screen in units where the shortest axis spans [-1, 1]
+fn screen_extents(aspect: f32) -> Vec2 {
+	if aspect < 1.0 {
+		Vec2::new(1.0, 1.0 / aspect)
+	} else {
+		Vec2::new(aspect, 1.0)
+	}
+}
+
+
+
 
 #[derive(Copy, Clone, Debug)]
 pub enum UiPosition {
@@ -84,12 +207,8 @@ pub enum UiPosition {
 }
 
 impl UiPosition {
-	pub fn resolve(&self, aspect: f32) -> Vec2 {
-		let screen_extents = if aspect < 1.0 {
-			Vec2::new(UI_SAFE_REGION, UI_SAFE_REGION / aspect)
-		} else {
-			Vec2::new(UI_SAFE_REGION * aspect, UI_SAFE_REGION)
-		};
+	pub fn resolve(&self, metrics: UiMetrics) -> Vec2 {
+		let screen_extents = metrics.extents();
 
 		let corner_dir = match self {
 			UiPosition::TopLeft(_) => Vec2::new(-1.0, 1.0),
@@ -114,12 +233,12 @@ impl UiPosition {
 		}
 	}
 
-	pub fn diff_to(&self, other: Vec2, aspect: f32) -> Vec2 {
-		other - self.resolve(aspect)
+	pub fn diff_to(&self, other: Vec2, metrics: UiMetrics) -> Vec2 {
+		other - self.resolve(metrics)
 	}
 
-	pub fn distance_to(&self, other: Vec2, aspect: f32) -> f32 {
-		self.diff_to(other, aspect).length()
+	pub fn distance_to(&self, other: Vec2, metrics: UiMetrics) -> f32 {
+		self.diff_to(other, metrics).length()
 	}
 }
 
@@ -0,0 +1,133 @@
+use crate::prelude::*;
+
+/// How long a hint stays up once its rule has fired, unless the player follows it first
+pub const HINT_DISPLAY_TIME: f32 = 8.0;
+
+/// Time between one hint going away and the next being allowed to appear
+pub const HINT_COOLDOWN: f32 = 4.0;
+
+
+/// Something a new player might not discover on their own
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HintKind {
+	/// Sitting at anchor - the sail button, or W, gets the boat moving
+	RaiseSail,
+
+	/// Sailing in a straight line - the wheel can be dragged
+	SteerWheel,
+
+	/// Never looked at the map
+	OpenMap,
+}
+
+impl HintKind {
+	pub const ALL: [HintKind; 3] = [
+		HintKind::RaiseSail,
+		HintKind::SteerWheel,
+		HintKind::OpenMap,
+	];
+
+	/// How long the hint's rule has to hold before the hint is shown
+	pub fn delay(&self) -> f32 {
+		match self {
+			HintKind::RaiseSail => 5.0,
+			HintKind::SteerWheel => 10.0,
+			HintKind::OpenMap => 60.0,
+		}
+	}
+
+	/// Whether the player is in the situation the hint is for
+	pub fn applies(&self, player: &model::Player) -> bool {
+		match self {
+			HintKind::RaiseSail => matches!(player.sail_state, model::SailState::Anchored) && !player.harbour.is_in_port(),
+			HintKind::SteerWheel => matches!(player.sail_state, model::SailState::Sailing{..}),
+			HintKind::OpenMap => true,
+		}
+	}
+
+	/// Whether the player has done what the hint would suggest, hinted or not
+	pub fn is_followed(&self, player: &model::Player, ui: &model::Ui) -> bool {
+		match self {
+			HintKind::RaiseSail => matches!(player.sail_state, model::SailState::Sailing{..}),
+			HintKind::SteerWheel => ui.wheel_angle().abs() > 0.3,
+			HintKind::OpenMap => ui.map_state().is_open(),
+		}
+	}
+
+	/// Where the hint points
+	pub fn target(&self, ui: &model::Ui) -> model::UiPosition {
+		match self {
+			HintKind::RaiseSail => ui.widgets[ui.hud.sail_button].position,
+			HintKind::SteerWheel => ui.widgets[ui.hud.wheel].position,
+			HintKind::OpenMap => ui.widgets[ui.hud.map_button].position,
+		}
+	}
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			HintKind::RaiseSail => "raise_sail",
+			HintKind::SteerWheel => "steer_wheel",
+			HintKind::OpenMap => "open_map",
+		}
+	}
+
+	pub fn from_name(name: &str) -> Option<HintKind> {
+		HintKind::ALL.iter().copied().find(|kind| kind.name() == name)
+	}
+}
+
+
+/// Watches for moments when a hint would help, and shows one at a time
+#[derive(Debug)]
+pub struct Hints {
+	pub state: model::UiPanelState,
+	pub active: Option<HintKind>,
+
+	/// Counts down while a hint is shown, then while waiting to show the next
+	pub timer: f32,
+
+	/// How long each rule in `HintKind::ALL` has held for
+	pub rule_timers: [f32; 3],
+
+	/// Drives the pulsing of hinted buttons and the bobbing of the pointer
+	pub pulse_phase: f32,
+}
+
+impl Hints {
+	pub fn new() -> Hints {
+		Hints {
+			state: model::UiPanelState::Closed,
+			active: None,
+			timer: 0.0,
+			rule_timers: [0.0; 3],
+			pulse_phase: 0.0,
+		}
+	}
+
+	pub fn show(&mut self, kind: HintKind) {
+		self.active = Some(kind);
+		self.timer = HINT_DISPLAY_TIME;
+		self.state.open(0.4);
+	}
+
+	/// Leaves the active hint in place while the panel slides away
+	pub fn dismiss(&mut self) {
+		if self.state.is_open() {
+			self.timer = HINT_COOLDOWN;
+			self.state.close(0.3);
+		}
+	}
+
+	/// How strongly `kind`'s target should pulse, from 0 to 1
+	pub fn pulse(&self, kind: HintKind) -> f32 {
+		match self.active == Some(kind) {
+			true => self.state.as_phase() * (self.pulse_phase * TAU).sin().abs(),
+			false => 0.0,
+		}
+	}
+
+	pub fn position(&self) -> model::UiPosition {
+		let phase = self.state.as_phase();
+		model::UiPosition::Top(phase.ease_back_out(-1.0, 3.8))
+	}
+}
@@ -0,0 +1,161 @@
+use crate::prelude::*;
+use model::{UiPanelState, UiPosition, Widget, WidgetId, WidgetKind, WidgetShape, WidgetTree};
+use model::{Arrangement, Extent, Layout, LayoutId, LayoutNode};
+
+/// The wheel is grabbed anywhere within this distance of its center
+pub const WHEEL_GRAB_RADIUS: f32 = 4.0;
+
+
+/// Something the heads up display can ask of the boat, camera or ui - by button or by key
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HudAction {
+	ToggleMap,
+	ToggleJournal,
+	RaiseSail,
+	LowerSail,
+	ZoomIn,
+	ZoomOut,
+}
+
+
+/// The widgets making up a player's heads up display
+#[derive(Debug)]
+pub struct HudWidgets {
+	pub map_button: WidgetId,
+	pub journal_button: WidgetId,
+	pub sail_button: WidgetId,
+	pub anchor_button: WidgetId,
+	pub zoom_in_button: WidgetId,
+	pub zoom_out_button: WidgetId,
+
+	pub wheel: WidgetId,
+
+	/// Covers the middle of the screen while open, blocking everything beneath it
+	pub map: WidgetId,
+
+	/// Which layout node each button is placed by
+	placements: Vec<(WidgetId, LayoutId)>,
+}
+
+impl HudWidgets {
+	pub fn build(widgets: &mut WidgetTree, layout: &mut Layout, map_size: Vec2) -> HudWidgets {
+		let root = widgets.root();
+		let layout_root = layout.root();
+
+		let stack = |anchor, padding, arrangement| LayoutNode::new(Extent::Fit, Extent::Fit)
+			.anchored(anchor)
+			.with_padding(padding)
+			.with_arrangement(arrangement);
+
+		let top_left = layout.add(layout_root, stack(Vec2::new(-1.0, 1.0), Vec2::splat(1.0), Arrangement::VerticalStack{spacing: 0.5}));
+		let top_right = layout.add(layout_root, stack(Vec2::new(1.0, 1.0), Vec2::splat(1.0), Arrangement::HorizontalStack{spacing: 0.5}));
+		let bottom_right = layout.add(layout_root, stack(Vec2::new(1.0, -1.0), Vec2::new(1.0, 0.5), Arrangement::VerticalStack{spacing: 0.5}));
+
+		let mut placements = Vec::new();
+
+		// Positions are filled in by `apply_layout`, and shapes replaced every frame by `update_shapes`
+		let mut add_button = |stack, size| {
+			let button = widgets.add(root, Widget::new(WidgetKind::Button, UiPosition::Center(Vec2::zero()), WidgetShape::Circle(1.0)));
+			placements.push((button, layout.add(stack, LayoutNode::sized(Vec2::splat(size)))));
+			button
+		};
+
+		let map_button = add_button(top_left, 2.0);
+		let journal_button = add_button(top_left, 2.0);
+		let zoom_in_button = add_button(top_right, 1.0);
+		let zoom_out_button = add_button(top_right, 1.0);
+		let sail_button = add_button(bottom_right, 2.0);
+		let anchor_button = add_button(bottom_right, 2.0);
+
+		let mut wheel = Widget::new(WidgetKind::Dial{angle: 0.0}, UiPosition::Bottom(0.0), WidgetShape::Circle(WHEEL_GRAB_RADIUS));
+		wheel.focusable = false;
+		let wheel = widgets.add(root, wheel);
+
+		let map = widgets.add(root, Widget::new(WidgetKind::Panel, UiPosition::Center(Vec2::zero()), WidgetShape::Rect(map_size)));
+
+		HudWidgets {
+			map_button,
+			journal_button,
+			sail_button,
+			anchor_button,
+			zoom_in_button,
+			zoom_out_button,
+			wheel,
+			map,
+
+			placements,
+		}
+	}
+
+	/// Moves buttons to where the layout last placed them
+	pub fn apply_layout(&self, layout: &Layout, widgets: &mut WidgetTree) {
+		for &(button, node) in self.placements.iter() {
+			widgets[button].position = UiPosition::Center(layout.rect(node).center);
+		}
+	}
+
+	pub fn buttons(&self) -> [(WidgetId, HudAction); 6] {
+		[
+			(self.map_button, HudAction::ToggleMap),
+			(self.journal_button, HudAction::ToggleJournal),
+			(self.sail_button, HudAction::RaiseSail),
+			(self.anchor_button, HudAction::LowerSail),
+			(self.zoom_in_button, HudAction::ZoomIn),
+			(self.zoom_out_button, HudAction::ZoomOut),
+		]
+	}
+
+	pub fn action(&self, id: WidgetId) -> Option<HudAction> {
+		self.buttons().iter()
+			.find(|(button, _)| *button == id)
+			.map(|&(_, action)| action)
+	}
+
+	pub fn button(&self, action: HudAction) -> WidgetId {
+		self.buttons().iter()
+			.find(|(_, button_action)| *button_action == action)
+			.map(|&(button, _)| button)
+			.unwrap()
+	}
+
+	/// Button hit areas are tunable, so they can change while the game is running
+	pub fn update_shapes(&self, widgets: &mut WidgetTree, tunables: &model::Tunables) {
+		for (button, _) in self.buttons() {
+			widgets[button].shape = WidgetShape::Circle(tunables.button_interact_dist);
+		}
+	}
+
+	/// The wheel rises up from the bottom of the screen as it's highlighted
+	pub fn update_wheel(&self, widgets: &mut WidgetTree) {
+		let phase = widgets[self.wheel].state.as_phase();
+		widgets[self.wheel].position = UiPosition::Bottom(phase * 2.0);
+	}
+
+	pub fn toggle_map(&self, widgets: &mut WidgetTree) {
+		let state = &mut widgets[self.map].state;
+
+		if state.is_open() {
+			state.close(0.3);
+		} else {
+			state.open(0.4);
+		}
+	}
+
+	pub fn map_state(&self, widgets: &WidgetTree) -> UiPanelState {
+		widgets[self.map].state
+	}
+
+	pub fn wheel_angle(&self, widgets: &WidgetTree) -> f32 {
+		match widgets[self.wheel].kind {
+			WidgetKind::Dial{angle} => angle,
+			_ => 0.0,
+		}
+	}
+
+	pub fn wheel_angle_mut<'w>(&self, widgets: &'w mut WidgetTree) -> Option<&'w mut f32> {
+		match &mut widgets[self.wheel].kind {
+			WidgetKind::Dial{angle} => Some(angle),
+			_ => None,
+		}
+	}
+}
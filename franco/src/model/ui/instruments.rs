@@ -0,0 +1,64 @@
+use crate::prelude::*;
+
+/// Full scale on the speedometer - a little over the top speed under full sail
+pub const SPEEDOMETER_MAX: f32 = 3.0;
+
+/// How quickly needles swing towards their readings, as a fraction of the difference per second
+const INSTRUMENT_RESPONSE: f32 = 6.0;
+
+
+/// Gauges for the boat's sails, speed and heading, tucked into the corners of the HUD
+#[derive(Debug)]
+pub struct Instruments {
+	pub state: model::UiPanelState,
+
+	/// Readings follow the boat with a little lag, so needles swing rather than snap.
+	/// The sail level goes from 0 when anchored to 1 under full sail.
+	pub sail_level: f32,
+	pub speed: f32,
+	pub heading: f32,
+}
+
+impl Instruments {
+	pub fn new() -> Instruments {
+		Instruments {
+			state: model::UiPanelState::Closed,
+			sail_level: 0.0,
+			speed: 0.0,
+			heading: 0.0,
+		}
+	}
+
+	/// Follows `player`'s boat, and tucks the gauges away while `visible` isn't set
+	pub fn track(&mut self, player: &model::Player, tunables: &model::Tunables, visible: bool) {
+		let response = (INSTRUMENT_RESPONSE / 60.0).min(1.0);
+		let sail_level = player.sail_state.speed() as f32 / tunables.max_sail_speed.max(1) as f32;
+		let heading_diff = (player.heading - self.heading + PI).rem_euclid(TAU) - PI;
+
+		self.sail_level += (sail_level - self.sail_level) * response;
+		self.speed += (player.speed - self.speed) * response;
+		self.heading += heading_diff * response;
+
+		match visible {
+			true => self.state.open(0.5),
+			false => self.state.close(0.3),
+		}
+
+		self.state.update();
+	}
+
+	pub fn sail_gauge_position(&self) -> model::UiPosition {
+		let phase = self.state.as_phase();
+		model::UiPosition::BottomRight(Vec2::new(7.0, phase.ease_back_out(-2.0, 2.0)))
+	}
+
+	pub fn speedometer_position(&self) -> model::UiPosition {
+		let phase = self.state.as_phase();
+		model::UiPosition::BottomRight(Vec2::new(4.8, phase.ease_back_out(-2.0, 2.0)))
+	}
+
+	pub fn compass_position(&self) -> model::UiPosition {
+		let phase = self.state.as_phase();
+		model::UiPosition::TopRight(Vec2::new(phase.ease_back_out(-2.0, 2.0), 4.5))
+	}
+}
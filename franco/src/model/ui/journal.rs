@@ -0,0 +1,56 @@
+use crate::prelude::*;
+
+use model::{FriendName, FriendMeeting};
+
+/// A record of every species of friend in the world, and what is known about each
+#[derive(Debug)]
+pub struct Journal {
+	pub state: model::UiPanelState,
+}
+
+/// What the journal knows about one species
+#[derive(Copy, Clone, Debug)]
+pub struct JournalEntry {
+	pub name: FriendName,
+
+	/// The first meeting with any friend of this species
+	pub meeting: Option<FriendMeeting>,
+}
+
+impl Journal {
+	pub fn new() -> Journal {
+		Journal {
+			state: model::UiPanelState::Closed,
+		}
+	}
+
+	pub fn toggle(&mut self) {
+		if self.state.is_open() {
+			self.close();
+		} else {
+			self.state.open(0.4);
+		}
+	}
+
+	pub fn close(&mut self) {
+		self.state.close(0.3);
+	}
+
+	pub fn entries(world: &model::World) -> Vec<JournalEntry> {
+		FriendName::ALL.iter()
+			.filter_map(|&name| {
+				let mut friends = world.friends.iter()
+					.filter(|friend| friend.name == name)
+					.peekable();
+
+				friends.peek()?;
+
+				let meeting = friends
+					.filter_map(|friend| friend.meeting)
+					.min_by(|a, b| a.time.total_cmp(&b.time));
+
+				Some(JournalEntry { name, meeting })
+			})
+			.collect()
+	}
+}
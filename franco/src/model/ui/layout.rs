@@ -0,0 +1,318 @@
+use crate::prelude::*;
+use model::UiMetrics;
+
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LayoutId(usize);
+
+
+/// How big a node is along one axis
+#[derive(Copy, Clone, Debug)]
+pub enum Extent {
+	/// In ui units
+	Fixed(f32),
+
+	/// A fraction of the space inside the parent's padding
+	Relative(f32),
+
+	/// Just big enough for the node's children and padding
+	Fit,
+
+	/// Shares out whatever space a stack has left along its direction. Everywhere else, fills the parent.
+	Stretch,
+}
+
+
+#[derive(Copy, Clone, Debug)]
+pub enum Arrangement {
+	/// Children are placed by their own anchors
+	Free,
+
+	/// Children are laid out left to right, and placed by their anchors vertically
+	HorizontalStack { spacing: f32 },
+
+	/// Children are laid out top to bottom, and placed by their anchors horizontally
+	VerticalStack { spacing: f32 },
+}
+
+
+/// A rectangle in ui units - the same space as `UiPosition::resolve` and the ui camera
+#[derive(Copy, Clone, Debug)]
+pub struct UiRect {
+	pub center: Vec2,
+	pub size: Vec2,
+}
+
+impl UiRect {
+	pub fn min(&self) -> Vec2 { self.center - self.size / 2.0 }
+	pub fn max(&self) -> Vec2 { self.center + self.size / 2.0 }
+
+	/// A point within the rect, from (-1, -1) at the bottom left to (1, 1) at the top right
+	pub fn point(&self, anchor: Vec2) -> Vec2 {
+		self.center + anchor * self.size / 2.0
+	}
+
+	pub fn shrink(&self, amount: Vec2) -> UiRect {
+		let size = self.size - amount * 2.0;
+
+		UiRect {
+			center: self.center,
+			size: Vec2::new(size.x.max(0.0), size.y.max(0.0)),
+		}
+	}
+}
+
+
+#[derive(Debug)]
+pub struct LayoutNode {
+	/// The point on the parent, from (-1, -1) at its bottom left to (1, 1) at its top right, that this node is attached to
+	pub anchor: Vec2,
+
+	/// The point on this node that sits on the anchor, in the same range
+	pub pivot: Vec2,
+
+	/// Applied after anchoring, in ui units
+	pub offset: Vec2,
+
+	pub width: Extent,
+	pub height: Extent,
+
+	/// Space kept clear around the inside edges, for children
+	pub padding: Vec2,
+	pub arrangement: Arrangement,
+
+	children: Vec<LayoutId>,
+}
+
+impl LayoutNode {
+	pub fn new(width: Extent, height: Extent) -> LayoutNode {
+		LayoutNode {
+			anchor: Vec2::zero(),
+			pivot: Vec2::zero(),
+			offset: Vec2::zero(),
+			width,
+			height,
+			padding: Vec2::zero(),
+			arrangement: Arrangement::Free,
+			children: Vec::new(),
+		}
+	}
+
+	/// A fixed size node
+	pub fn sized(size: Vec2) -> LayoutNode {
+		LayoutNode::new(Extent::Fixed(size.x), Extent::Fixed(size.y))
+	}
+
+	/// Attaches the node by the same point on both itself and its parent - so (-1, 1) sits it in the parent's top left corner
+	pub fn anchored(mut self, anchor: Vec2) -> LayoutNode {
+		self.anchor = anchor;
+		self.pivot = anchor;
+		self
+	}
+
+	pub fn with_offset(mut self, offset: Vec2) -> LayoutNode {
+		self.offset = offset;
+		self
+	}
+
+	pub fn with_padding(mut self, padding: Vec2) -> LayoutNode {
+		self.padding = padding;
+		self
+	}
+
+	pub fn with_arrangement(mut self, arrangement: Arrangement) -> LayoutNode {
+		self.arrangement = arrangement;
+		self
+	}
+}
+
+
+/// A tree of nodes sized and positioned relative to the viewport and to each other.
+/// Rects are only recomputed when the metrics they were computed for change, i.e., on resize or rescale.
+#[derive(Debug)]
+pub struct Layout {
+	nodes: Vec<LayoutNode>,
+	rects: Vec<UiRect>,
+	computed_for: Option<UiMetrics>,
+}
+
+impl Layout {
+	/// Starts with a root node covering the whole viewport
+	pub fn new() -> Layout {
+		let root_rect = UiRect { center: Vec2::zero(), size: Vec2::zero() };
+
+		Layout {
+			nodes: vec![LayoutNode::new(Extent::Stretch, Extent::Stretch)],
+			rects: vec![root_rect],
+			computed_for: None,
+		}
+	}
+
+	pub fn root(&self) -> LayoutId {
+		LayoutId(0)
+	}
+
+	pub fn add(&mut self, parent: LayoutId, node: LayoutNode) -> LayoutId {
+		let id = LayoutId(self.nodes.len());
+
+		self.nodes.push(node);
+		self.rects.push(self.rects[parent.0]);
+		self.nodes[parent.0].children.push(id);
+
+		// Force the new node to be placed
+		self.computed_for = None;
+		id
+	}
+
+	pub fn rect(&self, id: LayoutId) -> UiRect {
+		self.rects[id.0]
+	}
+
+	/// Returns whether anything was recomputed
+	pub fn compute(&mut self, metrics: UiMetrics) -> bool {
+		if self.computed_for == Some(metrics) {
+			return false
+		}
+
+		let root_rect = UiRect {
+			center: Vec2::zero(),
+			size: metrics.extents() * 2.0,
+		};
+
+		self.place(self.root(), root_rect);
+		self.computed_for = Some(metrics);
+		true
+	}
+
+	fn place(&mut self, id: LayoutId, rect: UiRect) {
+		self.rects[id.0] = rect;
+
+		let node = &self.nodes[id.0];
+		let inner = rect.shrink(node.padding);
+		let arrangement = node.arrangement;
+		let children = node.children.clone();
+
+		let sizes: Vec<Vec2> = children.iter()
+			.map(|&child| Vec2::new(
+				self.resolve_extent(child, 0, inner.size.x),
+				self.resolve_extent(child, 1, inner.size.y),
+			))
+			.collect();
+
+		let stack_axis = match arrangement {
+			Arrangement::Free => None,
+			Arrangement::HorizontalStack{spacing} => Some((0, spacing)),
+			Arrangement::VerticalStack{spacing} => Some((1, spacing)),
+		};
+
+		let (axis, spacing) = match stack_axis {
+			Some(stack) => stack,
+			None => {
+				for (&child, &size) in children.iter().zip(sizes.iter()) {
+					let child_rect = self.anchor_rect(child, inner, size);
+					self.place(child, child_rect);
+				}
+
+				return
+			}
+		};
+
+		// Stretched children share out whatever's left along the stack
+		let stretched: Vec<bool> = children.iter()
+			.map(|&child| matches!(self.extent(child, axis), Extent::Stretch))
+			.collect();
+
+		let num_stretched = stretched.iter().filter(|&&is_stretched| is_stretched).count();
+
+		let used = sizes.iter().zip(stretched.iter())
+			.filter(|(_, &is_stretched)| !is_stretched)
+			.map(|(size, _)| axis_of(*size, axis))
+			.sum::<f32>()
+			+ spacing * children.len().saturating_sub(1) as f32;
+
+		let stretched_size = (axis_of(inner.size, axis) - used).max(0.0) / num_stretched.max(1) as f32;
+
+		// Horizontal stacks run from the left edge, vertical ones down from the top
+		let (mut cursor, direction) = match axis {
+			0 => (inner.min().x, 1.0),
+			_ => (inner.max().y, -1.0),
+		};
+
+		for ((&child, &size), &is_stretched) in children.iter().zip(sizes.iter()).zip(stretched.iter()) {
+			let mut size = size;
+			if is_stretched {
+				set_axis(&mut size, axis, stretched_size);
+			}
+
+			let mut child_rect = self.anchor_rect(child, inner, size);
+			let main_center = cursor + direction * axis_of(size, axis) / 2.0 + axis_of(self.nodes[child.0].offset, axis);
+			set_axis(&mut child_rect.center, axis, main_center);
+
+			self.place(child, child_rect);
+
+			cursor += direction * (axis_of(size, axis) + spacing);
+		}
+	}
+
+	/// Where a child of size `size` sits within `parent`, according to its anchor, pivot and offset
+	fn anchor_rect(&self, id: LayoutId, parent: UiRect, size: Vec2) -> UiRect {
+		let node = &self.nodes[id.0];
+		let center = parent.point(node.anchor) - node.pivot * size / 2.0 + node.offset;
+
+		UiRect { center, size }
+	}
+
+	fn extent(&self, id: LayoutId, axis: usize) -> Extent {
+		let node = &self.nodes[id.0];
+		match axis {
+			0 => node.width,
+			_ => node.height,
+		}
+	}
+
+	fn resolve_extent(&self, id: LayoutId, axis: usize, available: f32) -> f32 {
+		match self.extent(id, axis) {
+			Extent::Fixed(size) => size,
+			Extent::Relative(fraction) => available * fraction,
+			Extent::Fit => self.fit_size(id, axis),
+			Extent::Stretch => available,
+		}
+	}
+
+	/// The size a node needs along `axis` to hold its children. Children sized relative to it don't count.
+	fn fit_size(&self, id: LayoutId, axis: usize) -> f32 {
+		let node = &self.nodes[id.0];
+
+		let child_sizes = node.children.iter()
+			.map(|&child| match self.extent(child, axis) {
+				Extent::Fixed(size) => size,
+				Extent::Fit => self.fit_size(child, axis),
+				Extent::Relative(_) | Extent::Stretch => 0.0,
+			});
+
+		let content = match (node.arrangement, axis) {
+			(Arrangement::HorizontalStack{spacing}, 0) | (Arrangement::VerticalStack{spacing}, 1) => {
+				child_sizes.sum::<f32>() + spacing * node.children.len().saturating_sub(1) as f32
+			}
+
+			_ => child_sizes.fold(0.0, f32::max),
+		};
+
+		content + axis_of(node.padding, axis) * 2.0
+	}
+}
+
+
+fn axis_of(v: Vec2, axis: usize) -> f32 {
+	match axis {
+		0 => v.x,
+		_ => v.y,
+	}
+}
+
+fn set_axis(v: &mut Vec2, axis: usize, value: f32) {
+	match axis {
+		0 => v.x = value,
+		_ => v.y = value,
+	}
+}
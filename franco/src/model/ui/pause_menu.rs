@@ -0,0 +1,118 @@
+use crate::prelude::*;
+
+use model::SettingItem;
+
+/// Vertical distance between rows, in ui units
+pub const PAUSE_MENU_ROW_SPACING: f32 = 1.6;
+pub const PAUSE_MENU_ROW_WIDTH: f32 = 14.0;
+
+/// Where slider bars sit within a settings row
+pub const PAUSE_MENU_SLIDER_CENTER: f32 = 2.0;
+pub const PAUSE_MENU_SLIDER_WIDTH: f32 = 8.0;
+
+
+/// Covers the whole window regardless of split screen, since pausing affects everyone
+#[derive(Debug)]
+pub struct PauseMenu {
+	pub state: model::UiPanelState,
+	pub page: PausePage,
+
+	/// Index into the items of the current page
+	pub selected: usize,
+
+	pub metrics: model::UiMetrics,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PausePage {
+	Main,
+	Settings,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PauseItem {
+	Resume,
+	Settings,
+	Quit,
+}
+
+impl PauseItem {
+	pub const ALL: [PauseItem; 3] = [PauseItem::Resume, PauseItem::Settings, PauseItem::Quit];
+}
+
+
+impl PauseMenu {
+	pub fn new() -> PauseMenu {
+		PauseMenu {
+			state: model::UiPanelState::Closed,
+			page: PausePage::Main,
+			selected: 0,
+			metrics: model::UiMetrics::new(1.0, 1.0),
+		}
+	}
+
+	pub fn open(&mut self) {
+		self.state.open(0.3);
+		self.show_page(PausePage::Main);
+	}
+
+	pub fn close(&mut self) {
+		self.state.close(0.2);
+	}
+
+	pub fn show_page(&mut self, page: PausePage) {
+		self.page = page;
+		self.selected = 0;
+	}
+
+	/// Leaves the settings page, keeping the settings item selected
+	pub fn return_to_main(&mut self) {
+		self.page = PausePage::Main;
+		self.selected = PauseItem::ALL.iter().position(|&item| item == PauseItem::Settings).unwrap();
+	}
+
+	pub fn num_rows(&self) -> usize {
+		match self.page {
+			PausePage::Main => PauseItem::ALL.len(),
+			PausePage::Settings => SettingItem::ALL.len(),
+		}
+	}
+
+	pub fn selected_pause_item(&self) -> Option<PauseItem> {
+		match self.page {
+			PausePage::Main => PauseItem::ALL.get(self.selected).copied(),
+			PausePage::Settings => None,
+		}
+	}
+
+	pub fn selected_setting(&self) -> Option<SettingItem> {
+		match self.page {
+			PausePage::Settings => SettingItem::ALL.get(self.selected).copied(),
+			PausePage::Main => None,
+		}
+	}
+
+	pub fn select_next(&mut self) {
+		self.selected = (self.selected + 1) % self.num_rows();
+	}
+
+	pub fn select_previous(&mut self) {
+		self.selected = (self.selected + self.num_rows() - 1) % self.num_rows();
+	}
+
+	/// The center of a row, relative to the center of the window
+	pub fn row_position(&self, row: usize) -> Vec2 {
+		let top = (self.num_rows() as f32 - 1.0) / 2.0 * PAUSE_MENU_ROW_SPACING;
+		Vec2::from_y(top - row as f32 * PAUSE_MENU_ROW_SPACING)
+	}
+
+	/// The row under `pos`, in ui units
+	pub fn row_at(&self, pos: Vec2) -> Option<usize> {
+		if pos.x.abs() > PAUSE_MENU_ROW_WIDTH / 2.0 {
+			return None
+		}
+
+		(0..self.num_rows())
+			.find(|&row| (self.row_position(row).y - pos.y).abs() < PAUSE_MENU_ROW_SPACING / 2.0)
+	}
+}
@@ -0,0 +1,52 @@
+use crate::prelude::*;
+
+pub const PICKUP_COUNTER_ROW_SPACING: f32 = 1.4;
+
+/// How many of each kind of pickup have been collected, shown in the corner of the HUD
+#[derive(Debug)]
+pub struct PickupCounter {
+	pub rows: Vec<PickupCounterRow>,
+}
+
+#[derive(Debug)]
+pub struct PickupCounterRow {
+	pub kind: model::PickupKind,
+	pub collected: usize,
+	pub total: usize,
+
+	/// Set to 1 when a pickup of this kind is collected, then fades back to 0
+	pub pulse: f32,
+}
+
+impl PickupCounter {
+	pub fn new() -> PickupCounter {
+		let rows = model::PickupKind::ALL.iter()
+			.map(|&kind| PickupCounterRow {
+				kind,
+				collected: 0,
+				total: 0,
+				pulse: 0.0,
+			})
+			.collect();
+
+		PickupCounter {rows}
+	}
+
+	pub fn track(&mut self, world: &model::World) {
+		for row in self.rows.iter_mut() {
+			let collected = world.pickups_collected(row.kind);
+
+			if collected > row.collected {
+				row.pulse = 1.0;
+			}
+
+			row.collected = collected;
+			row.total = world.pickups_total(row.kind);
+			row.pulse = (row.pulse - 2.0 / 60.0).max(0.0);
+		}
+	}
+
+	pub fn row_position(&self, row: usize) -> model::UiPosition {
+		model::UiPosition::BottomLeft(Vec2::new(1.5, 1.5 + row as f32 * PICKUP_COUNTER_ROW_SPACING))
+	}
+}
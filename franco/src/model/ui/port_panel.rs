@@ -0,0 +1,115 @@
+use crate::prelude::*;
+
+/// Vertical distance between rows, in ui units
+pub const PORT_PANEL_ROW_SPACING: f32 = 1.8;
+pub const PORT_PANEL_ROW_WIDTH: f32 = 10.0;
+
+/// How long the save row stays lit after saving
+pub const PORT_PANEL_SAVED_TIME: f32 = 1.5;
+
+
+/// Opened while a boat is moored - a save point, the paint shop, and a chance to part ways with friends
+#[derive(Debug)]
+pub struct PortPanel {
+	pub state: model::UiPanelState,
+	pub rows: Vec<PortRow>,
+
+	/// Index into `rows`
+	pub selected: usize,
+
+	/// Counts down after saving, so the save row can show that it worked
+	pub saved_timer: f32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PortRow {
+	Save,
+	Paint,
+
+	/// One of the friends following the boat, by index into the world's friends.
+	/// Friends marked to leave are said goodbye to as the boat sets sail.
+	Friend { friend: usize, keep: bool },
+
+	Leave,
+}
+
+
+impl PortPanel {
+	pub fn new() -> PortPanel {
+		PortPanel {
+			state: model::UiPanelState::Closed,
+			rows: Vec::new(),
+			selected: 0,
+			saved_timer: 0.0,
+		}
+	}
+
+	/// Lists the friends following the boats - every one is kept unless the player says otherwise.
+	/// Clients follow the host's world rather than their own save, so they aren't offered a save row.
+	pub fn open(&mut self, friends: &[model::Friend], can_save: bool) {
+		let friend_rows = friends.iter()
+			.enumerate()
+			.filter(|(_, friend)| friend.follows_player())
+			.map(|(friend, _)| PortRow::Friend { friend, keep: true });
+
+		let save_row = Some(PortRow::Save).filter(|_| can_save);
+
+		self.rows = save_row.into_iter()
+			.chain(std::iter::once(PortRow::Paint))
+			.chain(friend_rows)
+			.chain(std::iter::once(PortRow::Leave))
+			.collect();
+
+		self.selected = 0;
+		self.saved_timer = 0.0;
+		self.state.open(0.4);
+	}
+
+	pub fn close(&mut self) {
+		self.state.close(0.3);
+	}
+
+	pub fn selected_row(&self) -> Option<PortRow> {
+		self.rows.get(self.selected).copied()
+	}
+
+	/// The friends marked to be said goodbye to
+	pub fn departing_friends(&self) -> impl Iterator<Item=usize> + '_ {
+		self.rows.iter()
+			.filter_map(|row| match *row {
+				PortRow::Friend { friend, keep: false } => Some(friend),
+				_ => None,
+			})
+	}
+
+	pub fn toggle_keep(&mut self, row: usize) {
+		if let Some(PortRow::Friend { keep, .. }) = self.rows.get_mut(row) {
+			*keep = !*keep;
+		}
+	}
+
+	pub fn select_next(&mut self) {
+		self.selected = (self.selected + 1) % self.rows.len().max(1);
+	}
+
+	pub fn select_previous(&mut self) {
+		let num_rows = self.rows.len().max(1);
+		self.selected = (self.selected + num_rows - 1) % num_rows;
+	}
+
+	/// The center of a row, relative to the center of the viewport
+	pub fn row_position(&self, row: usize) -> Vec2 {
+		let top = (self.rows.len() as f32 - 1.0) / 2.0 * PORT_PANEL_ROW_SPACING;
+		Vec2::from_y(top - row as f32 * PORT_PANEL_ROW_SPACING)
+	}
+
+	/// The row under `pos`, in ui units
+	pub fn row_at(&self, pos: Vec2) -> Option<usize> {
+		if pos.x.abs() > PORT_PANEL_ROW_WIDTH / 2.0 {
+			return None
+		}
+
+		(0..self.rows.len())
+			.find(|&row| (self.row_position(row).y - pos.y).abs() < PORT_PANEL_ROW_SPACING / 2.0)
+	}
+}
@@ -1,15 +1,14 @@
 use crate::prelude::*;
 
 #[derive(Debug)]
-pub struct Map {
+pub struct StatsScreen {
 	pub state: model::UiPanelState,
 }
 
-impl Map {
-	pub fn new() -> Map {
-		Map {
+impl StatsScreen {
+	pub fn new() -> StatsScreen {
+		StatsScreen {
 			state: model::UiPanelState::Closed,
 		}
 	}
 }
-
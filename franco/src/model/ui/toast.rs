@@ -0,0 +1,28 @@
+use crate::prelude::*;
+
+pub const TOAST_DISPLAY_TIME: f32 = 3.0;
+
+/// A short-lived notification, used to announce unlocked achievements
+#[derive(Debug)]
+pub struct Toast {
+	pub state: model::UiPanelState,
+	pub timer: f32,
+
+	/// Index into `Stats::achievements`
+	pub achievement: Option<usize>,
+}
+
+impl Toast {
+	pub fn new() -> Toast {
+		Toast {
+			state: model::UiPanelState::Closed,
+			timer: 0.0,
+			achievement: None,
+		}
+	}
+
+	pub fn position(&self) -> model::UiPosition {
+		let phase = self.state.as_phase();
+		model::UiPosition::Top(phase.ease_back_out(-1.0, 1.5))
+	}
+}
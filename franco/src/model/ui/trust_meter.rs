@@ -0,0 +1,47 @@
+use crate::prelude::*;
+
+/// How close a friend must be to a boat for that boat's player to see their trust building
+pub const TRUST_METER_RANGE: f32 = 15.0;
+
+/// Shows how close the nearest unmet friend is to trusting the boat
+#[derive(Debug)]
+pub struct TrustMeter {
+	pub state: model::UiPanelState,
+	pub trust: f32,
+	pub friend: Option<model::FriendName>,
+}
+
+impl TrustMeter {
+	pub fn new() -> TrustMeter {
+		TrustMeter {
+			state: model::UiPanelState::Closed,
+			trust: 0.0,
+			friend: None,
+		}
+	}
+
+	/// Follows the most trusting unmet friend near `boat_position`
+	pub fn track(&mut self, friends: &[model::Friend], boat_position: Vec2) {
+		let tracked = friends.iter()
+			.filter(|friend| !friend.met_player() && friend.trust > 0.0)
+			.filter(|friend| (friend.map_position - boat_position).length() < TRUST_METER_RANGE)
+			.max_by(|a, b| a.trust.total_cmp(&b.trust));
+
+		match tracked {
+			Some(friend) => {
+				self.trust = friend.trust;
+				self.friend = Some(friend.name);
+				self.state.open(0.3);
+			}
+
+			None => self.state.close(0.5),
+		}
+
+		self.state.update();
+	}
+
+	pub fn position(&self) -> model::UiPosition {
+		let phase = self.state.as_phase();
+		model::UiPosition::Top(phase.ease_back_out(-1.5, 3.5))
+	}
+}
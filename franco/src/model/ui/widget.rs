@@ -0,0 +1,516 @@
+use crate::prelude::*;
+use model::{UiMetrics, UiPanelState, UiPosition};
+
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct WidgetId(usize);
+
+
+#[derive(Copy, Clone, Debug)]
+pub enum WidgetKind {
+	/// Groups other widgets - while closed, nothing inside can be seen or interacted with
+	Panel,
+
+	Button,
+	Toggle { on: bool },
+
+	/// A horizontal slider, from 0 at its left edge to 1 at its right
+	Slider { value: f32 },
+
+	/// Turned by dragging sideways, like the ship's wheel - the angle is in radians, within [-PI, PI]
+	Dial { angle: f32 },
+}
+
+impl WidgetKind {
+	fn is_interactive(&self) -> bool {
+		!matches!(self, WidgetKind::Panel)
+	}
+
+	/// (open, close) times for highlight transitions
+	fn highlight_times(&self) -> (f32, f32) {
+		match self {
+			WidgetKind::Dial{..} => (0.2, 1.0),
+			_ => (0.1, 0.3),
+		}
+	}
+}
+
+
+/// The area of a widget that responds to the pointer, centered on its position
+#[derive(Copy, Clone, Debug)]
+pub enum WidgetShape {
+	Rect(Vec2),
+	Circle(f32),
+}
+
+impl WidgetShape {
+	pub fn contains(&self, offset: Vec2) -> bool {
+		match *self {
+			WidgetShape::Rect(size) => offset.x.abs() <= size.x / 2.0 && offset.y.abs() <= size.y / 2.0,
+			WidgetShape::Circle(radius) => offset.length() < radius,
+		}
+	}
+}
+
+
+#[derive(Debug)]
+pub struct Widget {
+	pub kind: WidgetKind,
+
+	/// Center positions are relative to the parent widget - everything else is anchored to the viewport
+	pub position: UiPosition,
+	pub shape: WidgetShape,
+
+	/// For panels, whether they're open. For everything else, how highlighted they are by hovering, pressing or focus
+	pub state: UiPanelState,
+
+	/// Whether keyboard navigation stops on this widget
+	pub focusable: bool,
+
+	parent: Option<WidgetId>,
+	children: Vec<WidgetId>,
+}
+
+impl Widget {
+	pub fn new(kind: WidgetKind, position: UiPosition, shape: WidgetShape) -> Widget {
+		Widget {
+			kind,
+			position,
+			shape,
+			state: UiPanelState::Closed,
+			focusable: kind.is_interactive(),
+			parent: None,
+			children: Vec::new(),
+		}
+	}
+
+	pub fn with_state(mut self, state: UiPanelState) -> Widget {
+		self.state = state;
+		self
+	}
+}
+
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum WidgetEvent {
+	Hovered(WidgetId),
+	Unhovered(WidgetId),
+
+	/// The pointer went down on a widget, which captures it until it's released
+	Pressed(WidgetId),
+	Released(WidgetId),
+
+	/// Released over the same widget it was pressed on, or activated from the keyboard
+	Clicked(WidgetId),
+
+	/// A toggle, slider or dial changed its value
+	Changed(WidgetId),
+
+	Focused(WidgetId),
+}
+
+
+/// A retained hierarchy of widgets, which routes pointer and keyboard input to them.
+/// Pointer input is routed from the root down - closed panels hide everything inside them,
+/// and later children sit above earlier ones.
+#[derive(Debug)]
+pub struct WidgetTree {
+	widgets: Vec<Widget>,
+
+	hovered: Option<WidgetId>,
+
+	/// The widget that was pressed, which gets all pointer input until release
+	captured: Option<WidgetId>,
+	focused: Option<WidgetId>,
+
+	/// Whether the pointer went down somewhere no widget claimed
+	pressed_unclaimed: bool,
+
+	events: Vec<WidgetEvent>,
+}
+
+impl std::ops::Index<WidgetId> for WidgetTree {
+	type Output = Widget;
+
+	fn index(&self, id: WidgetId) -> &Widget {
+		&self.widgets[id.0]
+	}
+}
+
+impl std::ops::IndexMut<WidgetId> for WidgetTree {
+	fn index_mut(&mut self, id: WidgetId) -> &mut Widget {
+		&mut self.widgets[id.0]
+	}
+}
+
+impl WidgetTree {
+	/// Starts with a root panel covering the whole viewport, which is always open
+	pub fn new() -> WidgetTree {
+		let root = Widget::new(WidgetKind::Panel, UiPosition::Center(Vec2::zero()), WidgetShape::Rect(Vec2::zero()))
+			.with_state(UiPanelState::Open);
+
+		WidgetTree {
+			widgets: vec![root],
+			hovered: None,
+			captured: None,
+			focused: None,
+			pressed_unclaimed: false,
+			events: Vec::new(),
+		}
+	}
+
+	pub fn root(&self) -> WidgetId {
+		WidgetId(0)
+	}
+
+	pub fn add(&mut self, parent: WidgetId, mut widget: Widget) -> WidgetId {
+		let id = WidgetId(self.widgets.len());
+		widget.parent = Some(parent);
+
+		self.widgets.push(widget);
+		self.widgets[parent.0].children.push(id);
+		id
+	}
+
+	pub fn hovered(&self) -> Option<WidgetId> { self.hovered }
+	pub fn captured(&self) -> Option<WidgetId> { self.captured }
+	pub fn focused(&self) -> Option<WidgetId> { self.focused }
+
+	/// Whether the pointer is held down outside of every widget - which leaves it free for things like dragging the camera
+	pub fn pressed_unclaimed(&self) -> bool {
+		self.pressed_unclaimed
+	}
+
+	pub fn position(&self, id: WidgetId, metrics: UiMetrics) -> Vec2 {
+		let widget = &self[id];
+
+		match (widget.position, widget.parent) {
+			(UiPosition::Center(offset), Some(parent)) => self.position(parent, metrics) + offset,
+			(position, _) => position.resolve(metrics),
+		}
+	}
+
+	/// Whether `id` and every panel containing it are open
+	pub fn is_visible(&self, id: WidgetId) -> bool {
+		let widget = &self[id];
+
+		let self_visible = match widget.kind {
+			WidgetKind::Panel => widget.state.is_open(),
+			_ => true,
+		};
+
+		self_visible && widget.parent.map_or(true, |parent| self.is_visible(parent))
+	}
+
+	/// The topmost widget under `pos` that will take pointer input.
+	/// Open panels block whatever is below them, but the root doesn't count.
+	pub fn hit_test(&self, pos: Vec2, metrics: UiMetrics) -> Option<WidgetId> {
+		self.hit_test_within(self.root(), pos, metrics)
+			.filter(|&id| id != self.root())
+	}
+
+	fn hit_test_within(&self, id: WidgetId, pos: Vec2, metrics: UiMetrics) -> Option<WidgetId> {
+		let widget = &self[id];
+
+		if widget.kind.is_interactive() || !widget.state.is_open() {
+			let inside = widget.shape.contains(pos - self.position(id, metrics));
+			return (inside && widget.kind.is_interactive()).then_some(id)
+		}
+
+		let child_hit = widget.children.iter().rev()
+			.find_map(|&child| self.hit_test_within(child, pos, metrics));
+
+		let inside = id == self.root() || widget.shape.contains(pos - self.position(id, metrics));
+		child_hit.or_else(|| inside.then_some(id))
+	}
+
+	pub fn pointer_moved(&mut self, pos: Vec2, metrics: UiMetrics) {
+		if let Some(captured) = self.captured {
+			self.slide_to(captured, pos, metrics);
+			return
+		}
+
+		let hovered = self.hit_test(pos, metrics)
+			.filter(|&id| self[id].kind.is_interactive());
+
+		if hovered != self.hovered {
+			if let Some(previous) = self.hovered {
+				self.events.push(WidgetEvent::Unhovered(previous));
+			}
+
+			if let Some(hovered) = hovered {
+				self.events.push(WidgetEvent::Hovered(hovered));
+			}
+
+			self.hovered = hovered;
+		}
+	}
+
+	/// Returns whether a widget claimed the press
+	pub fn pointer_down(&mut self, pos: Vec2, metrics: UiMetrics) -> bool {
+		self.focused = None;
+
+		let hit = self.hit_test(pos, metrics);
+		self.pressed_unclaimed = hit.is_none();
+
+		let target = match hit {
+			Some(id) if self[id].kind.is_interactive() => id,
+			_ => return hit.is_some(),
+		};
+
+		self.captured = Some(target);
+		self.events.push(WidgetEvent::Pressed(target));
+		self.slide_to(target, pos, metrics);
+
+		true
+	}
+
+	/// Relative pointer motion while something is captured, for dials
+	pub fn pointer_dragged(&mut self, delta: Vec2) {
+		let captured = match self.captured {
+			Some(captured) => captured,
+			None => return,
+		};
+
+		if let WidgetKind::Dial{angle} = &mut self[captured].kind {
+			*angle = (*angle - delta.x).clamp(-PI, PI);
+			self.events.push(WidgetEvent::Changed(captured));
+		}
+	}
+
+	/// `pos` is None if the pointer has left the window
+	pub fn pointer_up(&mut self, pos: Option<Vec2>, metrics: UiMetrics) {
+		self.pressed_unclaimed = false;
+
+		let captured = match self.captured.take() {
+			Some(captured) => captured,
+			None => return,
+		};
+
+		self.events.push(WidgetEvent::Released(captured));
+
+		let still_over = pos.map_or(false, |pos| self.hit_test(pos, metrics) == Some(captured));
+		if still_over {
+			self.click(captured);
+		}
+	}
+
+	/// Moves focus to the next visible focusable widget in tree order, wrapping around
+	pub fn focus_next(&mut self) {
+		let focusable: Vec<_> = (0..self.widgets.len())
+			.map(WidgetId)
+			.filter(|&id| self[id].focusable && self.is_visible(id))
+			.collect();
+
+		if focusable.is_empty() {
+			self.focused = None;
+			return
+		}
+
+		let next = match self.focused.and_then(|focused| focusable.iter().position(|&id| id == focused)) {
+			Some(idx) => focusable[(idx + 1) % focusable.len()],
+			None => focusable[0],
+		};
+
+		self.focused = Some(next);
+		self.events.push(WidgetEvent::Focused(next));
+	}
+
+	pub fn activate_focused(&mut self) {
+		if let Some(focused) = self.focused.filter(|&id| self.is_visible(id)) {
+			self.click(focused);
+		}
+	}
+
+	/// Pops a widget's highlight as if it had been pressed, for input that doesn't go through the pointer
+	pub fn pulse(&mut self, id: WidgetId) {
+		self[id].state = UiPanelState::Open;
+	}
+
+	/// Steps transitions, and highlights whatever is hovered, captured or focused
+	pub fn update(&mut self) {
+		for idx in 0..self.widgets.len() {
+			let id = WidgetId(idx);
+			let highlighted = self.hovered == Some(id) || self.captured == Some(id) || self.focused == Some(id);
+			let widget = &mut self.widgets[idx];
+
+			if widget.kind.is_interactive() {
+				let (open_time, close_time) = widget.kind.highlight_times();
+
+				match highlighted {
+					true => widget.state.open(open_time),
+					false => widget.state.close(close_time),
+				}
+			}
+
+			widget.state.update();
+		}
+	}
+
+	pub fn drain_events(&mut self) -> Vec<WidgetEvent> {
+		std::mem::take(&mut self.events)
+	}
+
+	fn click(&mut self, id: WidgetId) {
+		self.events.push(WidgetEvent::Clicked(id));
+
+		if let WidgetKind::Toggle{on} = &mut self[id].kind {
+			*on = !*on;
+			self.events.push(WidgetEvent::Changed(id));
+		}
+	}
+
+	fn slide_to(&mut self, id: WidgetId, pos: Vec2, metrics: UiMetrics) {
+		let center = self.position(id, metrics);
+		let widget = &mut self[id];
+
+		if let (WidgetKind::Slider{value}, WidgetShape::Rect(size)) = (&mut widget.kind, widget.shape) {
+			*value = ((pos.x - center.x) / size.x.max(0.01) + 0.5).clamp(0.0, 1.0);
+			self.events.push(WidgetEvent::Changed(id));
+		}
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn metrics() -> UiMetrics {
+		UiMetrics::new(1.0, 1.0)
+	}
+
+	fn button(pos: Vec2) -> Widget {
+		Widget::new(WidgetKind::Button, UiPosition::Center(pos), WidgetShape::Rect(Vec2::splat(2.0)))
+	}
+
+	fn panel(pos: Vec2, size: Vec2, state: UiPanelState) -> Widget {
+		Widget::new(WidgetKind::Panel, UiPosition::Center(pos), WidgetShape::Rect(size))
+			.with_state(state)
+	}
+
+	#[test]
+	fn test_hit_test() {
+		let mut tree = WidgetTree::new();
+		let root = tree.root();
+
+		let lower = tree.add(root, button(Vec2::zero()));
+		let upper = tree.add(root, button(Vec2::new(1.0, 0.0)));
+		let circle = tree.add(root, Widget::new(WidgetKind::Button, UiPosition::Center(Vec2::new(-5.0, 0.0)), WidgetShape::Circle(1.0)));
+
+		// Later children sit above earlier ones
+		assert_eq!(tree.hit_test(Vec2::new(-0.5, 0.0), metrics()), Some(lower));
+		assert_eq!(tree.hit_test(Vec2::new(0.5, 0.0), metrics()), Some(upper));
+
+		assert_eq!(tree.hit_test(Vec2::new(-5.0, 0.9), metrics()), Some(circle));
+		assert_eq!(tree.hit_test(Vec2::new(-5.9, 0.9), metrics()), None);
+
+		// The root never counts as a hit
+		assert_eq!(tree.hit_test(Vec2::new(10.0, 10.0), metrics()), None);
+	}
+
+	#[test]
+	fn test_hit_test_panels() {
+		let mut tree = WidgetTree::new();
+		let root = tree.root();
+
+		let below = tree.add(root, button(Vec2::zero()));
+		let open = tree.add(root, panel(Vec2::new(0.0, 5.0), Vec2::splat(6.0), UiPanelState::Open));
+		let closed = tree.add(root, panel(Vec2::new(0.0, -5.0), Vec2::splat(6.0), UiPanelState::Closed));
+
+		// Children are positioned relative to their panel
+		let in_open = tree.add(open, button(Vec2::new(1.0, 0.0)));
+		let in_closed = tree.add(closed, button(Vec2::zero()));
+
+		assert_eq!(tree.hit_test(Vec2::new(1.0, 5.0), metrics()), Some(in_open));
+		assert!(tree.is_visible(in_open));
+
+		// Open panels block whatever is below them, closed ones hide what's inside
+		assert_eq!(tree.hit_test(Vec2::new(-2.0, 5.0), metrics()), Some(open));
+		assert_eq!(tree.hit_test(Vec2::new(0.0, -5.0), metrics()), None);
+		assert!(!tree.is_visible(in_closed));
+
+		assert_eq!(tree.hit_test(Vec2::zero(), metrics()), Some(below));
+	}
+
+	#[test]
+	fn test_capture() {
+		let mut tree = WidgetTree::new();
+		let root = tree.root();
+
+		let toggle = tree.add(root, Widget::new(WidgetKind::Toggle{on: false}, UiPosition::Center(Vec2::zero()), WidgetShape::Rect(Vec2::splat(2.0))));
+		let other = tree.add(root, button(Vec2::new(5.0, 0.0)));
+
+		tree.pointer_moved(Vec2::zero(), metrics());
+		assert_eq!(tree.hovered(), Some(toggle));
+
+		assert!(tree.pointer_down(Vec2::zero(), metrics()));
+		assert_eq!(tree.captured(), Some(toggle));
+		assert!(!tree.pressed_unclaimed());
+
+		// While captured, nothing else gets hovered
+		tree.pointer_moved(Vec2::new(5.0, 0.0), metrics());
+		assert_eq!(tree.hovered(), Some(toggle));
+
+		// Released away from the widget it was pressed on, so it doesn't count as a click
+		tree.pointer_up(Some(Vec2::new(5.0, 0.0)), metrics());
+		assert_eq!(tree.captured(), None);
+		assert_eq!(tree.drain_events(), [
+			WidgetEvent::Hovered(toggle),
+			WidgetEvent::Pressed(toggle),
+			WidgetEvent::Released(toggle),
+		]);
+
+		tree.pointer_moved(Vec2::new(5.0, 0.0), metrics());
+		tree.pointer_moved(Vec2::zero(), metrics());
+		tree.pointer_down(Vec2::zero(), metrics());
+		tree.pointer_up(Some(Vec2::zero()), metrics());
+
+		assert_eq!(tree.drain_events(), [
+			WidgetEvent::Unhovered(toggle),
+			WidgetEvent::Hovered(other),
+			WidgetEvent::Unhovered(other),
+			WidgetEvent::Hovered(toggle),
+			WidgetEvent::Pressed(toggle),
+			WidgetEvent::Released(toggle),
+			WidgetEvent::Clicked(toggle),
+			WidgetEvent::Changed(toggle),
+		]);
+
+		assert!(matches!(tree[toggle].kind, WidgetKind::Toggle{on: true}));
+	}
+
+	#[test]
+	fn test_slider_capture() {
+		let mut tree = WidgetTree::new();
+		let root = tree.root();
+
+		let slider = tree.add(root, Widget::new(WidgetKind::Slider{value: 0.0}, UiPosition::Center(Vec2::zero()), WidgetShape::Rect(Vec2::new(4.0, 1.0))));
+
+		tree.pointer_down(Vec2::new(1.0, 0.0), metrics());
+		assert!(matches!(tree[slider].kind, WidgetKind::Slider{value} if value == 0.75));
+
+		// Dragging keeps control of the slider even once the pointer has left it
+		tree.pointer_moved(Vec2::new(10.0, 10.0), metrics());
+		assert!(matches!(tree[slider].kind, WidgetKind::Slider{value} if value == 1.0));
+
+		tree.pointer_up(None, metrics());
+		assert_eq!(tree.captured(), None);
+	}
+
+	#[test]
+	fn test_pressed_unclaimed() {
+		let mut tree = WidgetTree::new();
+		let root = tree.root();
+		tree.add(root, button(Vec2::zero()));
+
+		assert!(!tree.pointer_down(Vec2::new(5.0, 5.0), metrics()));
+		assert!(tree.pressed_unclaimed());
+		assert_eq!(tree.captured(), None);
+
+		tree.pointer_up(Some(Vec2::new(5.0, 5.0)), metrics());
+		assert!(!tree.pressed_unclaimed());
+		assert!(tree.drain_events().is_empty());
+	}
+}
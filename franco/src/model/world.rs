@@ -3,46 +3,142 @@ use crate::prelude::*;
 pub mod friend;
 pub use friend::*;
 
+pub mod trick;
+pub use trick::*;
+
+pub mod pickup;
+pub use pickup::*;
+
+pub mod sonar;
+pub use sonar::*;
+
+pub mod ambient;
+pub use ambient::*;
+
+pub mod port;
+pub use port::*;
+
 pub const MAP_SCALE: f32 = 10.0;
 
+/// Offsets the hash used for friend color variants, so it doesn't line up with pickup scattering
+const FRIEND_VARIANT_SEED: u32 = 0x4000;
+
 
 #[derive(Debug)]
 pub struct World {
 	pub map: Map,
 	pub friends: Vec<Friend>,
+	pub pickups: Vec<Pickup>,
+	pub sonar_pings: Vec<SonarPing>,
+	pub ambient: AmbientLife,
+	pub ports: Vec<Port>,
 
 	pub sky_color: Color,
 }
 
 impl World {
-	pub fn new(resources: &model::Resources) -> Result<World> {
-		let map_scene = resources.main_project.find_scene("map").unwrap();
+	pub fn new<'t>(map_scene: toy::SceneRef<'_>, island_scenes: impl Iterator<Item=toy::SceneRef<'t>>) -> Result<World> {
+		let map = Map::new(map_scene);
 
 		let friends = map_scene.entities_with_prefix("FRIEND_")
-			.map(|entity| {
-				Friend {
-					name: FriendName::from_name(&entity.name),
-					state: FriendState::HangingOut,
-					met_player: false,
-
-					map_position: entity.position.to_xz() * Vec2::new(1.0, -1.0),
-					heading: entity.rotation.yaw(),
-					speed: 0.0,
-
-					decision_timer: 0.0,
-					bob_phase: 0.0,
-					heading_wander: 0.0,
-				}
+			.enumerate()
+			.map(|(idx, entity)| {
+				let map_position = entity.position.to_xz() * Vec2::new(1.0, -1.0);
+
+				// Derived from spawn order, so hosts and clients agree on it without it being sent
+				let color_variant = (pickup::scatter_value(FRIEND_VARIANT_SEED + idx as u32) * 256.0) as usize;
+				let home_position = map.nearest_island(map_position).unwrap_or(map_position);
+
+				Friend::new(FriendName::from_name(&entity.name), map_position, entity.rotation.yaw(), home_position, color_variant)
 			})
 			.collect();
 
+		let pickups = load_pickups(map_scene, &map);
+		let ambient = AmbientLife::new(&map);
+		let ports = load_ports(island_scenes, &map);
+
 		Ok(World {
-			map: Map::new(map_scene),
+			map,
 			friends,
+			pickups,
+			sonar_pings: Vec::new(),
+			ambient,
+			ports,
 
 			sky_color: Color::hsv(200.0, 0.5, 0.9),
 		})
 	}
+
+	/// Brings the friends met in `previous` along into this world, gathered around the boat at `boat_position`
+	pub fn carry_over_friends(&mut self, previous: World, boat_position: Vec2) {
+		// Friends that have said goodbye stay behind
+		let met_friends = previous.friends.into_iter()
+			.filter(|friend| friend.met_player() && !friend.said_goodbye);
+
+		for (idx, mut friend) in met_friends.enumerate() {
+			self.gather_around_boat(&mut friend, boat_position, idx);
+			self.friends.push(friend);
+		}
+	}
+
+	/// Brings back the friends met before a save point. Friends native to this world are met in place of
+	/// their unmet selves, and friends brought from earlier levels are made anew - unless they said goodbye,
+	/// in which case they stayed behind on the level they were left on.
+	pub fn restore_friends(&mut self, saved_friends: &[model::SavedFriend], boat_position: Vec2) {
+		for (idx, saved) in saved_friends.iter().enumerate() {
+			let existing = self.friends.iter()
+				.position(|friend| friend.name == saved.name && !friend.met_player());
+
+			let native = existing.is_some();
+
+			let mut friend = match existing {
+				Some(existing) => self.friends.remove(existing),
+				None if saved.said_goodbye => continue,
+				None => Friend::new(saved.name, boat_position, 0.0, boat_position, idx),
+			};
+
+			friend.meet(0.0);
+
+			// Native friends that had left the boat are already home
+			if !saved.homesick || !native {
+				self.gather_around_boat(&mut friend, boat_position, idx);
+			}
+
+			if saved.said_goodbye {
+				friend.say_goodbye();
+			} else if saved.homesick {
+				friend.homesick = true;
+				friend.affinity = 0.0;
+				friend.state = FriendState::ReturningHome;
+			}
+
+			self.friends.push(friend);
+		}
+	}
+
+	fn gather_around_boat(&self, friend: &mut Friend, boat_position: Vec2, idx: usize) {
+		let offset = Vec2::from_angle(idx as f32 * 2.4) * (2.0 + idx as f32 * 0.5);
+
+		friend.map_position = boat_position + offset;
+		friend.home_position = self.map.nearest_island(friend.map_position).unwrap_or(friend.map_position);
+		friend.state = FriendState::Following;
+		friend.homesick = false;
+		friend.affinity = friend.affinity.max(FRIEND_INITIAL_AFFINITY);
+		friend.formation_slot = None;
+		friend.queued_trick = None;
+	}
+
+	pub fn pickups_collected(&self, kind: PickupKind) -> usize {
+		self.pickups.iter()
+			.filter(|pickup| pickup.kind == kind && pickup.collected)
+			.count()
+	}
+
+	pub fn pickups_total(&self, kind: PickupKind) -> usize {
+		self.pickups.iter()
+			.filter(|pickup| pickup.kind == kind)
+			.count()
+	}
 }
 
 
@@ -71,6 +167,17 @@ impl Map {
 			objects,
 		}
 	}
+
+	pub fn nearest_island(&self, map_position: Vec2) -> Option<Vec2> {
+		self.objects.iter()
+			.filter(|object| object.ty == MapObjectType::SmallIsland)
+			.map(|object| object.map_position)
+			.min_by(|a, b| {
+				let dist_a = (*a - map_position).length();
+				let dist_b = (*b - map_position).length();
+				dist_a.total_cmp(&dist_b)
+			})
+	}
 }
 
 
@@ -0,0 +1,104 @@
+use crate::prelude::*;
+
+/// Ambient life is only simulated and drawn within this distance of a boat
+pub const AMBIENT_CULL_DISTANCE: f32 = 40.0;
+
+pub const AMBIENT_MAX_SCHOOLS: usize = 4;
+pub const AMBIENT_SCHOOL_SIZE: usize = 10;
+
+/// Seconds a jumping fish spends out of the water
+pub const AMBIENT_JUMP_TIME: f32 = 0.8;
+
+const GULLS_PER_ISLAND: usize = 2;
+
+
+/// Scenery that moves - none of it can be met, and none of it affects friends or the game state
+#[derive(Debug)]
+pub struct AmbientLife {
+	pub schools: Vec<FishSchool>,
+	pub gulls: Vec<Gull>,
+	pub jumpers: Vec<JumpingFish>,
+
+	/// Counts down to the next jumping fish
+	pub jump_timer: f32,
+}
+
+impl AmbientLife {
+	pub fn new(map: &super::Map) -> AmbientLife {
+		let islands = map.objects.iter()
+			.filter(|object| object.ty == super::MapObjectType::SmallIsland);
+
+		let gulls = islands
+			.flat_map(|island| (0..GULLS_PER_ISLAND).map(move |idx| (island, idx)))
+			.map(|(island, idx)| Gull {
+				island_position: island.map_position,
+				orbit_angle: island.rotation + idx as f32 * PI,
+				orbit_radius: 3.0 + idx as f32 * 1.5,
+				orbit_speed: 0.4 - idx as f32 * 0.1,
+				height: 6.0 + idx as f32 * 2.0,
+			})
+			.collect();
+
+		AmbientLife {
+			schools: Vec::new(),
+			gulls,
+			jumpers: Vec::new(),
+			jump_timer: 3.0,
+		}
+	}
+}
+
+
+#[derive(Debug)]
+pub struct FishSchool {
+	pub fish: Vec<SchoolFish>,
+
+	/// Schools swim off and are replaced after a while, even if the boat stays close
+	pub lifetime: f32,
+}
+
+#[derive(Debug)]
+pub struct SchoolFish {
+	pub map_position: Vec2,
+	pub velocity: Vec2,
+	pub depth: f32,
+}
+
+impl FishSchool {
+	pub fn center(&self) -> Vec2 {
+		let sum = self.fish.iter().fold(Vec2::zero(), |acc, fish| acc + fish.map_position);
+		sum / self.fish.len().max(1) as f32
+	}
+}
+
+
+/// Circles an island forever
+#[derive(Debug)]
+pub struct Gull {
+	pub island_position: Vec2,
+	pub orbit_angle: f32,
+	pub orbit_radius: f32,
+	pub orbit_speed: f32,
+	pub height: f32,
+}
+
+impl Gull {
+	pub fn map_position(&self) -> Vec2 {
+		self.island_position + Vec2::from_angle(self.orbit_angle) * self.orbit_radius
+	}
+
+	/// Gulls fly tangent to their orbit
+	pub fn heading(&self) -> f32 {
+		self.orbit_angle + PI/2.0 * self.orbit_speed.signum()
+	}
+}
+
+
+#[derive(Debug)]
+pub struct JumpingFish {
+	pub map_position: Vec2,
+	pub heading: f32,
+
+	/// From 0 when leaving the water to 1 when splashing back in
+	pub phase: f32,
+}
@@ -1,5 +1,8 @@
 use crate::prelude::*;
 
+/// How long a spooked friend keeps fleeing once the boat has calmed down
+pub const FRIEND_FLEE_TIME: f32 = 3.0;
+
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum FriendName {
@@ -10,11 +13,28 @@ pub enum FriendName {
 	BoatBoy2,
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum FriendState {
 	HangingOut,
 	Following,
-	DoingTricks(f32),
+	DoingTricks(model::Trick),
+
+	/// Spooked by a boat - swimmers dive and boats turn away until the timer runs out
+	Fleeing(f32),
+
+	/// Lost interest in following, and heading back to `home_position`
+	ReturningHome,
+}
+
+
+/// How a species reacts to boats before it has met a player
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Temperament {
+	/// Flees from fast boats, so must be approached slowly
+	Shy,
+
+	/// Comes over to investigate anchored boats
+	Curious,
 }
 
 
@@ -28,30 +48,178 @@ pub struct Friend {
 	pub speed: f32,
 
 	pub decision_timer: f32,
-	pub met_player: bool,
+
+	/// Set once the friend has met a player
+	pub meeting: Option<FriendMeeting>,
+
+	/// Builds up while a boat stays close without spooking it - the friend is met once this reaches 1
+	pub trust: f32,
+
+	/// How much a met friend wants to keep following, from 0 to 1
+	pub affinity: f32,
+
+	/// Set when affinity runs out, until it recovers enough to follow again
+	pub homesick: bool,
+
+	/// The island a friend was found near, and returns to when homesick
+	pub home_position: Vec2,
+
+	/// Where to sit relative to the followed boat, as (ahead, left) of it
+	pub formation_slot: Option<Vec2>,
+
+	/// A trick to join in with once the delay runs out - used for combos
+	pub queued_trick: Option<QueuedTrick>,
+
+	/// Set once an unmet friend has been found by a sonar ping
+	pub sonar_contact: Option<model::SonarContact>,
+
+	/// Set when a player says goodbye at a port - the friend heads home and won't follow again
+	pub said_goodbye: bool,
+
+	/// Picks one of the species' color layers - wrapped to however many the mesh has
+	pub color_variant: usize,
 
 	pub heading_wander: f32,
 	pub bob_phase: f32,
 }
 
 
+#[derive(Copy, Clone, Debug)]
+pub struct QueuedTrick {
+	pub trick: model::Trick,
+	pub delay: f32,
+}
+
+
+/// When and where a friend was met
+#[derive(Copy, Clone, Debug)]
+pub struct FriendMeeting {
+	/// Session playtime at the moment of meeting
+	pub time: f32,
+	pub map_position: Vec2,
+}
+
+
+/// The affinity a friend has just after meeting
+pub const FRIEND_INITIAL_AFFINITY: f32 = 0.6;
+
+
+impl Friend {
+	pub fn new(name: FriendName, map_position: Vec2, heading: f32, home_position: Vec2, color_variant: usize) -> Friend {
+		Friend {
+			name,
+			state: FriendState::HangingOut,
+			meeting: None,
+			trust: 0.0,
+			affinity: 0.0,
+			homesick: false,
+			home_position,
+			formation_slot: None,
+			queued_trick: None,
+			sonar_contact: None,
+			said_goodbye: false,
+			color_variant,
+
+			map_position,
+			heading,
+			speed: 0.0,
+
+			decision_timer: 0.0,
+			bob_phase: 0.0,
+			heading_wander: 0.0,
+		}
+	}
+
+	pub fn met_player(&self) -> bool {
+		self.meeting.is_some()
+	}
+
+	/// Whether this friend is currently keeping a boat company
+	pub fn follows_player(&self) -> bool {
+		self.met_player() && !self.homesick && !self.said_goodbye
+	}
+
+	/// Parts ways with the boat for good, sending the friend back home
+	pub fn say_goodbye(&mut self) {
+		self.said_goodbye = true;
+		self.homesick = true;
+		self.affinity = 0.0;
+		self.formation_slot = None;
+		self.queued_trick = None;
+		self.state = FriendState::ReturningHome;
+	}
+
+	pub fn meet(&mut self, time: f32) {
+		self.meeting = Some(FriendMeeting {
+			time,
+			map_position: self.map_position,
+		});
+
+		self.affinity = FRIEND_INITIAL_AFFINITY;
+	}
+}
+
+
 impl FriendName {
+	pub const ALL: [FriendName; 4] = [
+		FriendName::Dolphin,
+		FriendName::Fish,
+		FriendName::BoatBoy,
+		FriendName::BoatBoy2,
+	];
+
+	/// For names from scenes, which always name a known friend
 	pub fn from_name(name: &str) -> FriendName {
+		FriendName::try_from_name(name)
+			.unwrap_or_else(|| panic!("Unknown friend name {}", name))
+	}
+
+	/// For names from files the player can edit, or that an older version wrote
+	pub fn try_from_name(name: &str) -> Option<FriendName> {
 		let name = name.trim_start_matches("FRIEND_");
 		let (name, _) = name.split_once('.').unwrap_or((name, ""));
 
 		match name {
-			"dolphin" => FriendName::Dolphin,
-			"fish" => FriendName::Fish,
-			"boat_boy" => FriendName::BoatBoy,
-			"boat_boy2" => FriendName::BoatBoy2,
-			_ => panic!("Unknown friend name {}", name),
+			"dolphin" => Some(FriendName::Dolphin),
+			"fish" => Some(FriendName::Fish),
+			"boat_boy" => Some(FriendName::BoatBoy),
+			"boat_boy2" => Some(FriendName::BoatBoy2),
+			_ => None,
+		}
+	}
+
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			FriendName::Dolphin => "dolphin",
+			FriendName::Fish => "fish",
+			FriendName::BoatBoy => "boat_boy",
+			FriendName::BoatBoy2 => "boat_boy2",
 		}
 	}
 
 	pub fn is_fish(&self) -> bool {
 		matches!(self, FriendName::Dolphin | FriendName::Fish)
 	}
+
+	/// How much this species likes another, from -1 to 1. Friends keep closer to those they like in formation.
+	pub fn relationship(&self, other: FriendName) -> f32 {
+		use FriendName::*;
+
+		match (*self, other) {
+			(Dolphin, Fish) | (Fish, Dolphin) => 1.0,
+			(Dolphin, BoatBoy) | (BoatBoy, Dolphin) => 0.5,
+			(BoatBoy, BoatBoy2) | (BoatBoy2, BoatBoy) => -1.0,
+			(Fish, BoatBoy2) | (BoatBoy2, Fish) => -0.5,
+			_ => 0.0,
+		}
+	}
+
+	pub fn temperament(&self) -> Temperament {
+		match self {
+			FriendName::Fish | FriendName::BoatBoy2 => Temperament::Shy,
+			FriendName::Dolphin | FriendName::BoatBoy => Temperament::Curious,
+		}
+	}
 }
 
 
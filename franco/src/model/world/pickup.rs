@@ -0,0 +1,118 @@
+use crate::prelude::*;
+
+/// Boats passing within this distance of a pickup collect it
+pub const PICKUP_COLLECT_DISTANCE: f32 = 1.5;
+
+/// Pickups within this distance of a boat are marked on the chart
+pub const PICKUP_DISCOVER_DISTANCE: f32 = 15.0;
+
+/// How many pickups are scattered across the map on top of any placed in the map scene
+pub const PICKUP_SCATTER_COUNT: usize = 24;
+
+/// Scattered pickups keep at least this far from islands so they can always be reached
+const PICKUP_ISLAND_CLEARANCE: f32 = 6.0;
+
+/// Maps too crowded with islands to find clear spots within this many tries get fewer pickups
+const PICKUP_SCATTER_MAX_ATTEMPTS: usize = PICKUP_SCATTER_COUNT * 16;
+
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PickupKind {
+	Bottle,
+	Crate,
+	Shell,
+}
+
+impl PickupKind {
+	pub const ALL: [PickupKind; 3] = [PickupKind::Bottle, PickupKind::Crate, PickupKind::Shell];
+
+	pub fn from_name(name: &str) -> PickupKind {
+		let name = name.trim_start_matches("PICKUP_");
+		let (name, _) = name.split_once('.').unwrap_or((name, ""));
+
+		match name {
+			"bottle" => PickupKind::Bottle,
+			"crate" => PickupKind::Crate,
+			"shell" => PickupKind::Shell,
+			_ => panic!("Unknown pickup kind {}", name),
+		}
+	}
+}
+
+
+#[derive(Debug)]
+pub struct Pickup {
+	pub kind: PickupKind,
+	pub map_position: Vec2,
+	pub rotation: f32,
+
+	/// Offsets each pickup's bobbing so they don't all move in lockstep
+	pub bob_phase: f32,
+
+	/// Set once a boat has come close enough to see it - only discovered pickups are shown on the chart
+	pub discovered: bool,
+	pub collected: bool,
+}
+
+impl Pickup {
+	fn new(kind: PickupKind, map_position: Vec2, rotation: f32) -> Pickup {
+		Pickup {
+			kind,
+			map_position,
+			rotation,
+
+			bob_phase: rotation,
+			discovered: false,
+			collected: false,
+		}
+	}
+}
+
+
+pub fn load_pickups(scene: toy::SceneRef<'_>, map: &super::Map) -> Vec<Pickup> {
+	let mut pickups: Vec<_> = scene.entities_with_prefix("PICKUP_")
+		.map(|entity| Pickup::new(
+			PickupKind::from_name(&entity.name),
+			entity.position.to_xz() * Vec2::new(1.0, -1.0),
+			entity.rotation.yaw(),
+		))
+		.collect();
+
+	// Scattered pickups are placed from a fixed sequence rather than randomly,
+	// so that every player in a networked game sees them in the same places
+	let mut sequence = (0..).map(scatter_value);
+	let mut next = || sequence.next().unwrap();
+	let mut num_scattered = 0;
+
+	for _ in 0..PICKUP_SCATTER_MAX_ATTEMPTS {
+		if num_scattered >= PICKUP_SCATTER_COUNT {
+			break
+		}
+
+		let map_position = (Vec2::new(next(), next()) - Vec2::splat(0.5)) * map.size;
+		let kind = PickupKind::ALL[(next() * PickupKind::ALL.len() as f32) as usize];
+		let rotation = next() * TAU;
+
+		let blocked = map.nearest_island(map_position)
+			.map_or(false, |island| (island - map_position).length() < PICKUP_ISLAND_CLEARANCE);
+
+		if !blocked {
+			pickups.push(Pickup::new(kind, map_position, rotation));
+			num_scattered += 1;
+		}
+	}
+
+	pickups
+}
+
+/// A cheap integer hash mapped to [0, 1)
+pub(super) fn scatter_value(index: u32) -> f32 {
+	let mut x = index.wrapping_mul(0x9E37_79B9) ^ 0x85EB_CA6B;
+	x ^= x >> 16;
+	x = x.wrapping_mul(0x7FEB_352D);
+	x ^= x >> 15;
+	x = x.wrapping_mul(0x846C_A68B);
+	x ^= x >> 16;
+
+	(x >> 8) as f32 / (1u32 << 24) as f32
+}
@@ -0,0 +1,86 @@
+use crate::prelude::*;
+
+/// Boats sailing into a harbour zone slower than this are taken in to moor
+pub const PORT_DOCK_MAX_SPEED: f32 = 0.8;
+pub const PORT_ZONE_RADIUS: f32 = 2.0;
+
+/// Seconds taken to ease a boat from where it entered the harbour onto its mooring
+pub const PORT_MOORING_TIME: f32 = 2.5;
+
+
+/// A mooring at an island harbour, placed by a `PORT_` entity in the island's scene
+#[derive(Debug)]
+pub struct Port {
+	pub map_position: Vec2,
+
+	/// The heading of a moored boat - ports face out to sea, so boats leave by sailing straight ahead
+	pub heading: f32,
+}
+
+
+/// Ports are authored relative to island scenes, so every island of a kind gets the same harbours
+pub fn load_ports<'t>(island_scenes: impl Iterator<Item=toy::SceneRef<'t>>, map: &super::Map) -> Vec<Port> {
+	let mut ports = Vec::new();
+
+	for scene in island_scenes {
+		let ty = super::MapObjectType::from_name(&scene.name);
+
+		let islands = map.objects.iter()
+			.filter(|object| object.ty == ty);
+
+		for island in islands {
+			let island_transform = Mat3x4::rotate_y(island.rotation);
+
+			for entity in scene.entities_with_prefix("PORT_") {
+				// Island scenes are in world units, and are placed without the map's y flip
+				let world_offset = island_transform * entity.position;
+				let map_offset = world_offset.to_xz() * Vec2::new(1.0, -1.0) / super::MAP_SCALE;
+
+				ports.push(Port {
+					map_position: island.map_position + map_offset,
+					heading: island.rotation + entity.rotation.yaw(),
+				});
+			}
+		}
+	}
+
+	ports
+}
+
+
+/// Where a boat is relative to the harbours of the world
+#[derive(Copy, Clone, Debug)]
+pub enum HarbourState {
+	AtSea,
+
+	/// Easing from where the boat entered the harbour zone onto the mooring, with `phase` going from 0 to 1
+	Mooring {
+		port: usize,
+		from_position: Vec2,
+		from_heading: f32,
+		phase: f32,
+	},
+
+	Moored {
+		port: usize,
+	},
+
+	/// Sailing back out - the boat can't moor again until it has cleared the harbour zone
+	Departing {
+		port: usize,
+	},
+}
+
+impl HarbourState {
+	/// The port a boat is mooring at or moored to
+	pub fn port(&self) -> Option<usize> {
+		match *self {
+			HarbourState::Mooring{port, ..} | HarbourState::Moored{port} => Some(port),
+			_ => None,
+		}
+	}
+
+	pub fn is_in_port(&self) -> bool {
+		self.port().is_some()
+	}
+}
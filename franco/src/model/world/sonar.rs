@@ -0,0 +1,54 @@
+use crate::prelude::*;
+
+/// Seconds between pings from the same boat
+pub const SONAR_COOLDOWN: f32 = 6.0;
+
+/// How far a ping travels before fading out, and how fast its ring expands
+pub const SONAR_RANGE: f32 = 40.0;
+pub const SONAR_RING_SPEED: f32 = 25.0;
+
+/// How long a friend stays outlined after being hit by a ping
+pub const SONAR_REVEAL_TIME: f32 = 4.0;
+
+
+/// An expanding ring of sound from a boat
+#[derive(Copy, Clone, Debug)]
+pub struct SonarPing {
+	pub origin: Vec2,
+	pub radius: f32,
+}
+
+impl SonarPing {
+	pub fn new(origin: Vec2) -> SonarPing {
+		SonarPing {
+			origin,
+			radius: 0.0,
+		}
+	}
+
+	pub fn is_finished(&self) -> bool {
+		self.radius >= SONAR_RANGE
+	}
+
+	/// Fades from 1 to 0 as the ring reaches the edge of its range
+	pub fn strength(&self) -> f32 {
+		(1.0 - self.radius / SONAR_RANGE).clamp(0.0, 1.0)
+	}
+}
+
+
+/// What a ping learned about a friend
+#[derive(Copy, Clone, Debug)]
+pub struct SonarContact {
+	/// Where the friend was when the ping reached them - the chart shows this rather than where they are now
+	pub map_position: Vec2,
+
+	/// Counts down from `SONAR_REVEAL_TIME` while the friend is outlined through the water
+	pub reveal_timer: f32,
+}
+
+impl SonarContact {
+	pub fn is_revealed(&self) -> bool {
+		self.reveal_timer > 0.0
+	}
+}
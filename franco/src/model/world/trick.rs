@@ -0,0 +1,146 @@
+use crate::prelude::*;
+
+use model::FriendName;
+
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TrickKind {
+	/// The original jump and double spin
+	Jump,
+
+	/// Leaps clear of the water nose first
+	Breach,
+	BarrelRoll,
+
+	/// Stands upright and skims backwards along the surface
+	TailWalk,
+
+	/// Rocks hard from side to side, throwing up spray
+	Spray,
+
+	/// Spins on the spot
+	Doughnut,
+}
+
+impl TrickKind {
+	pub const ALL: [TrickKind; 6] = [
+		TrickKind::Jump,
+		TrickKind::Breach,
+		TrickKind::BarrelRoll,
+		TrickKind::TailWalk,
+		TrickKind::Spray,
+		TrickKind::Doughnut,
+	];
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			TrickKind::Jump => "jump",
+			TrickKind::Breach => "breach",
+			TrickKind::BarrelRoll => "barrel roll",
+			TrickKind::TailWalk => "tail walk",
+			TrickKind::Spray => "spray",
+			TrickKind::Doughnut => "doughnut",
+		}
+	}
+}
+
+
+/// A trick in progress
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Trick {
+	pub kind: TrickKind,
+
+	/// How long the whole trick takes, in seconds
+	pub duration: f32,
+
+	/// Progress through the trick, from 0 to 1
+	pub phase: f32,
+}
+
+impl Trick {
+	pub fn new(entry: TrickEntry) -> Trick {
+		Trick {
+			kind: entry.kind,
+			duration: entry.duration,
+			phase: 0.0,
+		}
+	}
+}
+
+
+/// One trick a species knows, how likely it is to be picked and how long it takes them
+#[derive(Copy, Clone, Debug)]
+pub struct TrickEntry {
+	pub kind: TrickKind,
+	pub weight: f32,
+	pub duration: f32,
+}
+
+const fn entry(kind: TrickKind, weight: f32, duration: f32) -> TrickEntry {
+	TrickEntry { kind, weight, duration }
+}
+
+
+use TrickKind::*;
+
+const DOLPHIN_TRICKS: &[TrickEntry] = &[
+	entry(Jump, 1.0, 1.4),
+	entry(Breach, 2.0, 1.6),
+	entry(BarrelRoll, 1.5, 1.2),
+	entry(TailWalk, 1.0, 2.2),
+];
+
+const FISH_TRICKS: &[TrickEntry] = &[
+	entry(Jump, 2.0, 1.0),
+	entry(BarrelRoll, 1.0, 0.9),
+	entry(Breach, 0.5, 1.2),
+];
+
+const BOAT_BOY_TRICKS: &[TrickEntry] = &[
+	entry(Jump, 1.0, 1.4),
+	entry(Spray, 2.0, 1.5),
+	entry(Doughnut, 1.5, 2.0),
+];
+
+const BOAT_BOY2_TRICKS: &[TrickEntry] = &[
+	entry(Jump, 0.5, 1.4),
+	entry(Spray, 1.0, 1.5),
+	entry(Doughnut, 2.0, 2.5),
+];
+
+
+impl FriendName {
+	pub fn trick_library(&self) -> &'static [TrickEntry] {
+		match self {
+			FriendName::Dolphin => DOLPHIN_TRICKS,
+			FriendName::Fish => FISH_TRICKS,
+			FriendName::BoatBoy => BOAT_BOY_TRICKS,
+			FriendName::BoatBoy2 => BOAT_BOY2_TRICKS,
+		}
+	}
+
+	/// Picks a trick from this species' library at random, according to its weights
+	pub fn pick_trick(&self) -> Trick {
+		let library = self.trick_library();
+		let total_weight: f32 = library.iter().map(|entry| entry.weight).sum();
+
+		let mut choice = rand::random::<f32>() * total_weight;
+
+		for &entry in library {
+			if choice < entry.weight {
+				return Trick::new(entry)
+			}
+
+			choice -= entry.weight;
+		}
+
+		Trick::new(*library.last().unwrap())
+	}
+
+	/// This species' version of `kind`, if it knows it
+	pub fn find_trick(&self, kind: TrickKind) -> Option<Trick> {
+		self.trick_library().iter()
+			.find(|entry| entry.kind == kind)
+			.map(|&entry| Trick::new(entry))
+	}
+}
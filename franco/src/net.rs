@@ -0,0 +1,71 @@
+use crate::prelude::*;
+use std::net::SocketAddr;
+
+pub mod protocol;
+pub use protocol::*;
+
+pub mod transport;
+pub use transport::*;
+
+pub mod host;
+pub use host::*;
+
+pub mod client;
+pub use client::*;
+
+
+pub const DEFAULT_PORT: u16 = 21021;
+
+/// Silence from the other side for longer than this drops the connection
+pub const CONNECTION_TIMEOUT: f32 = 5.0;
+
+/// Snapshots are sent every this many frames
+pub const SNAPSHOT_INTERVAL: u32 = 2;
+
+/// Clients render remote entities this far in the past, so there are always two snapshots to interpolate between
+pub const INTERPOLATION_DELAY: f32 = 0.1;
+
+
+#[derive(Debug, Clone)]
+pub enum NetOptions {
+	Offline,
+	Host { port: u16 },
+	Join { host_addr: SocketAddr },
+
+	/// Hosts a session with an in-process client connected over a fake network. Useful for testing without a second machine
+	Loopback,
+}
+
+impl NetOptions {
+	/// Parses `--host [port]`, `--join <addr:port>` or `--loopback` from the command line
+	pub fn from_args(args: impl Iterator<Item=String>) -> Result<NetOptions> {
+		let mut args = args.peekable();
+
+		while let Some(arg) = args.next() {
+			match arg.as_str() {
+				"--host" => {
+					let port = match args.next_if(|arg| !arg.starts_with("--")) {
+						Some(port) => port.parse()?,
+						None => DEFAULT_PORT,
+					};
+
+					return Ok(NetOptions::Host { port })
+				}
+
+				"--join" => {
+					let host_addr = args.next()
+						.ok_or("--join requires a host address")?
+						.parse()?;
+
+					return Ok(NetOptions::Join { host_addr })
+				}
+
+				"--loopback" => return Ok(NetOptions::Loopback),
+
+				_ => {}
+			}
+		}
+
+		Ok(NetOptions::Offline)
+	}
+}
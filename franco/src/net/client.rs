@@ -0,0 +1,222 @@
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+
+use crate::net::{Transport, CONNECTION_TIMEOUT};
+use crate::net::host::send;
+use crate::net::protocol::*;
+
+/// How long to keep retrying the handshake before giving up
+pub const CONNECT_TIMEOUT: f32 = 10.0;
+const CONNECT_RETRY_INTERVAL: f32 = 0.5;
+
+/// Received snapshots are kept both for interpolation and to decode deltas against
+const SNAPSHOT_HISTORY: usize = 64;
+
+/// Inputs the host hasn't acknowledged yet are kept for replaying over its snapshots, up to this many
+const INPUT_HISTORY: usize = 120;
+
+
+#[derive(Debug)]
+pub enum ClientEvent {
+	Connected(u16),
+	Disconnected,
+	Snapshot,
+}
+
+
+#[derive(Copy, Clone, Debug)]
+pub enum ClientState {
+	Connecting {
+		elapsed: f32,
+		retry_timer: f32,
+	},
+
+	Connected {
+		player_id: u16,
+	},
+
+	Disconnected,
+}
+
+
+pub struct Client {
+	transport: Box<dyn Transport>,
+	host_addr: SocketAddr,
+	state: ClientState,
+
+	time_since_packet: f32,
+	input_sequence: u32,
+
+	/// Ordered by sequence, oldest first
+	snapshots: VecDeque<Snapshot>,
+
+	/// Sent inputs the latest snapshot doesn't account for yet, oldest first
+	unacked_inputs: VecDeque<BoatInput>,
+}
+
+impl Client {
+	pub fn new(transport: Box<dyn Transport>, host_addr: SocketAddr) -> Client {
+		Client {
+			transport,
+			host_addr,
+			state: ClientState::Connecting { elapsed: 0.0, retry_timer: 0.0 },
+
+			time_since_packet: 0.0,
+			input_sequence: 0,
+
+			snapshots: VecDeque::new(),
+			unacked_inputs: VecDeque::new(),
+		}
+	}
+
+	pub fn state(&self) -> ClientState { self.state }
+
+	pub fn player_id(&self) -> Option<u16> {
+		match self.state {
+			ClientState::Connected { player_id } => Some(player_id),
+			_ => None,
+		}
+	}
+
+	pub fn snapshots(&self) -> &VecDeque<Snapshot> { &self.snapshots }
+	pub fn unacked_inputs(&self) -> &VecDeque<BoatInput> { &self.unacked_inputs }
+
+	pub fn poll(&mut self, dt: f32) -> Vec<ClientEvent> {
+		let mut events = Vec::new();
+
+		if let ClientState::Connecting { elapsed, retry_timer } = self.state {
+			let elapsed = elapsed + dt;
+			let mut retry_timer = retry_timer - dt;
+
+			if retry_timer <= 0.0 {
+				send(&mut *self.transport, &Message::Connect, self.host_addr);
+				retry_timer = CONNECT_RETRY_INTERVAL;
+			}
+
+			self.state = ClientState::Connecting { elapsed, retry_timer };
+
+			if elapsed > CONNECT_TIMEOUT {
+				eprintln!("[net] couldn't connect to {}", self.host_addr);
+				self.state = ClientState::Disconnected;
+				events.push(ClientEvent::Disconnected);
+			}
+		}
+
+		let mut buf = [0; MAX_PACKET_SIZE];
+
+		loop {
+			match self.transport.recv_from(&mut buf) {
+				Ok(Some((len, addr))) if addr == self.host_addr => self.handle_packet(&buf[..len], &mut events),
+				Ok(Some(_)) => continue,
+				Ok(None) => break,
+				Err(err) => {
+					eprintln!("[net] client receive failed: {}", err);
+					break
+				}
+			}
+		}
+
+		if let ClientState::Connected {..} = self.state {
+			self.time_since_packet += dt;
+
+			if self.time_since_packet > CONNECTION_TIMEOUT {
+				eprintln!("[net] connection to host timed out");
+				self.state = ClientState::Disconnected;
+				events.push(ClientEvent::Disconnected);
+			}
+		}
+
+		events
+	}
+
+	pub fn send_input(&mut self, sail_speed: i8, wheel_angle: f32, paint: u8) {
+		if self.player_id().is_none() {
+			return
+		}
+
+		self.input_sequence += 1;
+
+		let input = BoatInput {
+			sequence: self.input_sequence,
+			ack_snapshot: self.snapshots.back().map(|s| s.sequence),
+			sail_speed,
+			wheel_angle,
+			paint,
+		};
+
+		send(&mut *self.transport, &Message::Input(input), self.host_addr);
+
+		self.unacked_inputs.push_back(input);
+
+		while self.unacked_inputs.len() > INPUT_HISTORY {
+			self.unacked_inputs.pop_front();
+		}
+	}
+
+	pub fn shutdown(&mut self) {
+		if self.player_id().is_some() {
+			send(&mut *self.transport, &Message::Disconnect, self.host_addr);
+		}
+
+		self.state = ClientState::Disconnected;
+	}
+
+
+	fn handle_packet(&mut self, data: &[u8], events: &mut Vec<ClientEvent>) {
+		let snapshots = &self.snapshots;
+		let find_baseline = |sequence| snapshots.iter().find(|s: &&Snapshot| s.sequence == sequence);
+
+		let message = match Message::decode(data, find_baseline) {
+			Ok(message) => message,
+			Err(err) => {
+				eprintln!("[net] client dropping packet: {}", err);
+				return
+			}
+		};
+
+		self.time_since_packet = 0.0;
+
+		match (message, self.state) {
+			(Message::Accept { player_id }, ClientState::Connecting {..}) => {
+				println!("[net] connected to {} as player {}", self.host_addr, player_id);
+				self.state = ClientState::Connected { player_id };
+				events.push(ClientEvent::Connected(player_id));
+			}
+
+			(Message::Reject, ClientState::Connecting {..}) => {
+				eprintln!("[net] {} rejected connection", self.host_addr);
+				self.state = ClientState::Disconnected;
+				events.push(ClientEvent::Disconnected);
+			}
+
+			(Message::Snapshot(packet), ClientState::Connected {..}) => {
+				let snapshot = packet.snapshot;
+
+				// Drop stale or duplicated snapshots
+				if self.snapshots.iter().any(|s| s.sequence >= snapshot.sequence) {
+					return
+				}
+
+				if let Some(ack_input) = packet.ack_input {
+					self.unacked_inputs.retain(|input| input.sequence > ack_input);
+				}
+
+				self.snapshots.push_back(snapshot);
+
+				while self.snapshots.len() > SNAPSHOT_HISTORY {
+					self.snapshots.pop_front();
+				}
+
+				events.push(ClientEvent::Snapshot);
+			}
+
+			(Message::Disconnect, ClientState::Connected {..}) => {
+				println!("[net] host closed the session");
+				self.state = ClientState::Disconnected;
+				events.push(ClientEvent::Disconnected);
+			}
+
+			_ => {}
+		}
+	}
+}
@@ -0,0 +1,202 @@
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+
+use crate::net::{Transport, CONNECTION_TIMEOUT};
+use crate::net::protocol::*;
+
+pub const MAX_CLIENTS: usize = 3;
+
+/// How many sent snapshots are kept around to delta encode against
+const SNAPSHOT_HISTORY: usize = 64;
+
+
+#[derive(Debug)]
+pub enum HostEvent {
+	ClientJoined(u16),
+	ClientLeft(u16),
+	Input(u16, BoatInput),
+}
+
+
+struct ClientConnection {
+	addr: SocketAddr,
+	player_id: u16,
+
+	time_since_packet: f32,
+	last_input_sequence: Option<u32>,
+	acked_snapshot: Option<u32>,
+}
+
+
+pub struct Host {
+	transport: Box<dyn Transport>,
+	clients: Vec<ClientConnection>,
+	next_player_id: u16,
+
+	snapshot_history: VecDeque<Snapshot>,
+	next_snapshot_sequence: u32,
+}
+
+impl Host {
+	pub fn new(transport: Box<dyn Transport>) -> Host {
+		Host {
+			transport,
+			clients: Vec::new(),
+
+			// The host itself is always player 0
+			next_player_id: 1,
+
+			snapshot_history: VecDeque::new(),
+			next_snapshot_sequence: 0,
+		}
+	}
+
+	pub fn num_clients(&self) -> usize { self.clients.len() }
+
+	pub fn poll(&mut self, dt: f32) -> Vec<HostEvent> {
+		let mut events = Vec::new();
+		let mut buf = [0; MAX_PACKET_SIZE];
+
+		loop {
+			match self.transport.recv_from(&mut buf) {
+				Ok(Some((len, addr))) => self.handle_packet(&buf[..len], addr, &mut events),
+				Ok(None) => break,
+				Err(err) => {
+					eprintln!("[net] host receive failed: {}", err);
+					break
+				}
+			}
+		}
+
+		for client in self.clients.iter_mut() {
+			client.time_since_packet += dt;
+		}
+
+		let (timed_out, connected) = std::mem::take(&mut self.clients).into_iter()
+			.partition(|client| client.time_since_packet > CONNECTION_TIMEOUT);
+
+		self.clients = connected;
+
+		for client in timed_out {
+			println!("[net] player {} timed out", client.player_id);
+			events.push(HostEvent::ClientLeft(client.player_id));
+		}
+
+		events
+	}
+
+	/// Sends `boats` and `friends` to every client, delta encoded against whatever each has last acknowledged
+	pub fn broadcast_snapshot(&mut self, boats: Vec<BoatSnapshot>, friends: Vec<FriendSnapshot>) {
+		let snapshot = Snapshot {
+			sequence: self.next_snapshot_sequence,
+			boats,
+			friends,
+		};
+
+		self.next_snapshot_sequence += 1;
+
+		for client in self.clients.iter() {
+			let baseline = client.acked_snapshot
+				.and_then(|sequence| self.snapshot_history.iter().find(|s| s.sequence == sequence))
+				.cloned();
+
+			let message = Message::Snapshot(SnapshotPacket {
+				snapshot: snapshot.clone(),
+				baseline,
+				ack_input: client.last_input_sequence,
+			});
+
+			send(&mut *self.transport, &message, client.addr);
+		}
+
+		self.snapshot_history.push_back(snapshot);
+
+		while self.snapshot_history.len() > SNAPSHOT_HISTORY {
+			self.snapshot_history.pop_front();
+		}
+	}
+
+	pub fn shutdown(&mut self) {
+		for client in self.clients.drain(..) {
+			send(&mut *self.transport, &Message::Disconnect, client.addr);
+		}
+	}
+
+
+	fn handle_packet(&mut self, data: &[u8], addr: SocketAddr, events: &mut Vec<HostEvent>) {
+		// Clients never send snapshots, so there is never a baseline to find
+		let message = match Message::decode(data, |_| None) {
+			Ok(message) => message,
+			Err(err) => {
+				eprintln!("[net] host dropping packet from {}: {}", addr, err);
+				return
+			}
+		};
+
+		let client_idx = self.clients.iter().position(|client| client.addr == addr);
+
+		if let Some(idx) = client_idx {
+			self.clients[idx].time_since_packet = 0.0;
+		}
+
+		match (message, client_idx) {
+			// Our Accept may have been lost - send it again
+			(Message::Connect, Some(idx)) => {
+				let player_id = self.clients[idx].player_id;
+				send(&mut *self.transport, &Message::Accept { player_id }, addr);
+			}
+
+			(Message::Connect, None) => {
+				if self.clients.len() >= MAX_CLIENTS {
+					send(&mut *self.transport, &Message::Reject, addr);
+					return
+				}
+
+				let player_id = self.next_player_id;
+				self.next_player_id += 1;
+
+				self.clients.push(ClientConnection {
+					addr,
+					player_id,
+
+					time_since_packet: 0.0,
+					last_input_sequence: None,
+					acked_snapshot: None,
+				});
+
+				println!("[net] player {} joined from {}", player_id, addr);
+
+				send(&mut *self.transport, &Message::Accept { player_id }, addr);
+				events.push(HostEvent::ClientJoined(player_id));
+			}
+
+			(Message::Input(input), Some(idx)) => {
+				let client = &mut self.clients[idx];
+
+				// Inputs can arrive out of order - only the newest matters
+				if client.last_input_sequence.map_or(false, |last| input.sequence <= last) {
+					return
+				}
+
+				client.last_input_sequence = Some(input.sequence);
+				client.acked_snapshot = input.ack_snapshot;
+				events.push(HostEvent::Input(client.player_id, input));
+			}
+
+			(Message::Disconnect, Some(idx)) => {
+				let client = self.clients.remove(idx);
+				println!("[net] player {} left", client.player_id);
+				events.push(HostEvent::ClientLeft(client.player_id));
+			}
+
+			_ => {}
+		}
+	}
+}
+
+
+pub(super) fn send(transport: &mut dyn Transport, message: &Message, addr: SocketAddr) {
+	if let Err(err) = transport.send_to(&message.encode(), addr) {
+		eprintln!("[net] send to {} failed: {}", addr, err);
+	}
+}
@@ -0,0 +1,600 @@
+use crate::prelude::*;
+use model::{FriendState, Trick, TrickKind};
+
+/// Identifies franco packets, so stray traffic on the port can be ignored
+pub const PROTOCOL_MAGIC: u32 = 0x4652_3231; // "FR21"
+pub const PROTOCOL_VERSION: u8 = 6;
+
+pub const MAX_PACKET_SIZE: usize = 1200;
+
+
+#[derive(Debug)]
+pub struct DecodeError(pub &'static str);
+
+impl std::fmt::Display for DecodeError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Failed to decode packet: {}", self.0)
+	}
+}
+
+impl Error for DecodeError {}
+
+
+
+#[derive(Debug, Clone)]
+pub enum Message {
+	// Client -> Host
+	Connect,
+	Input(BoatInput),
+
+	// Host -> Client
+	Accept { player_id: u16 },
+	Reject,
+	Snapshot(SnapshotPacket),
+
+	// Either direction
+	Disconnect,
+}
+
+
+/// The controls a client has over its own boat - sent every frame and doubling as a heartbeat
+#[derive(Debug, Copy, Clone, Default)]
+pub struct BoatInput {
+	pub sequence: u32,
+
+	/// The most recent snapshot the client has received, for delta compression
+	pub ack_snapshot: Option<u32>,
+
+	/// 0 when anchored
+	pub sail_speed: i8,
+	pub wheel_angle: f32,
+
+	/// Chosen locally, so the host can pass it on to everyone else
+	pub paint: u8,
+}
+
+
+/// A full snapshot, delta encoded against `baseline` if the client has acknowledged it
+#[derive(Debug, Clone)]
+pub struct SnapshotPacket {
+	pub snapshot: Snapshot,
+	pub baseline: Option<Snapshot>,
+
+	/// The newest of the receiving client's inputs that the host had applied when the snapshot was taken
+	pub ack_input: Option<u32>,
+}
+
+
+
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+	pub sequence: u32,
+	pub boats: Vec<BoatSnapshot>,
+	pub friends: Vec<FriendSnapshot>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct BoatSnapshot {
+	pub player_id: u16,
+	pub map_position: Vec2,
+	pub heading: f32,
+	pub speed: f32,
+	pub sail_speed: i8,
+	pub paint: u8,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct FriendSnapshot {
+	pub map_position: Vec2,
+	pub heading: f32,
+	pub speed: f32,
+	pub state: FriendState,
+	pub met_player: bool,
+	pub trust: f32,
+}
+
+
+
+impl BoatSnapshot {
+	fn unchanged_from(&self, baseline: &BoatSnapshot) -> bool {
+		self.map_position.to_array() == baseline.map_position.to_array()
+			&& self.heading == baseline.heading
+			&& self.speed == baseline.speed
+			&& self.sail_speed == baseline.sail_speed
+			&& self.paint == baseline.paint
+	}
+}
+
+impl FriendSnapshot {
+	fn unchanged_from(&self, baseline: &FriendSnapshot) -> bool {
+		self.map_position.to_array() == baseline.map_position.to_array()
+			&& self.heading == baseline.heading
+			&& self.speed == baseline.speed
+			&& self.state == baseline.state
+			&& self.met_player == baseline.met_player
+			&& self.trust == baseline.trust
+	}
+}
+
+
+
+impl Message {
+	pub fn encode(&self) -> Vec<u8> {
+		let mut w = Writer::new();
+		w.put_u32(PROTOCOL_MAGIC);
+		w.put_u8(PROTOCOL_VERSION);
+
+		match self {
+			Message::Connect => w.put_u8(0),
+
+			Message::Input(input) => {
+				w.put_u8(1);
+				w.put_u32(input.sequence);
+				w.put_option_u32(input.ack_snapshot);
+				w.put_u8(input.sail_speed as u8);
+				w.put_f32(input.wheel_angle);
+				w.put_u8(input.paint);
+			}
+
+			Message::Accept { player_id } => {
+				w.put_u8(2);
+				w.put_u16(*player_id);
+			}
+
+			Message::Reject => w.put_u8(3),
+
+			Message::Snapshot(packet) => {
+				w.put_u8(4);
+				packet.encode(&mut w);
+			}
+
+			Message::Disconnect => w.put_u8(5),
+		}
+
+		w.into_inner()
+	}
+
+	/// Snapshots are delta encoded, so decoding one requires the baseline it was encoded against.
+	/// `find_baseline` is used to look up previously received snapshots by sequence number.
+	pub fn decode<'s>(data: &[u8], find_baseline: impl FnOnce(u32) -> Option<&'s Snapshot>) -> std::result::Result<Message, DecodeError> {
+		let mut r = Reader::new(data);
+
+		if r.get_u32()? != PROTOCOL_MAGIC {
+			return Err(DecodeError("bad magic"))
+		}
+
+		if r.get_u8()? != PROTOCOL_VERSION {
+			return Err(DecodeError("protocol version mismatch"))
+		}
+
+		let message = match r.get_u8()? {
+			0 => Message::Connect,
+
+			1 => Message::Input(BoatInput {
+				sequence: r.get_u32()?,
+				ack_snapshot: r.get_option_u32()?,
+				sail_speed: r.get_u8()? as i8,
+				wheel_angle: r.get_f32()?,
+				paint: r.get_u8()?,
+			}),
+
+			2 => Message::Accept { player_id: r.get_u16()? },
+			3 => Message::Reject,
+			4 => Message::Snapshot(SnapshotPacket::decode(&mut r, find_baseline)?),
+			5 => Message::Disconnect,
+
+			_ => return Err(DecodeError("unknown message type")),
+		};
+
+		Ok(message)
+	}
+}
+
+
+
+impl SnapshotPacket {
+	fn encode(&self, w: &mut Writer) {
+		let Snapshot {sequence, boats, friends} = &self.snapshot;
+		let baseline = self.baseline.as_ref();
+
+		w.put_u32(*sequence);
+		w.put_option_u32(baseline.map(|b| b.sequence));
+		w.put_option_u32(self.ack_input);
+
+		// Boats come and go, so are matched against the baseline by id
+		w.put_u16(boats.len() as u16);
+		for boat in boats {
+			let baseline_boat = baseline.and_then(|b| b.boats.iter().find(|b| b.player_id == boat.player_id));
+
+			w.put_u16(boat.player_id);
+
+			if baseline_boat.map_or(false, |b| boat.unchanged_from(b)) {
+				w.put_u8(0);
+				continue
+			}
+
+			w.put_u8(1);
+			w.put_vec2(boat.map_position);
+			w.put_f32(boat.heading);
+			w.put_f32(boat.speed);
+			w.put_u8(boat.sail_speed as u8);
+			w.put_u8(boat.paint);
+		}
+
+		// Friends never change, so are matched by index
+		w.put_u16(friends.len() as u16);
+		for (idx, friend) in friends.iter().enumerate() {
+			let baseline_friend = baseline.and_then(|b| b.friends.get(idx));
+
+			if baseline_friend.map_or(false, |b| friend.unchanged_from(b)) {
+				w.put_u8(0);
+				continue
+			}
+
+			w.put_u8(1);
+			w.put_vec2(friend.map_position);
+			w.put_f32(friend.heading);
+			w.put_f32(friend.speed);
+			w.put_friend_state(friend.state);
+			w.put_u8(friend.met_player as u8);
+			w.put_f32(friend.trust);
+		}
+	}
+
+	fn decode<'s>(r: &mut Reader<'_>, find_baseline: impl FnOnce(u32) -> Option<&'s Snapshot>) -> std::result::Result<SnapshotPacket, DecodeError> {
+		let sequence = r.get_u32()?;
+
+		let baseline = match r.get_option_u32()? {
+			Some(baseline_sequence) => Some(find_baseline(baseline_sequence)
+				.ok_or(DecodeError("missing baseline"))?
+				.clone()),
+
+			None => None,
+		};
+
+		let ack_input = r.get_option_u32()?;
+
+		let num_boats = r.get_u16()?;
+		let mut boats = Vec::with_capacity(num_boats as usize);
+
+		for _ in 0..num_boats {
+			let player_id = r.get_u16()?;
+
+			if r.get_u8()? == 0 {
+				let boat = baseline.as_ref()
+					.and_then(|b| b.boats.iter().find(|b| b.player_id == player_id))
+					.ok_or(DecodeError("unchanged boat missing from baseline"))?;
+
+				boats.push(*boat);
+				continue
+			}
+
+			boats.push(BoatSnapshot {
+				player_id,
+				map_position: r.get_vec2()?,
+				heading: r.get_f32()?,
+				speed: r.get_f32()?,
+				sail_speed: r.get_u8()? as i8,
+				paint: r.get_u8()?,
+			});
+		}
+
+		let num_friends = r.get_u16()?;
+		let mut friends = Vec::with_capacity(num_friends as usize);
+
+		for idx in 0..num_friends as usize {
+			if r.get_u8()? == 0 {
+				let friend = baseline.as_ref()
+					.and_then(|b| b.friends.get(idx))
+					.ok_or(DecodeError("unchanged friend missing from baseline"))?;
+
+				friends.push(*friend);
+				continue
+			}
+
+			friends.push(FriendSnapshot {
+				map_position: r.get_vec2()?,
+				heading: r.get_f32()?,
+				speed: r.get_f32()?,
+				state: r.get_friend_state()?,
+				met_player: r.get_u8()? != 0,
+				trust: r.get_f32()?,
+			});
+		}
+
+		Ok(SnapshotPacket {
+			snapshot: Snapshot { sequence, boats, friends },
+			baseline,
+			ack_input,
+		})
+	}
+}
+
+
+
+struct Writer {
+	data: Vec<u8>,
+}
+
+impl Writer {
+	fn new() -> Writer {
+		Writer { data: Vec::with_capacity(MAX_PACKET_SIZE) }
+	}
+
+	fn into_inner(self) -> Vec<u8> { self.data }
+
+	fn put_u8(&mut self, v: u8) { self.data.push(v) }
+	fn put_u16(&mut self, v: u16) { self.data.extend_from_slice(&v.to_le_bytes()) }
+	fn put_u32(&mut self, v: u32) { self.data.extend_from_slice(&v.to_le_bytes()) }
+	fn put_f32(&mut self, v: f32) { self.data.extend_from_slice(&v.to_le_bytes()) }
+
+	fn put_vec2(&mut self, v: Vec2) {
+		self.put_f32(v.x);
+		self.put_f32(v.y);
+	}
+
+	fn put_option_u32(&mut self, v: Option<u32>) {
+		match v {
+			Some(v) => { self.put_u8(1); self.put_u32(v); }
+			None => self.put_u8(0),
+		}
+	}
+
+	fn put_friend_state(&mut self, state: FriendState) {
+		match state {
+			FriendState::HangingOut => self.put_u8(0),
+			FriendState::Following => self.put_u8(1),
+			FriendState::DoingTricks(trick) => {
+				self.put_u8(2);
+				self.put_u8(trick.kind as u8);
+				self.put_f32(trick.duration);
+				self.put_f32(trick.phase);
+			}
+			FriendState::Fleeing(timer) => {
+				self.put_u8(3);
+				self.put_f32(timer);
+			}
+			FriendState::ReturningHome => self.put_u8(4),
+		}
+	}
+}
+
+
+
+struct Reader<'d> {
+	data: &'d [u8],
+}
+
+impl<'d> Reader<'d> {
+	fn new(data: &'d [u8]) -> Reader<'d> {
+		Reader { data }
+	}
+
+	fn take<const N: usize>(&mut self) -> std::result::Result<[u8; N], DecodeError> {
+		if self.data.len() < N {
+			return Err(DecodeError("unexpected end of packet"))
+		}
+
+		let (head, tail) = self.data.split_at(N);
+		self.data = tail;
+
+		let mut bytes = [0; N];
+		bytes.copy_from_slice(head);
+		Ok(bytes)
+	}
+
+	fn get_u8(&mut self) -> std::result::Result<u8, DecodeError> { Ok(self.take::<1>()?[0]) }
+	fn get_u16(&mut self) -> std::result::Result<u16, DecodeError> { Ok(u16::from_le_bytes(self.take()?)) }
+	fn get_u32(&mut self) -> std::result::Result<u32, DecodeError> { Ok(u32::from_le_bytes(self.take()?)) }
+	fn get_f32(&mut self) -> std::result::Result<f32, DecodeError> { Ok(f32::from_le_bytes(self.take()?)) }
+
+	fn get_vec2(&mut self) -> std::result::Result<Vec2, DecodeError> {
+		Ok(Vec2::new(self.get_f32()?, self.get_f32()?))
+	}
+
+	fn get_option_u32(&mut self) -> std::result::Result<Option<u32>, DecodeError> {
+		match self.get_u8()? {
+			0 => Ok(None),
+			_ => Ok(Some(self.get_u32()?)),
+		}
+	}
+
+	fn get_friend_state(&mut self) -> std::result::Result<FriendState, DecodeError> {
+		match self.get_u8()? {
+			0 => Ok(FriendState::HangingOut),
+			1 => Ok(FriendState::Following),
+			2 => {
+				let kind = *TrickKind::ALL.get(self.get_u8()? as usize)
+					.ok_or(DecodeError("unknown trick"))?;
+
+				Ok(FriendState::DoingTricks(Trick {
+					kind,
+					duration: self.get_f32()?,
+					phase: self.get_f32()?,
+				}))
+			}
+			3 => Ok(FriendState::Fleeing(self.get_f32()?)),
+			4 => Ok(FriendState::ReturningHome),
+			_ => Err(DecodeError("unknown friend state")),
+		}
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn boat(player_id: u16, x: f32) -> BoatSnapshot {
+		BoatSnapshot {
+			player_id,
+			map_position: Vec2::new(x, -2.5),
+			heading: 1.25,
+			speed: 3.0,
+			sail_speed: 2,
+			paint: 1,
+		}
+	}
+
+	fn friend(state: FriendState) -> FriendSnapshot {
+		FriendSnapshot {
+			map_position: Vec2::new(10.0, 20.0),
+			heading: -0.5,
+			speed: 1.5,
+			state,
+			met_player: true,
+			trust: 0.75,
+		}
+	}
+
+	fn trick(kind: TrickKind) -> FriendState {
+		FriendState::DoingTricks(Trick { kind, duration: 2.0, phase: 0.25 })
+	}
+
+	fn snapshot(sequence: u32, boats: Vec<BoatSnapshot>, friends: Vec<FriendSnapshot>) -> Snapshot {
+		Snapshot { sequence, boats, friends }
+	}
+
+	fn encode_snapshot(snapshot: &Snapshot, baseline: Option<&Snapshot>, ack_input: Option<u32>) -> Vec<u8> {
+		Message::Snapshot(SnapshotPacket {
+			snapshot: snapshot.clone(),
+			baseline: baseline.cloned(),
+			ack_input,
+		}).encode()
+	}
+
+	fn decode_snapshot(data: &[u8], baseline: Option<&Snapshot>) -> std::result::Result<SnapshotPacket, DecodeError> {
+		match Message::decode(data, |sequence| baseline.filter(|b| b.sequence == sequence))? {
+			Message::Snapshot(packet) => Ok(packet),
+			message => panic!("Expected a snapshot, got {:?}", message),
+		}
+	}
+
+	fn assert_snapshots_eq(a: &Snapshot, b: &Snapshot) {
+		assert_eq!(a.sequence, b.sequence);
+		assert_eq!(a.boats.len(), b.boats.len());
+		assert_eq!(a.friends.len(), b.friends.len());
+
+		for (a, b) in a.boats.iter().zip(b.boats.iter()) {
+			assert_eq!(a.player_id, b.player_id);
+			assert!(a.unchanged_from(b), "{:?} != {:?}", a, b);
+		}
+
+		for (a, b) in a.friends.iter().zip(b.friends.iter()) {
+			assert!(a.unchanged_from(b), "{:?} != {:?}", a, b);
+		}
+	}
+
+
+	#[test]
+	fn test_input_round_trip() {
+		let input = BoatInput {
+			sequence: 42,
+			ack_snapshot: Some(7),
+			sail_speed: -1,
+			wheel_angle: 0.5,
+			paint: 3,
+		};
+
+		let decoded = match Message::decode(&Message::Input(input).encode(), |_| None) {
+			Ok(Message::Input(decoded)) => decoded,
+			other => panic!("Expected an input, got {:?}", other),
+		};
+
+		assert_eq!(decoded.sequence, 42);
+		assert_eq!(decoded.ack_snapshot, Some(7));
+		assert_eq!(decoded.sail_speed, -1);
+		assert_eq!(decoded.wheel_angle, 0.5);
+		assert_eq!(decoded.paint, 3);
+	}
+
+	#[test]
+	fn test_snapshot_round_trip_without_baseline() {
+		let friends = vec![
+			friend(FriendState::HangingOut),
+			friend(FriendState::Following),
+			friend(trick(TrickKind::BarrelRoll)),
+			friend(FriendState::Fleeing(0.5)),
+			friend(FriendState::ReturningHome),
+		];
+
+		let snapshot = snapshot(3, vec![boat(0, 1.0), boat(2, 5.0)], friends);
+
+		let packet = decode_snapshot(&encode_snapshot(&snapshot, None, Some(11)), None).unwrap();
+		assert_snapshots_eq(&packet.snapshot, &snapshot);
+		assert!(packet.baseline.is_none());
+		assert_eq!(packet.ack_input, Some(11));
+	}
+
+	#[test]
+	fn test_snapshot_round_trip_with_baseline() {
+		let baseline = snapshot(3, vec![boat(0, 1.0), boat(2, 5.0)], vec![friend(FriendState::HangingOut), friend(FriendState::Following)]);
+
+		// Boat 2 and the first friend are unchanged, boat 3 is new
+		let mut friends = baseline.friends.clone();
+		friends[1].state = trick(TrickKind::Jump);
+		let snapshot = snapshot(4, vec![boat(0, 2.0), boat(2, 5.0), boat(3, 8.0)], friends);
+
+		let delta = encode_snapshot(&snapshot, Some(&baseline), None);
+		let full = encode_snapshot(&snapshot, None, None);
+		assert!(delta.len() < full.len());
+
+		let packet = decode_snapshot(&delta, Some(&baseline)).unwrap();
+		assert_snapshots_eq(&packet.snapshot, &snapshot);
+		assert_eq!(packet.baseline.map(|b| b.sequence), Some(3));
+		assert_eq!(packet.ack_input, None);
+	}
+
+	#[test]
+	fn test_snapshot_missing_baseline() {
+		let baseline = snapshot(3, vec![boat(0, 1.0)], vec![friend(FriendState::HangingOut)]);
+		let snapshot = snapshot(4, vec![boat(0, 1.0)], vec![friend(FriendState::HangingOut)]);
+
+		let data = encode_snapshot(&snapshot, Some(&baseline), None);
+		let other_baseline = Snapshot { sequence: 2, ..baseline };
+
+		let err = decode_snapshot(&data, Some(&other_baseline)).unwrap_err();
+		assert_eq!(err.0, "missing baseline");
+	}
+
+	#[test]
+	fn test_snapshot_unknown_friend_state() {
+		let snapshot = snapshot(1, Vec::new(), vec![friend(FriendState::HangingOut)]);
+		let mut data = encode_snapshot(&snapshot, None, None);
+
+		// The state is followed by met_player and trust
+		let state_idx = data.len() - 6;
+		assert_eq!(data[state_idx], 0);
+		data[state_idx] = 200;
+
+		let err = decode_snapshot(&data, None).unwrap_err();
+		assert_eq!(err.0, "unknown friend state");
+	}
+
+	#[test]
+	fn test_snapshot_unknown_trick() {
+		let snapshot = snapshot(1, Vec::new(), vec![friend(trick(TrickKind::Jump))]);
+		let mut data = encode_snapshot(&snapshot, None, None);
+
+		// The trick kind is followed by duration and phase, then met_player and trust
+		let kind_idx = data.len() - 14;
+		assert_eq!(data[kind_idx], TrickKind::Jump as u8);
+		data[kind_idx] = TrickKind::ALL.len() as u8;
+
+		let err = decode_snapshot(&data, None).unwrap_err();
+		assert_eq!(err.0, "unknown trick");
+	}
+
+	#[test]
+	fn test_bad_header() {
+		let mut data = Message::Connect.encode();
+		data[4] = PROTOCOL_VERSION + 1;
+		assert_eq!(Message::decode(&data, |_| None).unwrap_err().0, "protocol version mismatch");
+
+		data[0] ^= 0xff;
+		assert_eq!(Message::decode(&data, |_| None).unwrap_err().0, "bad magic");
+
+		assert_eq!(Message::decode(&data[..3], |_| None).unwrap_err().0, "unexpected end of packet");
+	}
+}
@@ -0,0 +1,111 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::net::{SocketAddr, UdpSocket};
+use std::rc::Rc;
+
+
+/// An unreliable, unordered datagram transport
+pub trait Transport {
+	fn send_to(&mut self, data: &[u8], addr: SocketAddr) -> io::Result<()>;
+
+	/// Never blocks - returns None if no datagrams are waiting
+	fn recv_from(&mut self, buf: &mut [u8]) -> io::Result<Option<(usize, SocketAddr)>>;
+}
+
+
+
+pub struct UdpTransport {
+	socket: UdpSocket,
+}
+
+impl UdpTransport {
+	pub fn bind(addr: impl std::net::ToSocketAddrs) -> io::Result<UdpTransport> {
+		let socket = UdpSocket::bind(addr)?;
+		socket.set_nonblocking(true)?;
+		Ok(UdpTransport { socket })
+	}
+}
+
+impl Transport for UdpTransport {
+	fn send_to(&mut self, data: &[u8], addr: SocketAddr) -> io::Result<()> {
+		match self.socket.send_to(data, addr) {
+			Ok(_) => Ok(()),
+			Err(err) if err.kind() == io::ErrorKind::WouldBlock => Ok(()),
+			Err(err) => Err(err),
+		}
+	}
+
+	fn recv_from(&mut self, buf: &mut [u8]) -> io::Result<Option<(usize, SocketAddr)>> {
+		match self.socket.recv_from(buf) {
+			Ok(result) => Ok(Some(result)),
+			Err(err) if err.kind() == io::ErrorKind::WouldBlock => Ok(None),
+
+			// On some platforms an ICMP port unreachable from a previous send surfaces here - it isn't fatal
+			Err(err) if err.kind() == io::ErrorKind::ConnectionReset => Ok(None),
+
+			Err(err) => Err(err),
+		}
+	}
+}
+
+
+
+/// An in-memory stand-in for the network, so that hosts and clients can run in the same process
+#[derive(Clone, Default)]
+pub struct LoopbackNetwork {
+	inboxes: Rc<RefCell<HashMap<SocketAddr, VecDeque<(Vec<u8>, SocketAddr)>>>>,
+}
+
+impl LoopbackNetwork {
+	pub fn new() -> LoopbackNetwork {
+		LoopbackNetwork::default()
+	}
+
+	pub fn bind(&self, addr: SocketAddr) -> LoopbackTransport {
+		self.inboxes.borrow_mut().insert(addr, VecDeque::new());
+
+		LoopbackTransport {
+			network: self.clone(),
+			addr,
+		}
+	}
+}
+
+
+pub struct LoopbackTransport {
+	network: LoopbackNetwork,
+	addr: SocketAddr,
+}
+
+impl Transport for LoopbackTransport {
+	fn send_to(&mut self, data: &[u8], addr: SocketAddr) -> io::Result<()> {
+		// Like UDP, sending to nobody silently drops the datagram
+		if let Some(inbox) = self.network.inboxes.borrow_mut().get_mut(&addr) {
+			inbox.push_back((data.to_vec(), self.addr));
+		}
+
+		Ok(())
+	}
+
+	fn recv_from(&mut self, buf: &mut [u8]) -> io::Result<Option<(usize, SocketAddr)>> {
+		let mut inboxes = self.network.inboxes.borrow_mut();
+		let inbox = inboxes.get_mut(&self.addr).expect("Loopback transport unbound");
+
+		match inbox.pop_front() {
+			Some((data, from)) => {
+				let len = data.len().min(buf.len());
+				buf[..len].copy_from_slice(&data[..len]);
+				Ok(Some((len, from)))
+			}
+
+			None => Ok(None),
+		}
+	}
+}
+
+impl Drop for LoopbackTransport {
+	fn drop(&mut self) {
+		self.network.inboxes.borrow_mut().remove(&self.addr);
+	}
+}
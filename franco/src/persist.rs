@@ -0,0 +1,130 @@
+use crate::prelude::*;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+
+
+/// A flat set of `key = value` pairs, backed by a simple line based text file.
+/// Blank lines and lines starting with '#' are ignored.
+#[derive(Debug, Default, Clone)]
+pub struct KeyValues {
+	values: BTreeMap<String, String>,
+}
+
+impl KeyValues {
+	pub fn new() -> KeyValues {
+		KeyValues::default()
+	}
+
+	pub fn parse(source: &str) -> KeyValues {
+		let values = source.lines()
+			.map(str::trim)
+			.filter(|line| !line.is_empty() && !line.starts_with('#'))
+			.filter_map(|line| line.split_once('='))
+			.map(|(key, value)| (key.trim().to_owned(), value.trim().to_owned()))
+			.collect();
+
+		KeyValues { values }
+	}
+
+	/// Loads `path` if it exists - a missing file is treated as empty.
+	pub fn load(path: impl AsRef<Path>) -> Result<KeyValues> {
+		match std::fs::read_to_string(path) {
+			Ok(source) => Ok(KeyValues::parse(&source)),
+			Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(KeyValues::new()),
+			Err(err) => Err(err.into()),
+		}
+	}
+
+	pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+		std::fs::write(path, self.to_string())?;
+		Ok(())
+	}
+
+	pub fn get<T: FromStr>(&self, key: &str) -> Option<T> {
+		self.values.get(key)?.parse().ok()
+	}
+
+	pub fn get_or<T: FromStr>(&self, key: &str, default: T) -> T {
+		self.get(key).unwrap_or(default)
+	}
+
+	pub fn set(&mut self, key: impl Into<String>, value: impl std::fmt::Display) {
+		self.values.insert(key.into(), value.to_string());
+	}
+
+	/// Iterates over all pairs whose key starts with `prefix`, with the prefix stripped.
+	pub fn with_prefix<'s>(&'s self, prefix: &'s str) -> impl Iterator<Item=(&'s str, &'s str)> + 's {
+		self.values.iter()
+			.filter_map(move |(key, value)| Some((key.strip_prefix(prefix)?, value.as_str())))
+	}
+}
+
+impl std::fmt::Display for KeyValues {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for (key, value) in self.values.iter() {
+			writeln!(f, "{} = {}", key, value)?;
+		}
+
+		Ok(())
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse() {
+		let kv = KeyValues::parse("
+			# a comment
+			speed = 1.5
+			  name=boat
+
+			count = 3
+		");
+
+		assert_eq!(kv.get::<f32>("speed"), Some(1.5));
+		assert_eq!(kv.get::<String>("name").as_deref(), Some("boat"));
+		assert_eq!(kv.get::<u32>("count"), Some(3));
+		assert_eq!(kv.to_string().lines().count(), 3);
+	}
+
+	#[test]
+	fn test_parse_malformed_lines() {
+		let kv = KeyValues::parse("
+			no equals sign here
+			= no key
+			empty =
+			expr = a = b
+			count = three
+		");
+
+		// Lines without an '=' are skipped, and only the first '=' splits a line
+		assert_eq!(kv.get::<String>("no equals sign here"), None);
+		assert_eq!(kv.get::<String>("").as_deref(), Some("no key"));
+		assert_eq!(kv.get::<String>("empty").as_deref(), Some(""));
+		assert_eq!(kv.get::<String>("expr").as_deref(), Some("a = b"));
+
+		// Values that don't parse as the requested type fall back to the default
+		assert_eq!(kv.get::<u32>("count"), None);
+		assert_eq!(kv.get_or("count", 7u32), 7);
+		assert_eq!(kv.get_or("missing", 7u32), 7);
+	}
+
+	#[test]
+	fn test_round_trip() {
+		let mut kv = KeyValues::new();
+		kv.set("distance_sailed", 12.25);
+		kv.set("tricks_seen.dolphin", 4);
+		kv.set("tricks_seen.fish", 2);
+
+		let parsed = KeyValues::parse(&kv.to_string());
+		assert_eq!(parsed.get::<f32>("distance_sailed"), Some(12.25));
+
+		let tricks: Vec<_> = parsed.with_prefix("tricks_seen.").collect();
+		assert_eq!(tricks, [("dolphin", "4"), ("fish", "2")]);
+	}
+}
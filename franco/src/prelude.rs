@@ -9,6 +9,8 @@ pub use crate::{
 	view,
 	shaders,
 	debug,
+	persist,
+	net,
 };
 
 pub type Result<T> = std::result::Result<T, Box<dyn Error>>;
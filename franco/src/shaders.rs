@@ -8,9 +8,12 @@ pub const WATER_COMMON: &str = include_str!("shaders/water.common.glsl");
 
 pub const COLOR_3D_VERT: &str = include_str!("shaders/color_3d.vert.glsl");
 pub const COLOR_3D_INSTANCED_VERT: &str = include_str!("shaders/color_3d_instanced.vert.glsl");
+pub const TEXTURED_VERT: &str = include_str!("shaders/textured.vert.glsl");
 
 
 pub const FLAT_COLOR_FRAG: &str = include_str!("shaders/flat_color.frag.glsl");
 pub const FLAT_COLOR_FOG_FRAG: &str = include_str!("shaders/flat_color_fog.frag.glsl");
 pub const WATER_FRAG: &str = include_str!("shaders/water.frag.glsl");
+pub const TEXT_FRAG: &str = include_str!("shaders/text.frag.glsl");
+pub const SPRITE_FRAG: &str = include_str!("shaders/sprite.frag.glsl");
 
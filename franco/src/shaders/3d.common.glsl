@@ -10,6 +10,10 @@ layout(std140, row_major, binding = 1) uniform WorldData {
 	vec2 player_position;
 	float fog_start;
 	float fog_distance;
+
+	// Rows of the matrix applied to linear colours by the final composite
+	vec4 color_transform[3];
+	float high_contrast;
 } u_world;
 
 
@@ -11,6 +11,25 @@ layout(binding=3) uniform sampler2D u_friend_depth;
 
 layout(location=0) out vec4 out_color;
 
+
+float luminance(in vec3 color) {
+	return dot(color, vec3(0.2126, 0.7152, 0.0722));
+}
+
+// The color transform is built for linear colours, so round trip through an approximate gamma
+vec3 apply_color_transform(in vec3 color) {
+	const vec3 linear = pow(max(color, vec3(0.0)), vec3(2.2));
+
+	const vec3 transformed = vec3(
+		dot(u_world.color_transform[0].xyz, linear),
+		dot(u_world.color_transform[1].xyz, linear),
+		dot(u_world.color_transform[2].xyz, linear)
+	);
+
+	return pow(clamp(transformed, 0.0, 1.0), vec3(1.0/2.2));
+}
+
+
 void main() {
 	vec4 scene_color = texture(u_scene_color, v_uv);
 	vec4 friend_color = texture(u_friend_color, v_uv);
@@ -23,12 +42,20 @@ void main() {
 
 	const float scene_foreground = scene_color.a;
 
+	// High contrast dims and desaturates the world, saturates friends, and lets them show through the water more clearly
+	const float high_contrast = u_world.high_contrast;
+	scene_color.rgb = mix(scene_color.rgb, vec3(luminance(scene_color.rgb)) * 0.7, high_contrast * 0.6);
+	friend_color.rgb = mix(friend_color.rgb, clamp(mix(vec3(luminance(friend_color.rgb)), friend_color.rgb, 1.6) * 1.2, 0.0, 1.0), high_contrast);
+	const float water_obscure = mix(1.0, 0.5, high_contrast) * depth_fail;
+
 	if (scene_foreground < 0.5) {
 		// Water
-		friend_color.rgb = mix(friend_color.rgb, u_world.water_obscure_color.rgb, depth_fail);
+		friend_color.rgb = mix(friend_color.rgb, u_world.water_obscure_color.rgb, water_obscure);
 		out_color = mix(scene_color, friend_color, friend_presence);
 	} else {
 		// Not water
 		out_color = mix(scene_color, friend_color, 1.0 - depth_fail);
 	}
+
+	out_color.rgb = apply_color_transform(out_color.rgb);
 }
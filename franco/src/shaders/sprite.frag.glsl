@@ -0,0 +1,18 @@
+#version 450
+
+layout(binding=0) uniform sampler2D u_sprite_atlas;
+
+in vec4 v_color;
+in vec2 v_uv;
+
+layout(location=0) out vec4 out_color;
+
+void main() {
+	// Without blending, transparent parts of a sprite are cut out rather than blended
+	const vec4 color = texture(u_sprite_atlas, v_uv) * v_color;
+	if (color.a < 0.5) {
+		discard;
+	}
+
+	out_color = vec4(color.rgb, 1.0);
+}
@@ -0,0 +1,18 @@
+#version 450
+
+layout(binding=0) uniform sampler2D u_glyph_atlas;
+
+in vec4 v_color;
+in vec2 v_uv;
+
+layout(location=0) out vec4 out_color;
+
+void main() {
+	// There's no blending, so coverage is thresholded - linear filtering keeps the edge smooth as glyphs scale
+	const float coverage = texture(u_glyph_atlas, v_uv).r;
+	if (coverage < 0.5) {
+		discard;
+	}
+
+	out_color = v_color;
+}
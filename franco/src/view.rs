@@ -15,9 +15,77 @@ pub use island::*;
 pub mod friends;
 pub use friends::*;
 
+pub mod pickups;
+pub use pickups::*;
+
+pub mod regatta;
+pub use regatta::*;
+
+pub mod sonar;
+pub use sonar::*;
+
+pub mod ambient;
+pub use ambient::*;
+
+pub mod text;
+pub use text::*;
+
 pub mod ui;
 pub use ui::*;
 
+pub mod pause_menu;
+pub use pause_menu::*;
+
+pub mod debug_inspector;
+pub use debug_inspector::*;
+
+pub mod color_transform;
+pub use color_transform::*;
+
+
+/// Everything drawn from a single local player's point of view.
+/// Each player sharing the screen gets their own set.
+pub struct PlayerViews {
+	pub boat: BoatView,
+	pub water: WaterView,
+	pub island: IslandView,
+	pub friend: FriendView,
+	pub pickup: PickupView,
+	pub regatta: RegattaView,
+	pub sonar: SonarView,
+	pub ambient: AmbientView,
+	pub ui: UiView,
+}
+
+impl PlayerViews {
+	pub fn new(gfx: &mut gfx::Context, resources: &model::Resources, font: &gfx::FontAtlas) -> Result<PlayerViews> {
+		Ok(PlayerViews {
+			boat: BoatView::new(gfx, resources)?,
+			water: WaterView::new(gfx, resources)?,
+			island: IslandView::new(gfx, resources)?,
+			friend: FriendView::new(gfx, resources)?,
+			pickup: PickupView::new(gfx)?,
+			regatta: RegattaView::new(gfx, resources)?,
+			sonar: SonarView::new(gfx, resources)?,
+			ambient: AmbientView::new(gfx)?,
+			ui: UiView::new(gfx, resources, font)?,
+		})
+	}
+
+	pub fn update(&mut self, model: &model::Model, viewer: &model::Viewer<'_>) {
+		self.boat.update(model, viewer);
+		self.water.update(model, viewer);
+		self.island.update(model, viewer);
+		self.friend.update(model, viewer);
+		self.pickup.update(model, viewer);
+		self.regatta.update(model, viewer);
+		self.sonar.update(model, viewer);
+		self.ambient.update(model, viewer);
+		self.ui.update(model, viewer);
+	}
+}
+
+
 
 pub struct ViewContext<'engine> {
 	pub gfx: gfx::RenderState<'engine>,
@@ -0,0 +1,154 @@
+use crate::prelude::*;
+
+use gfx::geom;
+
+
+/// Small fish, gulls and jumping fish - each kind is a single mesh drawn instanced
+pub struct AmbientView {
+	school_fish: AmbientKind,
+	gulls: AmbientKind,
+	jumpers: AmbientKind,
+
+	shader: gfx::Shader,
+	glide_phase: f32,
+}
+
+
+impl AmbientView {
+	pub fn new(gfx: &mut gfx::Context) -> Result<Self> {
+		let shader = gfx.new_simple_shader(shaders::COLOR_3D_INSTANCED_VERT, shaders::FLAT_COLOR_FOG_FRAG)?;
+
+		Ok(AmbientView {
+			school_fish: AmbientKind::new(gfx, build_fish_mesh(Color::hsv(190.0, 0.3, 0.8), 0.6)),
+			gulls: AmbientKind::new(gfx, build_gull_mesh()),
+			jumpers: AmbientKind::new(gfx, build_fish_mesh(Color::hsv(210.0, 0.4, 0.7), 1.0)),
+
+			shader,
+			glide_phase: 0.0,
+		})
+	}
+
+	pub fn update(&mut self, model: &model::Model, viewer: &model::Viewer<'_>) {
+		let player_pos_map = viewer.player.map_position;
+		let ambient = &model.world.ambient;
+
+		let in_range = move |pos: Vec2| (pos - player_pos_map).length() < model::AMBIENT_CULL_DISTANCE;
+		let to_world = move |pos: Vec2| model::map_to_world(pos - player_pos_map).to_x0z();
+
+		let fish_transforms: Vec<_> = ambient.schools.iter()
+			.flat_map(|school| school.fish.iter())
+			.filter(|fish| in_range(fish.map_position))
+			.map(|fish| {
+				let heading = fish.velocity.to_angle();
+				Mat3x4::rotate_y_translate(heading, to_world(fish.map_position) - Vec3::from_y(fish.depth))
+			})
+			.collect();
+
+		let gull_transforms: Vec<_> = ambient.gulls.iter()
+			.filter(|gull| in_range(gull.map_position()))
+			.map(|gull| {
+				// Gulls drift up and down as they glide, each slightly out of step with the others
+				let glide = ((self.glide_phase + gull.orbit_angle / TAU) * TAU).sin() * 0.4;
+
+				Mat3x4::rotate_y_translate(gull.heading(), to_world(gull.map_position()) + Vec3::from_y(gull.height + glide))
+					* Mat3x4::rotate_x(-gull.orbit_speed.signum() * PI / 10.0)
+			})
+			.collect();
+
+		let jumper_transforms: Vec<_> = ambient.jumpers.iter()
+			.filter(|jumper| in_range(jumper.map_position))
+			.map(|jumper| {
+				// An arc out of the water, nose up on the way out and down on the way back in
+				let height = (jumper.phase * PI).sin() * 2.0 - 0.3;
+				let pitch = (0.5 - jumper.phase) * PI * 0.8;
+
+				Mat3x4::rotate_y_translate(jumper.heading, to_world(jumper.map_position) + Vec3::from_y(height))
+					* Mat3x4::rotate_z(pitch)
+			})
+			.collect();
+
+		self.school_fish.instance_buffer.upload(&fish_transforms);
+		self.gulls.instance_buffer.upload(&gull_transforms);
+		self.jumpers.instance_buffer.upload(&jumper_transforms);
+
+		self.glide_phase = (self.glide_phase + 0.3 / 60.0) % 1.0;
+	}
+
+	/// Gulls and jumping fish, above the water
+	pub fn draw(&self, ctx: &mut view::ViewContext) {
+		ctx.gfx.bind_shader(self.shader);
+		self.gulls.draw(ctx);
+		self.jumpers.draw(ctx);
+	}
+
+	/// Schools swim below the surface, so are drawn along with the friends
+	pub fn draw_underwater(&self, ctx: &mut view::ViewContext) {
+		ctx.gfx.bind_shader(self.shader);
+		self.school_fish.draw(ctx);
+	}
+}
+
+
+
+
+struct AmbientKind {
+	mesh: gfx::Mesh<gfx::ColorVertex>,
+	instance_buffer: gfx::Buffer<Mat3x4>,
+}
+
+
+impl AmbientKind {
+	fn new(gfx: &mut gfx::Context, mesh_data: gfx::MeshData<gfx::ColorVertex>) -> AmbientKind {
+		AmbientKind {
+			mesh: gfx::Mesh::from_mesh_data(gfx, &mesh_data),
+			instance_buffer: gfx.new_buffer(gfx::BufferUsage::Stream),
+		}
+	}
+
+	fn draw(&self, ctx: &mut view::ViewContext) {
+		ctx.gfx.bind_shader_storage_buffer(0, self.instance_buffer);
+		self.mesh.draw_instanced(&mut ctx.gfx, gfx::DrawMode::Triangles, self.instance_buffer.len());
+	}
+}
+
+
+/// A body pointing along +x with a tail fin behind
+fn build_fish_mesh(color: Color, length: f32) -> gfx::MeshData<gfx::ColorVertex> {
+	let mut mesh_data = gfx::MeshData::new();
+
+	{
+		let mut mb = gfx::ColorMeshBuilder::new(&mut mesh_data);
+
+		mb.set_color(color);
+		PolyBuilder3D::build(&mut mb, geom::Cuboid::from_matrix(Mat3x4::scale(Vec3::new(length, length * 0.35, length * 0.2))));
+
+		mb.set_color(Color::hsv(190.0, 0.2, 0.95));
+		PolyBuilder3D::build(&mut mb, geom::Cuboid::from_matrix(Mat3x4::scale_translate(
+			Vec3::new(length * 0.3, length * 0.4, length * 0.05),
+			Vec3::from_x(-length * 0.6),
+		)));
+	}
+
+	mesh_data
+}
+
+
+/// A body with a pair of wings spread along z
+fn build_gull_mesh() -> gfx::MeshData<gfx::ColorVertex> {
+	let mut mesh_data = gfx::MeshData::new();
+
+	{
+		let mut mb = gfx::ColorMeshBuilder::new(&mut mesh_data);
+
+		mb.set_color(Color::grey(0.95));
+		PolyBuilder3D::build(&mut mb, geom::Cuboid::from_matrix(Mat3x4::scale(Vec3::new(1.0, 0.3, 0.3))));
+
+		mb.set_color(Color::grey(0.85));
+		PolyBuilder3D::build(&mut mb, geom::Cuboid::from_matrix(Mat3x4::scale_translate(Vec3::new(0.5, 0.05, 3.0), Vec3::new(0.1, 0.1, 0.0))));
+
+		mb.set_color(Color::hsv(40.0, 0.8, 0.9));
+		PolyBuilder3D::build(&mut mb, geom::Cuboid::from_matrix(Mat3x4::scale_translate(Vec3::new(0.25, 0.1, 0.1), Vec3::from_x(0.6))));
+	}
+
+	mesh_data
+}
@@ -9,8 +9,13 @@ pub struct BasicMesh {
 
 impl BasicMesh {
 	pub fn from_entity(entity: toy::EntityRef<'_>) -> BasicMesh {
+		BasicMesh::from_entity_variant(entity, None)
+	}
+
+	/// Uses the named alternative color layer, or the default layer if the entity doesn't have one by that name
+	pub fn from_entity_variant(entity: toy::EntityRef<'_>, variant: Option<&str>) -> BasicMesh {
 		let raw_mesh = entity.mesh_data().unwrap();
-		let colors = &raw_mesh.color_data(None).unwrap().data;
+		let colors = &raw_mesh.color_variant_data(variant).unwrap().data;
 		BasicMesh {
 			vertices: raw_mesh.positions.iter().cloned()
 				.zip(colors.iter().cloned().map(Color::from))
@@ -20,6 +25,34 @@ impl BasicMesh {
 		}
 	}
 
+	/// For meshes generated at runtime rather than loaded from a scene
+	pub fn from_mesh_data(mesh_data: &gfx::MeshData<gfx::ColorVertex>) -> BasicMesh {
+		BasicMesh {
+			vertices: mesh_data.vertices.iter()
+				.map(|vertex| (vertex.pos, vertex.color))
+				.collect(),
+
+			indices: mesh_data.indices.clone(),
+		}
+	}
+
+	/// The default mesh followed by one per alternative color layer
+	pub fn all_variants_from_entity(entity: toy::EntityRef<'_>) -> Vec<BasicMesh> {
+		let raw_mesh = entity.mesh_data().unwrap();
+
+		std::iter::once(None)
+			.chain(raw_mesh.color_variants().map(Some))
+			.map(|variant| BasicMesh::from_entity_variant(entity, variant))
+			.collect()
+	}
+
+	/// The distance from the origin to the furthest vertex
+	pub fn radius(&self) -> f32 {
+		self.vertices.iter()
+			.map(|(pos, _)| pos.length())
+			.fold(0.0, f32::max)
+	}
+
 	pub fn build_into(&self, mesh_data: &mut gfx::MeshData<gfx::ColorVertex>, transform: Mat3x4) {
 		let vertices = self.vertices.iter()
 			.map(move |&(pos, color)| gfx::ColorVertex::new(transform * pos, color));
@@ -29,4 +62,15 @@ impl BasicMesh {
 			self.indices.iter().cloned()
 		);
 	}
-}
\ No newline at end of file
+
+	/// Builds the mesh in a single flat color
+	pub fn build_silhouette_into(&self, mesh_data: &mut gfx::MeshData<gfx::ColorVertex>, transform: Mat3x4, color: Color) {
+		let vertices = self.vertices.iter()
+			.map(move |&(pos, _)| gfx::ColorVertex::new(transform * pos, color));
+
+		mesh_data.extend(
+			vertices,
+			self.indices.iter().cloned()
+		);
+	}
+}
@@ -2,8 +2,8 @@ use crate::prelude::*;
 
 
 pub struct BoatView {
-	boat_mesh: gfx::Mesh<gfx::ColorVertex>,
-	boat_ubo: gfx::Buffer<BoatUniforms>,
+	/// The default colors followed by each alternative paint, in the order `Player::paint` indexes them
+	paints: Vec<BoatPaint>,
 	shader: gfx::Shader,
 
 	time: f32,
@@ -15,57 +15,90 @@ impl BoatView {
 		let boat_ent = resources.main_project.find_entity("Boat").unwrap();
 
 		let raw_mesh = boat_ent.mesh_data().unwrap();
-		let color_data = raw_mesh.color_data(None).unwrap();
 
-		let mut mesh_data = gfx::MeshData::new();
+		let paints = std::iter::once(None)
+			.chain(raw_mesh.color_variants().map(Some))
+			.map(|variant| {
+				let color_data = raw_mesh.color_variant_data(variant).unwrap();
 
-		let vertices = raw_mesh.positions.iter()
-			.zip(&color_data.data)
-			.map(|(pos, color)| gfx::ColorVertex::new(*pos, color));
+				let mut mesh_data = gfx::MeshData::new();
 
-		mesh_data.extend(vertices, raw_mesh.indices.iter().cloned());
+				let vertices = raw_mesh.positions.iter()
+					.zip(&color_data.data)
+					.map(|(pos, color)| gfx::ColorVertex::new(*pos, color));
 
-		let shader = gfx.new_simple_shader(
-			include_str!("../shaders/boat.vert.glsl"),
-			shaders::FLAT_COLOR_FRAG
-		)?;
+				mesh_data.extend(vertices, raw_mesh.indices.iter().cloned());
+
+				BoatPaint {
+					mesh: gfx::Mesh::from_mesh_data(gfx, &mesh_data),
+					instance_buffer: gfx.new_buffer(gfx::BufferUsage::Stream),
+				}
+			})
+			.collect();
+
+		let shader = gfx.new_simple_shader(shaders::COLOR_3D_INSTANCED_VERT, shaders::FLAT_COLOR_FRAG)?;
 
 		Ok(BoatView {
-			boat_mesh: gfx::Mesh::from_mesh_data(gfx, &mesh_data),
-			boat_ubo: gfx.new_buffer(gfx::BufferUsage::Stream),
+			paints,
 			shader,
 
 			time: 0.0,
 		})
 	}
 
-	pub fn update(&mut self, model: &model::Model) {
-		let speed = model.player.speed;
-		let factor = (speed).clamp(0.2, 1.0);
+	pub fn update(&mut self, model: &model::Model, viewer: &model::Viewer<'_>) {
+		let player_pos_map = viewer.player.map_position;
+
+		let mut instance_transforms = vec![Vec::new(); self.paints.len()];
 
-		let uniforms = BoatUniforms {
-			transform: Mat4::translate(Vec3::from_y((0.7 + self.time.sin()) * factor * 0.3))
-				* Mat4::rotate_y(model.player.heading)
-				* Mat4::rotate_x((self.time).sin() * PI/48.0 * factor)
-				* Mat4::rotate_z((0.5 + self.time.cos()) * PI/16.0 * factor),
-		};
+		for (idx, boat) in model.boats().enumerate() {
+			let diff_map = boat.map_position - player_pos_map;
+			let phase_offset = idx as f32 * 1.7;
+			let transform = self.boat_transform(boat, model::map_to_world(diff_map).to_x0z(), phase_offset);
 
-		self.boat_ubo.upload(&[uniforms]);
+			// A remote player may have paints this machine doesn't
+			let paint = match (boat.paint as usize) < self.paints.len() {
+				true => boat.paint as usize,
+				false => 0,
+			};
 
-		self.time += speed.max(1.0) / 60.0;
+			instance_transforms[paint].push(transform);
+		}
+
+		for (paint, transforms) in self.paints.iter_mut().zip(&instance_transforms) {
+			paint.instance_buffer.upload(transforms);
+		}
+
+		self.time += viewer.player.speed.max(1.0) / 60.0;
 	}
 
 	pub fn draw(&self, ctx: &mut view::ViewContext) {
 		ctx.gfx.bind_shader(self.shader);
-		ctx.gfx.bind_uniform_buffer(4, self.boat_ubo);
-		self.boat_mesh.draw(&mut ctx.gfx, gfx::DrawMode::Triangles);
+
+		for paint in self.paints.iter() {
+			if paint.instance_buffer.is_empty() {
+				continue
+			}
+
+			ctx.gfx.bind_shader_storage_buffer(0, paint.instance_buffer);
+			paint.mesh.draw_instanced(&mut ctx.gfx, gfx::DrawMode::Triangles, paint.instance_buffer.len());
+		}
+	}
+
+	fn boat_transform(&self, player: &model::Player, position: Vec3, phase_offset: f32) -> Mat3x4 {
+		let factor = player.speed.clamp(0.2, 1.0);
+		let time = self.time + phase_offset;
+
+		Mat3x4::translate(position + Vec3::from_y((0.7 + time.sin()) * factor * 0.3))
+			* Mat3x4::rotate_y(player.heading)
+			* Mat3x4::rotate_x(time.sin() * PI/48.0 * factor)
+			* Mat3x4::rotate_z((0.5 + time.cos()) * PI/16.0 * factor)
 	}
 }
 
 
 
-#[repr(C)]
-#[derive(Copy, Clone)]
-struct BoatUniforms {
-	transform: Mat4,
-}
\ No newline at end of file
+struct BoatPaint {
+	mesh: gfx::Mesh<gfx::ColorVertex>,
+	instance_buffer: gfx::Buffer<Mat3x4>,
+}
@@ -0,0 +1,92 @@
+use crate::prelude::*;
+use model::{ColorMode, ColorBlindness};
+
+
+/// Applied to world colours by the final composite
+#[derive(Copy, Clone, Debug)]
+pub struct ColorTransform {
+	pub matrix: Mat3,
+
+	/// Boosts friends and dims the world behind them
+	pub high_contrast: bool,
+}
+
+impl ColorTransform {
+	/// Correction for the chosen `mode` first, then simulation of `simulated` on top of that -
+	/// so a corrected mode can be previewed as the players it's meant for would see it
+	pub fn new(mode: ColorMode, simulated: Option<ColorBlindness>) -> ColorTransform {
+		let correction = match mode {
+			ColorMode::Corrected(kind) => correction_matrix(kind),
+			_ => Mat3::identity(),
+		};
+
+		let simulation = simulated.map_or(Mat3::identity(), simulation_matrix);
+
+		ColorTransform {
+			matrix: simulation * correction,
+			high_contrast: mode == ColorMode::HighContrast,
+		}
+	}
+}
+
+
+/// Approximates how linear rgb is seen without one kind of cone, from Machado, Oliveira & Fernandes 2009
+fn simulation_matrix(kind: ColorBlindness) -> Mat3 {
+	match kind {
+		ColorBlindness::Protanopia => Mat3::new([
+			 0.152286, 1.052583,-0.204868,
+			 0.114503, 0.786281, 0.099216,
+			-0.003882,-0.048116, 1.051998,
+		]),
+
+		ColorBlindness::Deuteranopia => Mat3::new([
+			 0.367322, 0.860646,-0.227968,
+			 0.280085, 0.672501, 0.047413,
+			-0.011820, 0.042940, 0.968881,
+		]),
+
+		ColorBlindness::Tritanopia => Mat3::new([
+			 1.255528,-0.076749,-0.178779,
+			-0.078411, 0.930809, 0.147602,
+			 0.004733, 0.691367, 0.303900,
+		]),
+	}
+}
+
+
+/// Daltonisation - whatever detail is lost to a kind of colour blindness is added back into channels that can still be seen.
+/// The loss is `(I - S) * rgb`, so the whole correction folds into the single matrix `I + shift * (I - S)`.
+fn correction_matrix(kind: ColorBlindness) -> Mat3 {
+	let simulation = simulation_matrix(kind);
+
+	let [sx, sy, sz] = simulation.rows;
+	let loss = Mat3::from_rows([
+		Vec3::from_x(1.0) - sx,
+		Vec3::from_y(1.0) - sy,
+		Vec3::from_z(1.0) - sz,
+	]);
+
+	let shift = match kind {
+		// Lost red/green differences are moved into brightness and blue
+		ColorBlindness::Protanopia | ColorBlindness::Deuteranopia => Mat3::new([
+			0.0, 0.0, 0.0,
+			0.7, 1.0, 0.0,
+			0.7, 0.0, 1.0,
+		]),
+
+		// Lost blue/yellow differences are moved into red and green
+		ColorBlindness::Tritanopia => Mat3::new([
+			1.0, 0.0, 0.7,
+			0.0, 1.0, 0.7,
+			0.0, 0.0, 0.0,
+		]),
+	};
+
+	let [cx, cy, cz] = (shift * loss).rows;
+
+	Mat3::from_rows([
+		Vec3::from_x(1.0) + cx,
+		Vec3::from_y(1.0) + cy,
+		Vec3::from_z(1.0) + cz,
+	])
+}
@@ -0,0 +1,120 @@
+use crate::prelude::*;
+use model::{DebugDrawCommand, DEBUG_INSPECTOR_TEXT_SIZE};
+
+/// The inspector sits in front of everything else drawn under the same camera
+const INSPECTOR_DEPTH: f32 = 8.0;
+
+/// Size and corner radius of the generated image rounded rects are sliced from, in pixels
+const ROUNDED_RECT_IMAGE_SIZE: i32 = 16;
+const ROUNDED_RECT_IMAGE_RADIUS: f32 = 6.0;
+
+/// How big the corners of rounded rects are drawn, in ui units
+const ROUNDED_RECT_BORDER: f32 = 0.12;
+
+
+/// Draws the debug inspector's draw list over the whole window, in the same space as the pause menu
+pub struct DebugInspectorView {
+	sprite_shader: gfx::Shader,
+	sprites: gfx::SpriteBatch,
+	rounded_rect: gfx::NineSlice,
+
+	text: view::TextView,
+	visible: bool,
+}
+
+impl DebugInspectorView {
+	pub fn new(gfx: &mut gfx::Context, font: &gfx::FontAtlas) -> Result<DebugInspectorView> {
+		let sprite_shader = gfx.new_simple_shader(shaders::TEXTURED_VERT, shaders::SPRITE_FRAG)?;
+
+		let mut atlas = gfx::SpriteAtlasBuilder::new();
+		let rounded_rect_image = build_rounded_rect_image(ROUNDED_RECT_IMAGE_SIZE, ROUNDED_RECT_IMAGE_RADIUS);
+		let rounded_rect_image = atlas.add_rgba(Vec2i::splat(ROUNDED_RECT_IMAGE_SIZE), rounded_rect_image);
+		let atlas = atlas.build(gfx);
+
+		Ok(DebugInspectorView {
+			sprite_shader,
+			sprites: gfx::SpriteBatch::new(atlas),
+			rounded_rect: gfx::NineSlice::new(rounded_rect_image, ROUNDED_RECT_IMAGE_RADIUS, ROUNDED_RECT_BORDER),
+
+			text: view::TextView::new(gfx, font)?,
+			visible: false,
+		})
+	}
+
+	pub fn update(&mut self, gfx: &mut gfx::Context, model: &model::Model) {
+		self.sprites.clear();
+		self.text.clear();
+
+		let inspector = &model.debug_inspector;
+		self.visible = inspector.visible;
+
+		if self.visible {
+			for command in inspector.draw_list.iter() {
+				self.add_command(command);
+			}
+		}
+
+		self.sprites.upload(gfx, Mat3x4::translate(Vec3::from_z(INSPECTOR_DEPTH)));
+		self.text.upload();
+	}
+
+	pub fn draw(&self, ctx: &mut view::ViewContext<'_>) {
+		if !self.visible {
+			return
+		}
+
+		ctx.gfx.bind_shader(self.sprite_shader);
+		self.sprites.draw(&mut ctx.gfx);
+
+		self.text.draw(ctx);
+	}
+
+	fn add_command(&mut self, command: &DebugDrawCommand) {
+		match command {
+			&DebugDrawCommand::Rect{center, size, color, rounded: false} => {
+				self.sprites.add(&gfx::Sprite::new(center, size).with_color(color));
+			}
+
+			&DebugDrawCommand::Rect{center, size, color, rounded: true} => {
+				self.sprites.add_nine_slice(&self.rounded_rect, center, size, color, 0);
+			}
+
+			// Text goes in its own mesh, drawn after every rect, so it only needs to be in front of all of them
+			DebugDrawCommand::Text{text, position, color, align} => {
+				let style = gfx::TextStyle::new(DEBUG_INSPECTOR_TEXT_SIZE)
+					.with_color(*color)
+					.aligned(*align, gfx::VerticalAlign::Middle);
+
+				let transform = Mat3x4::translate(position.extend(INSPECTOR_DEPTH + 1.0));
+				self.text.add(text, &style, transform);
+			}
+		}
+	}
+}
+
+
+/// White, with corners cut away by alpha
+fn build_rounded_rect_image(size: i32, radius: f32) -> Vec<u8> {
+	let half_size = size as f32 / 2.0;
+	let inner_half_size = half_size - radius;
+
+	(0..size*size)
+		.flat_map(|idx| {
+			let pixel_center = Vec2::new((idx % size) as f32, (idx / size) as f32) + Vec2::splat(0.5);
+			let from_center = pixel_center - Vec2::splat(half_size);
+
+			// Distance outside of the rect inset by the radius
+			let outside = Vec2::new(
+				(from_center.x.abs() - inner_half_size).max(0.0),
+				(from_center.y.abs() - inner_half_size).max(0.0),
+			);
+
+			let alpha = match outside.length() <= radius {
+				true => 255,
+				false => 0,
+			};
+
+			[255, 255, 255, alpha]
+		})
+		.collect()
+}
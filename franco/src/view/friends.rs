@@ -10,7 +10,8 @@ pub struct FriendView {
 	mesh: gfx::Mesh<gfx::ColorVertex>,
 	mesh_data: gfx::MeshData<gfx::ColorVertex>,
 
-	friend_meshes: HashMap<model::FriendName, BasicMesh>,
+	/// Every color variant of each species, default first
+	friend_meshes: HashMap<model::FriendName, Vec<BasicMesh>>,
 	anim_phase: f32,
 }
 
@@ -22,7 +23,7 @@ impl FriendView {
 		let friend_meshes = friend_scene.entities_with_prefix("FRIEND_")
 			.map(|entity| {
 				let name = model::FriendName::from_name(&entity.name);
-				(name, BasicMesh::from_entity(entity))
+				(name, BasicMesh::all_variants_from_entity(entity))
 			})
 			.collect();
 
@@ -38,13 +39,13 @@ impl FriendView {
 		})
 	}
 
-	pub fn update(&mut self, model: &model::Model) {
+	pub fn update(&mut self, model: &model::Model, viewer: &model::Viewer<'_>) {
 		self.mesh_data.clear();
 
-		let player_speed = model.player.speed;
+		let player_speed = viewer.player.speed;
 
 		for friend in model.world.friends.iter() {
-			let player_diff_map = friend.map_position - model.player.map_position;
+			let player_diff_map = friend.map_position - viewer.player.map_position;
 			let world_pos = model::map_to_world(player_diff_map).to_x0z();
 			let base_transform = Mat3x4::rotate_y_translate(friend.heading, world_pos);
 
@@ -54,7 +55,9 @@ impl FriendView {
 				calc_boat_transform(friend, self.anim_phase)
 			};
 
-			self.friend_meshes[&friend.name].build_into(&mut self.mesh_data, base_transform * state_transform);
+			let variants = &self.friend_meshes[&friend.name];
+			let mesh = &variants[friend.color_variant % variants.len()];
+			mesh.build_into(&mut self.mesh_data, base_transform * state_transform);
 		}
 
 		self.mesh.upload(&self.mesh_data);
@@ -76,7 +79,7 @@ fn calc_fish_transform(friend: &model::Friend, anim_phase: f32) -> Mat3x4 {
 			Mat3x4::rotate_z_translate(PI/8.0, Vec3::from_y(bob))
 		}
 
-		FriendState::Following => {
+		FriendState::Following | FriendState::ReturningHome => {
 			let bob = (friend.bob_phase.sin() - 0.5) * friend.speed;
 			let yaw_wiggle = friend.bob_phase.sin() * PI/16.0;
 
@@ -84,11 +87,16 @@ fn calc_fish_transform(friend: &model::Friend, anim_phase: f32) -> Mat3x4 {
 				* Mat3x4::rotate_z(friend.bob_phase.cos() * PI/8.0 * friend.speed.min(2.0))
 		}
 
-		FriendState::DoingTricks(phase) => {
-			let height = (phase * PI).sin() * 6.0 - 2.0;
-			let spin = -phase * 2.0 * TAU;
+		FriendState::DoingTricks(trick) => calc_trick_transform(trick),
 
-			Mat3x4::rotate_z_translate(spin, Vec3::from_y(height))
+		FriendState::Fleeing(timer) => {
+			// Dive quickly, then resurface as the timer runs out
+			let elapsed = model::FRIEND_FLEE_TIME - timer;
+			let depth = elapsed.min(timer).min(0.5) * 2.0 * 3.0;
+			let yaw_wiggle = friend.bob_phase.sin() * PI/12.0;
+
+			Mat3x4::rotate_y_translate(yaw_wiggle, Vec3::from_y(-depth))
+				* Mat3x4::rotate_z(-PI/8.0 * elapsed.min(0.5) * 2.0)
 		}
 	}
 }
@@ -104,17 +112,72 @@ fn calc_boat_transform(friend: &model::Friend, anim_phase: f32) -> Mat3x4 {
 			Mat3x4::translate(Vec3::from_y(bob))
 		}
 
-		FriendState::Following => {
+		FriendState::Following | FriendState::Fleeing(_) | FriendState::ReturningHome => {
 			let bob = friend.bob_phase.sin() * bob_factor + 0.7;
 			let pitch_wobble = friend.bob_phase.cos() * PI/24.0 * friend.speed.min(2.0);
 			Mat3x4::rotate_z_translate(pitch_wobble, Vec3::from_y(bob))
 		}
 
-		FriendState::DoingTricks(phase) => {
-			let height = (phase * PI).sin() * 6.0 - 2.0;
+		FriendState::DoingTricks(trick) => calc_trick_transform(trick),
+	}
+}
+
+
+
+/// Plays back a trick - models face along +x, with +y up
+fn calc_trick_transform(trick: model::Trick) -> Mat3x4 {
+	use model::TrickKind;
+
+	let phase = trick.phase;
+
+	// Rises from 0 to 1 and back over the course of the trick
+	let envelope = (phase * PI).sin();
+
+	match trick.kind {
+		TrickKind::Jump => {
+			let height = envelope * 6.0 - 2.0;
 			let spin = -phase * 2.0 * TAU;
 
 			Mat3x4::rotate_z_translate(spin, Vec3::from_y(height))
 		}
+
+		TrickKind::Breach => {
+			// Nose up on the way out, nose down on the way back in
+			let height = envelope * 5.0 - 2.0;
+			let pitch = (0.5 - phase) * PI;
+
+			Mat3x4::rotate_z_translate(pitch, Vec3::from_y(height))
+		}
+
+		TrickKind::BarrelRoll => {
+			let height = envelope * 2.5 - 0.5;
+			let roll = phase.ease_quad_inout(0.0, TAU);
+
+			Mat3x4::rotate_x_translate(roll, Vec3::from_y(height))
+		}
+
+		TrickKind::TailWalk => {
+			let upright = (envelope * 3.0).min(1.0);
+			let wiggle = (phase * TAU * 6.0).sin() * PI/16.0 * upright;
+			let skim = Vec3::new(-envelope * 2.0, upright * 1.2, 0.0);
+
+			Mat3x4::rotate_y_translate(wiggle, skim)
+				* Mat3x4::rotate_z(upright * PI * 0.4)
+		}
+
+		TrickKind::Spray => {
+			let rock = (phase * TAU * 3.0).sin() * PI/8.0 * envelope;
+			let bob = envelope * 0.5 + 0.7;
+
+			Mat3x4::rotate_x_translate(rock, Vec3::from_y(bob))
+		}
+
+		TrickKind::Doughnut => {
+			let spin = phase.ease_quad_inout(0.0, 2.0 * TAU);
+			let lean = envelope * PI/10.0;
+
+			Mat3x4::rotate_y_translate(spin, Vec3::from_y(0.7))
+				* Mat3x4::rotate_x(lean)
+		}
 	}
 }
@@ -9,10 +9,7 @@ pub struct IslandView {
 
 impl IslandView {
 	pub fn new(gfx: &mut gfx::Context, resources: &model::Resources) -> Result<Self> {
-		let island_scenes = resources.main_project.scenes()
-			.filter(|scene| scene.name.starts_with("ISLAND_"));
-
-		let island_kinds = island_scenes
+		let island_kinds = resources.island_scenes()
 			.map(|scene| {
 				let ty = model::MapObjectType::from_name(&scene.name);
 				(ty, IslandKind::new(gfx, scene))
@@ -27,8 +24,8 @@ impl IslandView {
 		})
 	}
 
-	pub fn update(&mut self, model: &model::Model) {
-		let player_pos_map = model.player.map_position;
+	pub fn update(&mut self, model: &model::Model, viewer: &model::Viewer<'_>) {
+		let player_pos_map = viewer.player.map_position;
 
 		for (ty, kind) in self.island_kinds.iter_mut() {
 			let instance_transforms: Vec<_> = model.world.map.objects.iter()
@@ -65,8 +62,11 @@ impl IslandKind {
 	fn new(gfx: &mut gfx::Context, scene: toy::SceneRef<'_>) -> IslandKind {
 		let mut mesh_data = gfx::MeshData::new();
 
-		for entity in scene.entities() {
-			let raw_mesh = entity.mesh_data().unwrap();
+		// Ports and other markers have no mesh
+		let meshes = scene.entities()
+			.filter_map(|entity| Some((entity, entity.mesh_data()?)));
+
+		for (entity, raw_mesh) in meshes {
 			let color_data = raw_mesh.color_data(None).unwrap();
 			let txform = entity.transform();
 
@@ -0,0 +1,256 @@
+use crate::prelude::*;
+
+use model::{PausePage, PauseItem, SettingItem, SettingValue};
+use view::ui::{ui_plane, build_star};
+use gfx::geom;
+
+
+pub struct PauseMenuView {
+	shader: gfx::Shader,
+	mesh: gfx::Mesh<gfx::ColorVertex>,
+	mesh_data: gfx::MeshData<gfx::ColorVertex>,
+}
+
+impl PauseMenuView {
+	pub fn new(gfx: &mut gfx::Context) -> Result<PauseMenuView> {
+		let shader = gfx.new_simple_shader(shaders::COLOR_3D_VERT, shaders::FLAT_COLOR_FRAG)?;
+
+		Ok(PauseMenuView {
+			shader,
+			mesh: gfx::Mesh::new(gfx),
+			mesh_data: gfx::MeshData::new(),
+		})
+	}
+
+	pub fn update(&mut self, model: &model::Model) {
+		self.mesh_data.clear();
+
+		let menu = &model.pause_menu;
+		let phase = menu.state.as_phase();
+		if phase <= 0.0 {
+			self.mesh.upload(&self.mesh_data);
+			return
+		}
+
+		let scale = match menu.state.is_open() {
+			true => phase.ease_back_out(0.0, 1.0),
+			false => phase,
+		};
+
+		let mut mb = gfx::ColorMeshBuilder::new(&mut self.mesh_data);
+		let mut plane = mb.on_plane_ref(ui_plane(Vec3::from_z(5.0)) * Mat3::from_columns([
+			Vec3::from_x(scale),
+			Vec3::from_y(scale),
+			Vec3::from_z(1.0),
+		]));
+
+		let panel_height = menu.num_rows() as f32 * model::PAUSE_MENU_ROW_SPACING + 1.0;
+		let panel_width = model::PAUSE_MENU_ROW_WIDTH + 1.0;
+
+		plane.set_color(Color::hsv(200.0, 0.4, 0.3));
+		plane.build(geom::Quad::from_matrix(Mat2x3::scale(Vec2::new(panel_width, panel_height))));
+
+		plane.set_color(Color::hsv(200.0, 0.2, 0.9));
+		plane.build(geom::Quad::from_matrix(Mat2x3::scale(Vec2::new(panel_width - 0.5, panel_height - 0.5))));
+
+		for row in 0..menu.num_rows() {
+			let pos = menu.row_position(row);
+
+			if row == menu.selected {
+				let row_size = Vec2::new(model::PAUSE_MENU_ROW_WIDTH, model::PAUSE_MENU_ROW_SPACING * 0.9);
+				plane.set_color(Color::hsv(45.0, 0.4, 1.0));
+				plane.build(geom::Quad::from_matrix(Mat2x3::scale_translate(row_size, pos)));
+			}
+
+			match menu.page {
+				PausePage::Main => build_pause_item(&mut plane, PauseItem::ALL[row], pos),
+				PausePage::Settings => {
+					let item = SettingItem::ALL[row];
+					build_setting_icon(&mut plane, item, pos + Vec2::from_x(-5.0));
+					build_setting_value(&mut plane, &model.settings, item, pos);
+				}
+			}
+		}
+
+		self.mesh.upload(&self.mesh_data);
+	}
+
+	pub fn draw(&self, ctx: &mut view::ViewContext<'_>) {
+		ctx.gfx.bind_shader(self.shader);
+		self.mesh.draw(&mut ctx.gfx, gfx::DrawMode::Triangles);
+	}
+}
+
+
+
+fn build_pause_item(plane: &mut (impl PolyBuilder2D + ColoredPolyBuilder), item: PauseItem, pos: Vec2) {
+	match item {
+		PauseItem::Resume => {
+			plane.set_color(Color::hsv(120.0, 0.6, 0.7));
+			build_triangle(plane, pos, 0.7, 0.0);
+		}
+
+		PauseItem::Settings => {
+			plane.set_color(Color::hsv(210.0, 0.3, 0.5));
+			build_gear(plane, pos, 0.7);
+		}
+
+		PauseItem::Quit => {
+			plane.set_color(Color::hsv(0.0, 0.7, 0.8));
+			build_cross(plane, pos, 0.7);
+		}
+	}
+}
+
+fn build_setting_icon(plane: &mut (impl PolyBuilder2D + ColoredPolyBuilder), item: SettingItem, pos: Vec2) {
+	use SettingItem::*;
+
+	let color = Color::hsv(210.0, 0.4, 0.4);
+	plane.set_color(color);
+
+	match item {
+		MasterVolume | MusicVolume => {
+			build_speaker(plane, pos);
+
+			// Music gets a second wave, to tell the two apart
+			let waves = if item == MusicVolume { 2 } else { 1 };
+			for wave in 0..waves {
+				let x = 0.6 + wave as f32 * 0.35;
+				plane.build(geom::Quad::from_matrix(Mat2x3::scale_translate(Vec2::new(0.15, 0.4 + wave as f32 * 0.3), pos + Vec2::from_x(x))));
+			}
+		}
+
+		SfxVolume => build_star(plane, pos, 0.6, color),
+
+		MouseSensitivity => {
+			build_triangle(plane, pos + Vec2::from_y(0.1), 0.5, PI/2.0 + PI/8.0);
+			plane.build(geom::Quad::from_matrix(Mat2x3::rotate_translate(PI/8.0, pos - Vec2::new(-0.1, 0.35)) * Mat2x3::scale(Vec2::new(0.15, 0.5))));
+		}
+
+		InvertCamera => {
+			build_triangle(plane, pos + Vec2::from_y(0.3), 0.35, PI/2.0);
+			build_triangle(plane, pos - Vec2::from_y(0.3), 0.35, -PI/2.0);
+		}
+
+		Fullscreen => {
+			for corner in [Vec2::new(-1.0, -1.0), Vec2::new(-1.0, 1.0), Vec2::new(1.0, -1.0), Vec2::new(1.0, 1.0)] {
+				plane.build(geom::Quad::from_matrix(Mat2x3::scale_translate(Vec2::splat(0.25), pos + corner * 0.4)));
+			}
+		}
+
+		Vsync => {
+			for y in [-0.2, 0.2] {
+				plane.build(geom::Quad::from_matrix(Mat2x3::scale_translate(Vec2::new(1.0, 0.2), pos + Vec2::from_y(y))));
+			}
+		}
+
+		UiScale => {
+			plane.build(geom::Quad::from_matrix(Mat2x3::scale_translate(Vec2::splat(0.3), pos + Vec2::new(-0.4, -0.25))));
+			plane.build(geom::Quad::from_matrix(Mat2x3::scale_translate(Vec2::splat(0.7), pos + Vec2::new(0.25, 0.05))));
+		}
+
+		ColorMode => {
+			// Overlapping red, green and blue spots
+			for (idx, hue) in [0.0, 120.0, 240.0].iter().enumerate() {
+				let offset = Vec2::from_angle(PI/2.0 + idx as f32 * TAU / 3.0) * 0.25;
+				plane.set_color(Color::hsv(*hue, 0.7, 0.8));
+				plane.build(geom::Polygon::from_pos_scale(10, pos + offset, Vec2::splat(0.6)));
+			}
+		}
+
+		BoatPaint => {
+			// A little hull and sail
+			plane.build(geom::Quad::from_matrix(Mat2x3::scale_translate(Vec2::new(1.0, 0.3), pos - Vec2::from_y(0.3))));
+			build_triangle(plane, pos + Vec2::new(0.1, 0.2), 0.35, 0.0);
+		}
+
+		Back => build_triangle(plane, pos, 0.5, PI),
+	}
+}
+
+fn build_setting_value(plane: &mut (impl PolyBuilder2D + ColoredPolyBuilder), settings: &model::Settings, item: SettingItem, pos: Vec2) {
+	let center = pos + Vec2::from_x(model::PAUSE_MENU_SLIDER_CENTER);
+
+	match settings.get(item) {
+		SettingValue::Slider(_) => {
+			let width = model::PAUSE_MENU_SLIDER_WIDTH;
+			let ratio = settings.ratio(item);
+
+			plane.set_color(Color::hsv(200.0, 0.2, 0.6));
+			plane.build(geom::Quad::from_matrix(Mat2x3::scale_translate(Vec2::new(width, 0.4), center)));
+
+			let fill_width = width * ratio;
+			let fill_center = center + Vec2::from_x(fill_width/2.0 - width/2.0);
+
+			plane.set_color(Color::hsv(200.0, 0.7, 0.8));
+			plane.build(geom::Quad::from_matrix(Mat2x3::scale_translate(Vec2::new(fill_width, 0.4), fill_center)));
+			plane.build(geom::Polygon::from_pos_scale(8, center + Vec2::from_x(fill_width - width/2.0), Vec2::splat(0.8)));
+		}
+
+		SettingValue::Toggle(enabled) => {
+			let (knob_offset, color) = match enabled {
+				true => (0.5, Color::hsv(120.0, 0.6, 0.7)),
+				false => (-0.5, Color::grey(0.6)),
+			};
+
+			plane.set_color(Color::hsv(200.0, 0.2, 0.6));
+			plane.build(geom::Quad::from_matrix(Mat2x3::scale_translate(Vec2::new(2.0, 1.0), center)));
+
+			plane.set_color(color);
+			plane.build(geom::Quad::from_matrix(Mat2x3::scale_translate(Vec2::splat(0.8), center + Vec2::from_x(knob_offset))));
+		}
+
+		SettingValue::Choice{index, count} => {
+			let spacing = model::PAUSE_MENU_SLIDER_WIDTH / count.max(1) as f32;
+			let start = center - Vec2::from_x(spacing * (count as f32 - 1.0) / 2.0);
+
+			for option in 0..count {
+				let (size, color) = match option == index {
+					true => (0.8, Color::hsv(200.0, 0.7, 0.8)),
+					false => (0.4, Color::hsv(200.0, 0.2, 0.6)),
+				};
+
+				plane.set_color(color);
+				plane.build(geom::Polygon::from_pos_scale(8, start + Vec2::from_x(spacing * option as f32), Vec2::splat(size)));
+			}
+		}
+
+		SettingValue::None => {}
+	}
+}
+
+
+
+/// An equilateral triangle pointing along `angle`
+fn build_triangle(plane: &mut (impl PolyBuilder2D + ColoredPolyBuilder), center: Vec2, radius: f32, angle: f32) {
+	let points = (0..3).map(|i| center + Vec2::from_angle(angle + i as f32 * TAU / 3.0) * radius);
+	plane.extend_2d_fan(3, points);
+}
+
+fn build_gear(plane: &mut (impl PolyBuilder2D + ColoredPolyBuilder), center: Vec2, radius: f32) {
+	let num_teeth = 8;
+
+	for tooth in 0..num_teeth {
+		let angle = tooth as f32 * TAU / num_teeth as f32;
+		let tooth_center = center + Vec2::from_angle(angle) * radius * 0.8;
+		plane.build(geom::Quad::from_matrix(Mat2x3::rotate_translate(angle, tooth_center) * Mat2x3::scale(Vec2::splat(radius * 0.35))));
+	}
+
+	plane.build(geom::Polygon::from_pos_scale(12, center, Vec2::splat(radius * 1.6)));
+}
+
+fn build_cross(plane: &mut (impl PolyBuilder2D + ColoredPolyBuilder), center: Vec2, radius: f32) {
+	for angle in [PI/4.0, -PI/4.0] {
+		plane.build(geom::Quad::from_matrix(Mat2x3::rotate_translate(angle, center) * Mat2x3::scale(Vec2::new(radius * 2.0, radius * 0.5))));
+	}
+}
+
+fn build_speaker(plane: &mut (impl PolyBuilder2D + ColoredPolyBuilder), center: Vec2) {
+	plane.build(geom::Quad::from_matrix(Mat2x3::scale_translate(Vec2::new(0.3, 0.4), center + Vec2::from_x(-0.3))));
+	plane.extend_2d_fan(4, [
+		center + Vec2::new(-0.2, 0.2),
+		center + Vec2::new(-0.2, -0.2),
+		center + Vec2::new(0.3, -0.5),
+		center + Vec2::new(0.3, 0.5),
+	]);
+}
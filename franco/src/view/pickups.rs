@@ -0,0 +1,127 @@
+use crate::prelude::*;
+use std::collections::HashMap;
+
+use model::PickupKind;
+use gfx::geom;
+
+
+pub struct PickupView {
+	pickup_kinds: HashMap<PickupKind, PickupKindMesh>,
+	shader: gfx::Shader,
+}
+
+
+impl PickupView {
+	pub fn new(gfx: &mut gfx::Context) -> Result<Self> {
+		let pickup_kinds = PickupKind::ALL.iter()
+			.map(|&kind| (kind, PickupKindMesh::new(gfx, kind)))
+			.collect();
+
+		let shader = gfx.new_simple_shader(shaders::COLOR_3D_INSTANCED_VERT, shaders::FLAT_COLOR_FOG_FRAG)?;
+
+		Ok(PickupView {
+			pickup_kinds,
+			shader,
+		})
+	}
+
+	pub fn update(&mut self, model: &model::Model, viewer: &model::Viewer<'_>) {
+		let player_pos_map = viewer.player.map_position;
+
+		for (kind, kind_mesh) in self.pickup_kinds.iter_mut() {
+			let instance_transforms: Vec<_> = model.world.pickups.iter()
+				.filter(move |pickup| pickup.kind == *kind && !pickup.collected)
+				.map(move |pickup| {
+					let diff_map = pickup.map_position - player_pos_map;
+					let bob = pickup.bob_phase.sin() * 0.15;
+					let roll = (pickup.bob_phase * 0.7).cos() * PI / 16.0;
+
+					Mat3x4::rotate_y_translate(pickup.rotation, model::map_to_world(diff_map).to_x0z() + Vec3::from_y(bob))
+						* Mat3x4::rotate_x(roll)
+				})
+				.collect();
+
+			kind_mesh.instance_buffer.upload(&instance_transforms);
+		}
+	}
+
+	pub fn draw(&self, ctx: &mut view::ViewContext) {
+		ctx.gfx.bind_shader(self.shader);
+
+		for kind_mesh in self.pickup_kinds.values() {
+			kind_mesh.draw(ctx);
+		}
+	}
+}
+
+
+
+/// Used wherever a pickup kind is represented in the ui
+pub fn pickup_marker_color(kind: PickupKind) -> Color {
+	match kind {
+		PickupKind::Bottle => Color::hsv(150.0, 0.6, 0.7),
+		PickupKind::Crate => Color::hsv(30.0, 0.7, 0.6),
+		PickupKind::Shell => Color::hsv(345.0, 0.4, 0.95),
+	}
+}
+
+
+
+
+struct PickupKindMesh {
+	mesh: gfx::Mesh<gfx::ColorVertex>,
+	instance_buffer: gfx::Buffer<Mat3x4>,
+}
+
+
+impl PickupKindMesh {
+	fn new(gfx: &mut gfx::Context, kind: PickupKind) -> PickupKindMesh {
+		let mut mesh_data = gfx::MeshData::new();
+
+		{
+			let mut mb = gfx::ColorMeshBuilder::new(&mut mesh_data);
+
+			match kind {
+				PickupKind::Bottle => {
+					mb.set_color(Color::hsv(150.0, 0.5, 0.6));
+					PolyBuilder3D::build(&mut mb, geom::Cuboid::from_matrix(Mat3x4::scale(Vec3::new(0.8, 0.35, 0.35))));
+
+					mb.set_color(Color::hsv(150.0, 0.4, 0.7));
+					PolyBuilder3D::build(&mut mb, geom::Cuboid::from_matrix(Mat3x4::scale_translate(Vec3::new(0.4, 0.15, 0.15), Vec3::from_x(0.55))));
+
+					mb.set_color(Color::hsv(30.0, 0.5, 0.5));
+					PolyBuilder3D::build(&mut mb, geom::Cuboid::from_matrix(Mat3x4::scale_translate(Vec3::splat(0.12), Vec3::from_x(0.8))));
+				}
+
+				PickupKind::Crate => {
+					mb.set_color(Color::hsv(30.0, 0.6, 0.5));
+					PolyBuilder3D::build(&mut mb, geom::Cuboid::from_matrix(Mat3x4::uniform_scale(1.0)));
+
+					// Slats around the sides
+					mb.set_color(Color::hsv(25.0, 0.6, 0.35));
+					for y in [-0.3, 0.3] {
+						PolyBuilder3D::build(&mut mb, geom::Cuboid::from_matrix(Mat3x4::scale_translate(Vec3::new(1.05, 0.15, 1.05), Vec3::from_y(y))));
+					}
+				}
+
+				PickupKind::Shell => {
+					mb.set_color(Color::hsv(15.0, 0.3, 0.95));
+					PolyBuilder3D::build(&mut mb, geom::Tetrahedron::from_matrix(Mat3x4::scale_translate(Vec3::new(0.9, 0.4, 0.9), Vec3::from_y(0.1))));
+
+					mb.set_color(Color::hsv(345.0, 0.4, 0.9));
+					PolyBuilder3D::build(&mut mb, geom::Tetrahedron::from_matrix(Mat3x4::scale_translate(Vec3::new(0.5, 0.5, 0.5), Vec3::from_y(0.3))));
+				}
+			}
+		}
+
+		PickupKindMesh {
+			mesh: gfx::Mesh::from_mesh_data(gfx, &mesh_data),
+			instance_buffer: gfx.new_buffer(gfx::BufferUsage::Stream),
+		}
+	}
+
+	fn draw(&self, ctx: &mut view::ViewContext) {
+		ctx.gfx.bind_shader_storage_buffer(0, self.instance_buffer);
+		self.mesh.draw_instanced(&mut ctx.gfx, gfx::DrawMode::Triangles, self.instance_buffer.len());
+	}
+}
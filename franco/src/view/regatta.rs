@@ -0,0 +1,94 @@
+use crate::prelude::*;
+
+use view::BasicMesh;
+use gfx::geom;
+
+
+/// Draws the course buoys and the ghost of the best run
+pub struct RegattaView {
+	shader: gfx::Shader,
+	mesh: gfx::Mesh<gfx::ColorVertex>,
+	mesh_data: gfx::MeshData<gfx::ColorVertex>,
+
+	boat_mesh: BasicMesh,
+	buoy_phase: f32,
+}
+
+
+impl RegattaView {
+	pub fn new(gfx: &mut gfx::Context, resources: &model::Resources) -> Result<Self> {
+		let boat_ent = resources.main_project.find_entity("Boat").unwrap();
+		let shader = gfx.new_simple_shader(shaders::COLOR_3D_VERT, shaders::FLAT_COLOR_FOG_FRAG)?;
+
+		Ok(RegattaView {
+			shader,
+			mesh: gfx::Mesh::new(gfx),
+			mesh_data: gfx::MeshData::new(),
+
+			boat_mesh: BasicMesh::from_entity(boat_ent),
+			buoy_phase: 0.0,
+		})
+	}
+
+	pub fn update(&mut self, model: &model::Model, viewer: &model::Viewer<'_>) {
+		self.mesh_data.clear();
+
+		let regatta = match &model.regatta {
+			Some(regatta) => regatta,
+			None => {
+				self.mesh.upload(&self.mesh_data);
+				return
+			}
+		};
+
+		let player_pos_map = viewer.player.map_position;
+		let next_checkpoint = regatta.next_checkpoint_index();
+
+		for (idx, &checkpoint) in regatta.checkpoints.iter().enumerate() {
+			let diff_map = checkpoint - player_pos_map;
+			let bob = (self.buoy_phase + idx as f32).sin() * 0.1;
+			let transform = Mat3x4::translate(model::map_to_world(diff_map).to_x0z() + Vec3::from_y(bob));
+
+			let is_next = next_checkpoint == Some(idx);
+			self.build_buoy(transform, idx == 0, is_next);
+		}
+
+		if let Some(ghost) = regatta.ghost_frame() {
+			let diff_map = ghost.map_position - player_pos_map;
+			let transform = Mat3x4::rotate_y_translate(ghost.heading, model::map_to_world(diff_map).to_x0z());
+			self.boat_mesh.build_silhouette_into(&mut self.mesh_data, transform, Color::hsv(200.0, 0.1, 0.95));
+		}
+
+		self.mesh.upload(&self.mesh_data);
+		self.buoy_phase += PI / 60.0;
+	}
+
+	pub fn draw(&self, ctx: &mut view::ViewContext) {
+		ctx.gfx.bind_shader(self.shader);
+		self.mesh.draw(&mut ctx.gfx, gfx::DrawMode::Triangles);
+	}
+
+	/// The start buoy is striped, and the one being raced towards stands taller with a flag
+	fn build_buoy(&mut self, transform: Mat3x4, is_start: bool, is_next: bool) {
+		let mut mb = gfx::ColorMeshBuilder::new(&mut self.mesh_data);
+		let height = if is_next { 3.0 } else { 1.5 };
+
+		let body_color = match is_start {
+			true => Color::hsv(0.0, 0.0, 0.95),
+			false => Color::hsv(20.0, 0.8, 0.9),
+		};
+
+		mb.set_color(body_color);
+		PolyBuilder3D::build(&mut mb, geom::Cuboid::from_matrix(transform * Mat3x4::scale_translate(Vec3::new(1.0, height, 1.0), Vec3::from_y(height / 2.0))));
+
+		if is_start {
+			mb.set_color(Color::hsv(0.0, 0.8, 0.8));
+			PolyBuilder3D::build(&mut mb, geom::Cuboid::from_matrix(transform * Mat3x4::scale_translate(Vec3::new(1.05, height / 4.0, 1.05), Vec3::from_y(height / 2.0))));
+		}
+
+		if is_next {
+			mb.set_color(Color::hsv(50.0, 0.9, 1.0));
+			PolyBuilder3D::build(&mut mb, geom::Cuboid::from_matrix(transform * Mat3x4::scale_translate(Vec3::new(1.2, 0.6, 0.1), Vec3::new(0.6, height + 0.3, 0.0))));
+		}
+	}
+}
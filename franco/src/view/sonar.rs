@@ -0,0 +1,115 @@
+use crate::prelude::*;
+use std::collections::HashMap;
+
+use view::BasicMesh;
+use gfx::geom;
+
+
+const SONAR_RING_SEGMENTS: usize = 64;
+const SONAR_RING_WIDTH: f32 = 2.0;
+
+
+/// Draws expanding sonar rings on the water surface, and outlines of the friends they've found.
+/// Drawn after the water so outlines show even for friends swimming beneath it
+pub struct SonarView {
+	shader: gfx::Shader,
+	mesh: gfx::Mesh<gfx::ColorVertex>,
+	mesh_data: gfx::MeshData<gfx::ColorVertex>,
+
+	friend_meshes: HashMap<model::FriendName, BasicMesh>,
+}
+
+
+impl SonarView {
+	pub fn new(gfx: &mut gfx::Context, resources: &model::Resources) -> Result<Self> {
+		let friend_scene = resources.main_project.find_scene("friends").unwrap();
+
+		let friend_meshes = friend_scene.entities_with_prefix("FRIEND_")
+			.map(|entity| {
+				let name = model::FriendName::from_name(&entity.name);
+				(name, BasicMesh::from_entity(entity))
+			})
+			.collect();
+
+		let shader = gfx.new_simple_shader(shaders::COLOR_3D_VERT, shaders::FLAT_COLOR_FOG_FRAG)?;
+
+		Ok(SonarView {
+			shader,
+			mesh: gfx::Mesh::new(gfx),
+			mesh_data: gfx::MeshData::new(),
+
+			friend_meshes,
+		})
+	}
+
+	pub fn update(&mut self, model: &model::Model, viewer: &model::Viewer<'_>) {
+		self.mesh_data.clear();
+
+		let player_pos_map = viewer.player.map_position;
+
+		for ping in model.world.sonar_pings.iter() {
+			let center = model::map_to_world(ping.origin - player_pos_map);
+			let radius = ping.radius * model::MAP_SCALE;
+			let color = Color::hsv(180.0, 0.6, 0.5 + ping.strength() * 0.5);
+
+			self.build_ring(center, radius, color);
+		}
+
+		for friend in model.world.friends.iter() {
+			let contact = match friend.sonar_contact {
+				Some(contact) if contact.is_revealed() && !friend.met_player() => contact,
+				_ => continue,
+			};
+
+			let fade = (contact.reveal_timer / model::SONAR_REVEAL_TIME).min(1.0);
+			let world_pos = model::map_to_world(friend.map_position - player_pos_map).to_x0z();
+			let base_transform = Mat3x4::rotate_y_translate(friend.heading, world_pos);
+
+			// Flattened onto the surface, with a slightly larger bright copy underneath for the outline
+			let outline_transform = base_transform * Mat3x4::scale_translate(Vec3::new(1.3, 0.01, 1.3), Vec3::from_y(0.5));
+			let fill_transform = base_transform * Mat3x4::scale_translate(Vec3::new(1.0, 0.01, 1.0), Vec3::from_y(0.55));
+
+			let mesh = &self.friend_meshes[&friend.name];
+			mesh.build_silhouette_into(&mut self.mesh_data, outline_transform, Color::hsv(180.0, 0.7, fade));
+			mesh.build_silhouette_into(&mut self.mesh_data, fill_transform, Color::hsv(200.0, 0.6, 0.3 * fade));
+		}
+
+		self.mesh.upload(&self.mesh_data);
+	}
+
+	pub fn draw(&self, ctx: &mut view::ViewContext) {
+		ctx.gfx.bind_shader(self.shader);
+		self.mesh.draw(&mut ctx.gfx, gfx::DrawMode::Triangles);
+	}
+
+	fn build_ring(&mut self, center: Vec2, radius: f32, color: Color) {
+		if radius <= 0.0 {
+			return
+		}
+
+		let mut mb = gfx::ColorMeshBuilder::new(&mut self.mesh_data);
+
+		// Maps 2D map space onto the water surface, just above it to avoid z-fighting
+		let mut plane = mb.on_plane_ref(Mat3::from_columns([
+			Vec3::from_x(1.0),
+			Vec3::from_z(1.0),
+			center.to_x0z() + Vec3::from_y(0.5),
+		]));
+
+		plane.set_color(color);
+
+		let segment_length = TAU * radius / SONAR_RING_SEGMENTS as f32;
+
+		for segment in 0..SONAR_RING_SEGMENTS {
+			let angle = segment as f32 * TAU / SONAR_RING_SEGMENTS as f32;
+			let radial = Vec2::from_angle(angle);
+			let tangent = radial.perp();
+
+			plane.build(geom::Quad::from_matrix(Mat2x3::from_columns([
+				tangent * segment_length * 1.05,
+				radial * SONAR_RING_WIDTH,
+				radial * radius,
+			])));
+		}
+	}
+}
@@ -0,0 +1,47 @@
+use crate::prelude::*;
+
+
+/// Runtime text, built with a shared font atlas and drawn in whatever space the bound camera is in -
+/// ui units under the ui camera, world units in the 3d view
+pub struct TextView {
+	shader: gfx::Shader,
+	font: gfx::FontAtlas,
+	mesh: gfx::Mesh<gfx::TexturedVertex>,
+	mesh_data: gfx::MeshData<gfx::TexturedVertex>,
+}
+
+impl TextView {
+	pub fn new(gfx: &mut gfx::Context, font: &gfx::FontAtlas) -> Result<TextView> {
+		let shader = gfx.new_simple_shader(shaders::TEXTURED_VERT, shaders::TEXT_FRAG)?;
+
+		Ok(TextView {
+			shader,
+			font: font.clone(),
+			mesh: gfx::Mesh::new(gfx),
+			mesh_data: gfx::MeshData::new(),
+		})
+	}
+
+	pub fn font(&self) -> &gfx::FontAtlas {
+		&self.font
+	}
+
+	pub fn clear(&mut self) {
+		self.mesh_data.clear();
+	}
+
+	/// `transform` places the text's origin, which it's aligned around according to `style`
+	pub fn add(&mut self, text: &str, style: &gfx::TextStyle, transform: Mat3x4) {
+		self.font.build_text(&mut self.mesh_data, text, style, transform);
+	}
+
+	pub fn upload(&mut self) {
+		self.mesh.upload(&self.mesh_data);
+	}
+
+	pub fn draw(&self, ctx: &mut view::ViewContext<'_>) {
+		ctx.gfx.bind_shader(self.shader);
+		ctx.gfx.bind_texture(0, self.font.texture());
+		self.mesh.draw(&mut ctx.gfx, gfx::DrawMode::Triangles);
+	}
+}
@@ -1,7 +1,9 @@
 use crate::prelude::*;
+use std::collections::HashMap;
 
-use model::{UiPosition, MapObjectType};
-use view::BasicMesh;
+use model::{UiPosition, MapObjectType, StatKey, HintKind};
+use view::{BasicMesh, pickup_marker_color};
+use gfx::geom;
 
 
 pub struct UiView {
@@ -13,6 +15,7 @@ pub struct UiView {
 	zoom_out_icon: BasicMesh,
 
 	map_icon: BasicMesh,
+	journal_icon: BasicMesh,
 	sail_icon: BasicMesh,
 	anchor_icon: BasicMesh,
 	steering_wheel: BasicMesh,
@@ -24,16 +27,26 @@ pub struct UiView {
 	wiggle_phase: f32,
 
 	map_view: MapView,
+	journal_view: JournalView,
+	toast_view: ToastView,
+	hint_view: HintView,
+	trust_meter_view: TrustMeterView,
+	pickup_counter_view: PickupCounterView,
+	port_panel_view: PortPanelView,
+	instruments_view: InstrumentsView,
+	race_timer_view: RaceTimerView,
+	stats_screen_view: StatsScreenView,
 }
 
 impl UiView {
-	pub fn new(gfx: &mut gfx::Context, resources: &model::Resources) -> Result<UiView> {
+	pub fn new(gfx: &mut gfx::Context, resources: &model::Resources, font: &gfx::FontAtlas) -> Result<UiView> {
 		let ui_scene = resources.main_project.find_scene("ui").unwrap();
 
 		let zoom_in_icon = BasicMesh::from_entity(ui_scene.find_entity("ICON_zoom_in").unwrap());
 		let zoom_out_icon = BasicMesh::from_entity(ui_scene.find_entity("ICON_zoom_out").unwrap());
 
 		let map_icon = BasicMesh::from_entity(ui_scene.find_entity("ICON_map").unwrap());
+		let journal_icon = build_journal_icon();
 		let sail_icon = BasicMesh::from_entity(ui_scene.find_entity("ICON_sail").unwrap());
 		let anchor_icon = BasicMesh::from_entity(ui_scene.find_entity("ICON_anchor").unwrap());
 		let steering_wheel = BasicMesh::from_entity(ui_scene.find_entity("SteeringWheel").unwrap());
@@ -45,6 +58,15 @@ impl UiView {
 		let shader = gfx.new_simple_shader(shaders::COLOR_3D_VERT, shaders::FLAT_COLOR_FRAG)?;
 
 		let map_view = MapView::new(gfx, &ui_scene)?;
+		let journal_view = JournalView::new(gfx, resources, &ui_scene)?;
+		let toast_view = ToastView::new(gfx, font)?;
+		let hint_view = HintView::new(gfx, &ui_scene)?;
+		let trust_meter_view = TrustMeterView::new(gfx, &ui_scene)?;
+		let pickup_counter_view = PickupCounterView::new(gfx)?;
+		let port_panel_view = PortPanelView::new(gfx, resources)?;
+		let instruments_view = InstrumentsView::new(gfx)?;
+		let race_timer_view = RaceTimerView::new(gfx)?;
+		let stats_screen_view = StatsScreenView::new(gfx, font)?;
 
 		Ok(UiView {
 			shader,
@@ -55,6 +77,7 @@ impl UiView {
 			zoom_out_icon,
 
 			map_icon,
+			journal_icon,
 			sail_icon,
 			anchor_icon,
 			steering_wheel,
@@ -66,33 +89,57 @@ impl UiView {
 			wiggle_phase: 0.0,
 
 			map_view,
+			journal_view,
+			toast_view,
+			hint_view,
+			trust_meter_view,
+			pickup_counter_view,
+			port_panel_view,
+			instruments_view,
+			race_timer_view,
+			stats_screen_view,
 		})
 	}
 
-	pub fn update(&mut self, model: &model::Model) {
+	pub fn update(&mut self, model: &model::Model, viewer: &model::Viewer<'_>) {
 		use model::GameState;
 
 		self.mesh_data.clear();
 
+		let widgets = &viewer.ui.widgets;
+		let hud = &viewer.ui.hud;
+
 		let buttons = [
-			(&model.ui.map_button, &self.map_icon),
-			(&model.ui.sail_button, &self.sail_icon),
-			(&model.ui.anchor_button, &self.anchor_icon),
-			(&model.ui.zoom_in_button, &self.zoom_in_icon),
-			(&model.ui.zoom_out_button, &self.zoom_out_icon),
+			(hud.map_button, &self.map_icon, Some(HintKind::OpenMap)),
+			(hud.journal_button, &self.journal_icon, None),
+			(hud.sail_button, &self.sail_icon, Some(HintKind::RaiseSail)),
+			(hud.anchor_button, &self.anchor_icon, None),
+			(hud.zoom_in_button, &self.zoom_in_icon, None),
+			(hud.zoom_out_button, &self.zoom_out_icon, None),
 		];
 
-		for (button, icon) in buttons {
-			let pos = button.position.resolve(model.ui.aspect);
-			let phase = button.state.as_phase();
+		for (button, icon, hint) in buttons {
+			let pos = widgets.position(button, viewer.ui.metrics);
+			let phase = widgets[button].state.as_phase();
+
+			// Keyboard focus gets a backing, so it can be told apart from hovering
+			if widgets.focused() == Some(button) {
+				let mut mb = gfx::ColorMeshBuilder::new(&mut self.mesh_data);
+				let mut plane = mb.on_plane_ref(ui_plane(pos.extend(-0.1)));
+
+				plane.set_color(Color::hsv(45.0, 0.4, 1.0));
+				plane.build(geom::Polygon::from_pos_scale(16, Vec2::zero(), Vec2::splat(2.6 + phase*0.5)));
+			}
+
+			let pulse = hint.map_or(0.0, |hint| viewer.ui.hints.pulse(hint));
 			let wiggle = (self.wiggle_phase * TAU).sin() * phase * PI/16.0;
 			let transform = Mat3x4::rotate_z_translate(wiggle, pos.extend(0.0))
-				* Mat3x4::uniform_scale(1.0 + phase*0.2);
+				* Mat3x4::uniform_scale(1.0 + phase*0.2 + pulse*0.3);
 			icon.build_into(&mut self.mesh_data, transform);
 		}
 
 		let text_wiggle = (self.wiggle_phase * TAU).sin() * PI/16.0;
-		let text_pos = model::UiPosition::Center(Vec2::zero()).resolve(model.ui.aspect);
+		let text_pos = model::UiPosition::Center(Vec2::zero()).resolve(viewer.ui.metrics);
 		let transform = Mat3x4::rotate_z_translate(text_wiggle, text_pos.extend(1.0));
 
 		match model.global.game_state {
@@ -109,17 +156,26 @@ impl UiView {
 			_ => {}
 		}
 
-		let pos = model.ui.wheel.position().resolve(model.ui.aspect);
-		let wheel_phase = model.ui.wheel.state.as_phase();
+		let pos = widgets.position(hud.wheel, viewer.ui.metrics);
+		let wheel_phase = widgets[hud.wheel].state.as_phase();
 		let wheel_transform = Mat3x4::rotate_x_translate(-PI/8.0, pos.extend(-0.5))
-			* Mat3x4::rotate_z(model.ui.wheel.angle)
+			* Mat3x4::rotate_z(viewer.ui.wheel_angle())
 			* Mat3x4::scale(Vec3::splat(1.0 + wheel_phase*0.5));
 
 		self.steering_wheel.build_into(&mut self.mesh_data, wheel_transform);
 
 		self.mesh.upload(&self.mesh_data);
 
-		self.map_view.update(model);
+		self.map_view.update(model, viewer);
+		self.journal_view.update(model, viewer);
+		self.toast_view.update(model, viewer);
+		self.hint_view.update(viewer);
+		self.trust_meter_view.update(viewer);
+		self.pickup_counter_view.update(viewer);
+		self.port_panel_view.update(model, viewer);
+		self.instruments_view.update(model, viewer);
+		self.race_timer_view.update(model, viewer);
+		self.stats_screen_view.update(model, viewer);
 
 		self.wiggle_phase += 1.5 / 60.0;
 		self.wiggle_phase %= 1.0;
@@ -130,6 +186,15 @@ impl UiView {
 		self.mesh.draw(&mut ctx.gfx, gfx::DrawMode::Triangles);
 
 		self.map_view.draw(ctx);
+		self.journal_view.draw(ctx);
+		self.toast_view.draw(ctx);
+		self.hint_view.draw(ctx);
+		self.trust_meter_view.draw(ctx);
+		self.pickup_counter_view.draw(ctx);
+		self.port_panel_view.draw(ctx);
+		self.instruments_view.draw(ctx);
+		self.race_timer_view.draw(ctx);
+		self.stats_screen_view.draw(ctx);
 	}
 }
 
@@ -178,11 +243,11 @@ impl MapView {
 		})
 	}
 
-	fn update(&mut self, model: &model::Model) {
-		let open_pos = UiPosition::Center(Vec2::zero()).resolve(model.ui.aspect);
-		let close_pos = UiPosition::TopLeft(Vec2::splat(-1.0)).resolve(model.ui.aspect);
+	fn update(&mut self, model: &model::Model, viewer: &model::Viewer<'_>) {
+		let open_pos = UiPosition::Center(Vec2::zero()).resolve(viewer.ui.metrics);
+		let close_pos = UiPosition::TopLeft(Vec2::splat(-1.0)).resolve(viewer.ui.metrics);
 
-		let map_phase = model.ui.map.state.as_phase();
+		let map_phase = viewer.ui.map_state().as_phase();
 
 		let map_pos = map_phase.ease_linear(close_pos, open_pos).extend(0.1);
 		let map_scale = Vec3::splat(map_phase.ease_linear(0.01, 1.0));
@@ -194,6 +259,19 @@ impl MapView {
 
 		let map_to_ui_factor = self.usable_area / model.world.map.size;
 
+		// Dark backings behind friend and boat icons, so they stand out from the chart.
+		// They all sit just under the lowest of those icons, so one icon's backing never covers another icon.
+		let high_contrast = model.settings.color_mode == model::ColorMode::HighContrast;
+		let build_backing = |mesh_data: &mut gfx::MeshData<gfx::ColorVertex>, pos: Vec2, radius: f32| {
+			if high_contrast {
+				let mut mb = gfx::ColorMeshBuilder::new(mesh_data);
+				let mut plane = mb.on_plane_ref(ui_plane(base_transform * pos.extend(0.34)));
+
+				plane.set_color(Color::grey(0.05));
+				plane.build(geom::Polygon::from_pos_scale(12, Vec2::zero(), Vec2::splat(radius * 2.6 * map_scale.x)));
+			}
+		};
+
 		for object in model.world.map.objects.iter() {
 			let pos = (object.map_position * map_to_ui_factor).extend(0.3);
 			let island_transform = base_transform * Mat3x4::translate(pos);
@@ -207,19 +285,493 @@ impl MapView {
 			uimesh.build_into(&mut self.mesh_data, island_transform);
 		}
 
-		for friend in model.world.friends.iter() {
-			if friend.met_player {
-				continue
+		// Unmet friends only show up once sonar has found them, at the spot they were found
+		for friend in model.world.friends.iter().filter(|friend| !friend.met_player()) {
+			let contact = match friend.sonar_contact {
+				Some(contact) => contact,
+				None => continue,
+			};
+
+			let reveal = contact.reveal_timer / model::SONAR_REVEAL_TIME;
+			let scale = 1.0 + (reveal * PI * 4.0).sin().abs() * reveal * 0.5;
+
+			let pos = (contact.map_position * map_to_ui_factor).extend(0.35);
+			build_backing(&mut self.mesh_data, pos.to_xy(), self.friend_uimesh.radius() * scale);
+
+			let friend_transform = base_transform * Mat3x4::scale_translate(Vec3::splat(scale), pos);
+			self.friend_uimesh.build_into(&mut self.mesh_data, friend_transform);
+		}
+
+		{
+			let mut mb = gfx::ColorMeshBuilder::new(&mut self.mesh_data);
+
+			for pickup in model.world.pickups.iter().filter(|pickup| pickup.discovered && !pickup.collected) {
+				let pos = (pickup.map_position * map_to_ui_factor).extend(0.32);
+				let mut plane = mb.on_plane_ref(ui_plane(base_transform * pos));
+
+				plane.set_color(Color::grey(0.2));
+				plane.build(geom::Polygon::from_pos_scale(6, Vec2::zero(), Vec2::splat(0.5 * map_scale.x)));
+
+				plane.set_color(pickup_marker_color(pickup.kind));
+				plane.build(geom::Polygon::from_pos_scale(6, Vec2::zero(), Vec2::splat(0.35 * map_scale.x)));
 			}
+		}
 
-			let pos = (friend.map_position * map_to_ui_factor).extend(0.35);
-			let island_transform = base_transform * Mat3x4::translate(pos);
-			self.friend_uimesh.build_into(&mut self.mesh_data, island_transform);
+		if let Some(regatta) = &model.regatta {
+			let mut mb = gfx::ColorMeshBuilder::new(&mut self.mesh_data);
+			let next_checkpoint = regatta.next_checkpoint_index();
+
+			for (idx, &checkpoint) in regatta.checkpoints.iter().enumerate() {
+				let pos = (checkpoint * map_to_ui_factor).extend(0.33);
+				let mut plane = mb.on_plane_ref(ui_plane(base_transform * pos));
+
+				let (size, color) = match next_checkpoint == Some(idx) {
+					true => (0.6, Color::hsv(50.0, 0.9, 1.0)),
+					false => (0.4, Color::hsv(20.0, 0.8, 0.9)),
+				};
+
+				plane.set_color(color);
+				plane.build(geom::Quad::from_matrix(Mat2x3::scale_translate(Vec2::splat(size * map_scale.x), Vec2::zero())));
+			}
+		}
+
+		for player in model.boats() {
+			let pos = (player.map_position * map_to_ui_factor).extend(0.4);
+			build_backing(&mut self.mesh_data, pos.to_xy(), self.player_uimesh.radius());
+
+			let player_transform = base_transform * Mat3x4::rotate_z_translate(player.heading, pos);
+			self.player_uimesh.build_into(&mut self.mesh_data, player_transform);
+		}
+
+		self.mesh.upload(&self.mesh_data);
+	}
+
+	fn draw(&self, ctx: &mut view::ViewContext<'_>) {
+		ctx.gfx.bind_shader(self.shader);
+		self.mesh.draw(&mut ctx.gfx, gfx::DrawMode::Triangles);
+	}
+}
+
+
+
+
+
+const JOURNAL_CARD_SIZE: Vec2 = Vec2::new(5.0, 7.0);
+const JOURNAL_CARD_SPACING: f32 = 0.5;
+const JOURNAL_CHART_SIZE: f32 = 2.4;
+const JOURNAL_TIMELINE_WIDTH: f32 = 4.0;
+
+struct JournalView {
+	shader: gfx::Shader,
+	mesh: gfx::Mesh<gfx::ColorVertex>,
+	mesh_data: gfx::MeshData<gfx::ColorVertex>,
+
+	usable_area: Vec2,
+	chart_uimesh: BasicMesh,
+	friend_meshes: HashMap<model::FriendName, BasicMesh>,
+
+	spin_phase: f32,
+}
+
+impl JournalView {
+	fn new(gfx: &mut gfx::Context, resources: &model::Resources, ui_scene: &toy::SceneRef<'_>) -> Result<JournalView> {
+		let shader = gfx.new_simple_shader(shaders::COLOR_3D_VERT, shaders::FLAT_COLOR_FRAG)?;
+
+		let usable_area = ui_scene.find_entity("REF_usable_area").unwrap().scale.to_xy();
+		let chart_uimesh = BasicMesh::from_entity(ui_scene.find_entity("MapBg").unwrap());
+
+		let friend_scene = resources.main_project.find_scene("friends").unwrap();
+		let friend_meshes = friend_scene.entities_with_prefix("FRIEND_")
+			.map(|entity| (model::FriendName::from_name(&entity.name), BasicMesh::from_entity(entity)))
+			.collect();
+
+		Ok(JournalView {
+			shader,
+			mesh: gfx::Mesh::new(gfx),
+			mesh_data: gfx::MeshData::new(),
+
+			usable_area,
+			chart_uimesh,
+			friend_meshes,
+
+			spin_phase: 0.0,
+		})
+	}
+
+	fn update(&mut self, model: &model::Model, viewer: &model::Viewer<'_>) {
+		self.mesh_data.clear();
+
+		self.spin_phase += 0.3 / 60.0;
+		self.spin_phase %= 1.0;
+
+		let phase = viewer.ui.journal.state.as_phase();
+		if phase <= 0.0 {
+			self.mesh.upload(&self.mesh_data);
+			return
+		}
+
+		let open_pos = UiPosition::Center(Vec2::zero()).resolve(viewer.ui.metrics);
+		let close_pos = viewer.ui.widgets.position(viewer.ui.hud.journal_button, viewer.ui.metrics);
+		let panel_pos = phase.ease_linear(close_pos, open_pos);
+		let panel_scale = phase.ease_linear(0.01, 1.0);
+
+		let entries = model::Journal::entries(&model.world);
+
+		// Lay cards out in a single row if there's room, otherwise in a grid
+		let card_stride = JOURNAL_CARD_SIZE + Vec2::splat(JOURNAL_CARD_SPACING);
+		let max_columns = ((viewer.ui.metrics.extents().x * 2.0) / card_stride.x) as usize;
+		let columns = entries.len().min(max_columns).max(1);
+		let rows = (entries.len() + columns - 1) / columns;
+
+		let grid_size = Vec2::new(columns as f32, rows as f32) * card_stride;
+		let grid_origin = Vec2::new(-grid_size.x + card_stride.x, grid_size.y - card_stride.y) / 2.0;
+
+		let base_transform = Mat3x4::scale_translate(Vec3::splat(panel_scale), panel_pos.extend(2.5));
+		let now = model.stats.session.playtime.max(1.0);
+		let chart_scale = JOURNAL_CHART_SIZE / self.usable_area.x.max(self.usable_area.y);
+		let map_to_chart = self.usable_area / model.world.map.size * chart_scale;
+
+		for (idx, entry) in entries.iter().enumerate() {
+			let column = idx % columns;
+			let row = idx / columns;
+			let card_pos = grid_origin + Vec2::new(column as f32, -(row as f32)) * card_stride;
+
+			let mut mb = gfx::ColorMeshBuilder::new(&mut self.mesh_data);
+			let mut plane = mb.on_plane_ref(ui_plane(panel_pos.extend(2.5)) * Mat3::from_columns([
+				Vec3::from_x(panel_scale),
+				Vec3::from_y(panel_scale),
+				Vec3::from_z(1.0),
+			]));
+
+			plane.set_color(Color::hsv(40.0, 0.3, 0.4));
+			plane.build(geom::Quad::from_matrix(Mat2x3::scale_translate(JOURNAL_CARD_SIZE, card_pos)));
+
+			plane.set_color(Color::hsv(45.0, 0.1, 0.9));
+			plane.build(geom::Quad::from_matrix(Mat2x3::scale_translate(JOURNAL_CARD_SIZE - Vec2::splat(0.3), card_pos)));
+
+			// Where on the voyage they were met
+			let timeline_pos = card_pos + Vec2::from_y(-3.0);
+			plane.set_color(Color::hsv(200.0, 0.2, 0.6));
+			plane.build(geom::Quad::from_matrix(Mat2x3::scale_translate(Vec2::new(JOURNAL_TIMELINE_WIDTH, 0.3), timeline_pos)));
+
+			if let Some(meeting) = entry.meeting {
+				let ratio = (meeting.time / now).clamp(0.0, 1.0);
+				let marker_pos = timeline_pos + Vec2::from_x((ratio - 0.5) * JOURNAL_TIMELINE_WIDTH);
+
+				plane.set_color(Color::hsv(200.0, 0.7, 0.8));
+				plane.build(geom::Polygon::from_pos_scale(6, marker_pos, Vec2::splat(0.6)));
+
+				build_star(&mut plane, card_pos + JOURNAL_CARD_SIZE / 2.0 - Vec2::splat(0.7), 0.45, Color::hsv(45.0, 0.8, 1.0));
+			}
+
+			// Where they were found
+			let chart_pos = card_pos + Vec2::from_y(-1.0);
+			let chart_transform = base_transform * Mat3x4::scale_translate(Vec3::splat(chart_scale), chart_pos.extend(0.1));
+			self.chart_uimesh.build_into(&mut self.mesh_data, chart_transform);
+
+			if let Some(meeting) = entry.meeting {
+				let marker_pos = chart_pos + meeting.map_position * map_to_chart;
+
+				let mut mb = gfx::ColorMeshBuilder::new(&mut self.mesh_data);
+				let mut plane = mb.on_plane_ref(ui_plane(panel_pos.extend(2.8)) * Mat3::from_columns([
+					Vec3::from_x(panel_scale),
+					Vec3::from_y(panel_scale),
+					Vec3::from_z(1.0),
+				]));
+
+				build_star(&mut plane, marker_pos, 0.3, Color::hsv(0.0, 0.7, 0.8));
+			}
+
+			// Spinning preview, in silhouette until met
+			if let Some(friend_mesh) = self.friend_meshes.get(&entry.name) {
+				let preview_pos = card_pos + Vec2::from_y(1.8);
+				let preview_scale = 1.5 / friend_mesh.radius().max(0.01);
+
+				let transform = base_transform
+					* Mat3x4::rotate_x_translate(PI/8.0, preview_pos.extend(2.0))
+					* Mat3x4::rotate_y(self.spin_phase * TAU)
+					* Mat3x4::uniform_scale(preview_scale);
+
+				match entry.meeting {
+					Some(_) => friend_mesh.build_into(&mut self.mesh_data, transform),
+					None => friend_mesh.build_silhouette_into(&mut self.mesh_data, transform, Color::grey(0.25)),
+				}
+			}
+		}
+
+		self.mesh.upload(&self.mesh_data);
+	}
+
+	fn draw(&self, ctx: &mut view::ViewContext<'_>) {
+		ctx.gfx.bind_shader(self.shader);
+		self.mesh.draw(&mut ctx.gfx, gfx::DrawMode::Triangles);
+	}
+}
+
+
+
+
+
+struct ToastView {
+	shader: gfx::Shader,
+	mesh: gfx::Mesh<gfx::ColorVertex>,
+	mesh_data: gfx::MeshData<gfx::ColorVertex>,
+	text: view::TextView,
+}
+
+impl ToastView {
+	fn new(gfx: &mut gfx::Context, font: &gfx::FontAtlas) -> Result<ToastView> {
+		let shader = gfx.new_simple_shader(shaders::COLOR_3D_VERT, shaders::FLAT_COLOR_FRAG)?;
+
+		Ok(ToastView {
+			shader,
+			mesh: gfx::Mesh::new(gfx),
+			mesh_data: gfx::MeshData::new(),
+			text: view::TextView::new(gfx, font)?,
+		})
+	}
+
+	fn update(&mut self, model: &model::Model, viewer: &model::Viewer<'_>) {
+		self.mesh_data.clear();
+		self.text.clear();
+
+		let toast = &model.ui.toast;
+
+		if !matches!(toast.state, model::UiPanelState::Closed) {
+			let pos = toast.position().resolve(viewer.ui.metrics);
+
+			let mut mb = gfx::ColorMeshBuilder::new(&mut self.mesh_data);
+			let mut plane = mb.on_plane_ref(ui_plane(pos.extend(2.0)));
+
+			plane.set_color(Color::hsv(40.0, 0.3, 0.4));
+			plane.build(geom::Quad::from_matrix(Mat2x3::scale(Vec2::new(7.0, 1.6))));
+
+			plane.set_color(Color::hsv(45.0, 0.1, 0.9));
+			plane.build(geom::Quad::from_matrix(Mat2x3::scale(Vec2::new(6.7, 1.3))));
+
+			let star_phase = toast.state.as_phase();
+			build_star(&mut plane, Vec2::from_x(-2.6), 0.6 * star_phase, Color::hsv(45.0, 0.8, 1.0));
+
+			if let Some(achievement) = toast.achievement.and_then(|idx| model.stats.achievements.get(idx)) {
+				let style = gfx::TextStyle::new(0.55)
+					.with_color(Color::hsv(40.0, 0.4, 0.25))
+					.aligned(gfx::TextAlign::Left, gfx::VerticalAlign::Middle)
+					.wrapped(4.6);
+
+				self.text.add(&achievement.title, &style, Mat3x4::translate(pos.extend(2.1) + Vec3::from_x(-1.9)));
+			}
+		}
+
+		self.mesh.upload(&self.mesh_data);
+		self.text.upload();
+	}
+
+	fn draw(&self, ctx: &mut view::ViewContext<'_>) {
+		ctx.gfx.bind_shader(self.shader);
+		self.mesh.draw(&mut ctx.gfx, gfx::DrawMode::Triangles);
+
+		self.text.draw(ctx);
+	}
+}
+
+
+
+
+
+/// How far from the center of a hint's target the tip of its pointer sits
+const HINT_BUTTON_POINTER_DIST: f32 = 1.4;
+const HINT_WHEEL_POINTER_DIST: f32 = 4.5;
+const HINT_WHEEL_DRAG_RADIUS: f32 = 3.0;
+
+/// Shows what a hint is about, and points at where to find it
+struct HintView {
+	shader: gfx::Shader,
+	mesh: gfx::Mesh<gfx::ColorVertex>,
+	mesh_data: gfx::MeshData<gfx::ColorVertex>,
+
+	sail_icon: BasicMesh,
+	map_icon: BasicMesh,
+	wheel_icon: BasicMesh,
+}
+
+impl HintView {
+	fn new(gfx: &mut gfx::Context, ui_scene: &toy::SceneRef<'_>) -> Result<HintView> {
+		let shader = gfx.new_simple_shader(shaders::COLOR_3D_VERT, shaders::FLAT_COLOR_FRAG)?;
+
+		Ok(HintView {
+			shader,
+			mesh: gfx::Mesh::new(gfx),
+			mesh_data: gfx::MeshData::new(),
+
+			sail_icon: BasicMesh::from_entity(ui_scene.find_entity("ICON_sail").unwrap()),
+			map_icon: BasicMesh::from_entity(ui_scene.find_entity("ICON_map").unwrap()),
+			wheel_icon: BasicMesh::from_entity(ui_scene.find_entity("SteeringWheel").unwrap()),
+		})
+	}
+
+	fn update(&mut self, viewer: &model::Viewer<'_>) {
+		self.mesh_data.clear();
+
+		let hints = &viewer.ui.hints;
+
+		let kind = match hints.active {
+			Some(kind) if !matches!(hints.state, model::UiPanelState::Closed) => kind,
+			_ => {
+				self.mesh.upload(&self.mesh_data);
+				return
+			}
+		};
+
+		let metrics = viewer.ui.metrics;
+		let phase = hints.state.as_phase();
+		let pos = hints.position().resolve(metrics);
+
+		{
+			let mut mb = gfx::ColorMeshBuilder::new(&mut self.mesh_data);
+			let mut plane = mb.on_plane_ref(ui_plane(pos.extend(2.0)));
+
+			plane.set_color(Color::hsv(40.0, 0.3, 0.4));
+			plane.build(geom::Quad::from_matrix(Mat2x3::scale(Vec2::new(2.6, 2.6))));
+
+			plane.set_color(Color::hsv(45.0, 0.1, 0.9));
+			plane.build(geom::Quad::from_matrix(Mat2x3::scale(Vec2::new(2.3, 2.3))));
+		}
+
+		let (icon, icon_scale) = match kind {
+			HintKind::RaiseSail => (&self.sail_icon, 0.8),
+			HintKind::OpenMap => (&self.map_icon, 0.8),
+			HintKind::SteerWheel => (&self.wheel_icon, 0.25),
+		};
+
+		icon.build_into(&mut self.mesh_data, Mat3x4::scale_translate(Vec3::splat(icon_scale * phase), pos.extend(2.2)));
+
+		// Pointers come at their targets from the middle of the screen, so they stay on screen
+		let target = kind.target(viewer.ui).resolve(metrics);
+		let pointer_dir = match target.length() > 0.1 {
+			true => -target.normalize(),
+			false => Vec2::from_y(1.0),
+		};
+
+		let pointer_dist = match kind {
+			HintKind::SteerWheel => HINT_WHEEL_POINTER_DIST,
+			_ => HINT_BUTTON_POINTER_DIST,
+		};
+
+		let bob = (hints.pulse_phase * TAU).sin().abs() * 0.4;
+		let tip = target + pointer_dir * (pointer_dist + bob);
+
+		let mut mb = gfx::ColorMeshBuilder::new(&mut self.mesh_data);
+		let mut plane = mb.on_plane_ref(ui_plane(Vec3::from_z(2.0)));
+
+		build_hint_pointer(&mut plane, tip, -pointer_dir, phase);
+
+		// A fingertip dragging back and forth across the top of the wheel
+		if kind == HintKind::SteerWheel {
+			let sweep = (hints.pulse_phase * TAU).sin() * 0.7;
+
+			for trail in 0..4 {
+				let trail_sweep = ((hints.pulse_phase - trail as f32 * 0.03) * TAU).sin() * 0.7;
+				let trail_pos = target + Vec2::from_angle(PI/2.0 + trail_sweep) * HINT_WHEEL_DRAG_RADIUS;
+				let size = (0.3 - trail as f32 * 0.05) * phase;
+
+				plane.set_color(Color::hsv(45.0, 0.2 + trail as f32 * 0.1, 0.9));
+				plane.build(geom::Polygon::from_pos_scale(10, trail_pos, Vec2::splat(size)));
+			}
+
+			let finger_pos = target + Vec2::from_angle(PI/2.0 + sweep) * HINT_WHEEL_DRAG_RADIUS;
+			plane.set_color(Color::hsv(0.0, 0.7, 0.8));
+			plane.build(geom::Polygon::from_pos_scale(10, finger_pos, Vec2::splat(0.45 * phase)));
 		}
 
-		let pos = (model.player.map_position * map_to_ui_factor).extend(0.4);
-		let player_transform = base_transform * Mat3x4::rotate_z_translate(model.player.heading, pos);
-		self.player_uimesh.build_into(&mut self.mesh_data, player_transform);
+		self.mesh.upload(&self.mesh_data);
+	}
+
+	fn draw(&self, ctx: &mut view::ViewContext<'_>) {
+		ctx.gfx.bind_shader(self.shader);
+		self.mesh.draw(&mut ctx.gfx, gfx::DrawMode::Triangles);
+	}
+}
+
+
+/// An arrow with its tip at `tip`, pointing along `dir`
+fn build_hint_pointer(plane: &mut (impl PolyBuilder2D + ColoredPolyBuilder), tip: Vec2, dir: Vec2, scale: f32) {
+	let transform = Mat2x3::rotate_translate(dir.to_angle(), tip) * Mat2x3::uniform_scale(scale);
+
+	plane.set_color(Color::hsv(0.0, 0.7, 0.8));
+
+	plane.extend_2d_fan(3, [
+		transform * Vec2::zero(),
+		transform * Vec2::new(-0.7, 0.5),
+		transform * Vec2::new(-0.7,-0.5),
+	]);
+
+	plane.build(geom::Quad::from_matrix(transform * Mat2x3::scale_translate(Vec2::new(1.0, 0.35), Vec2::from_x(-1.15))));
+}
+
+
+
+
+
+const TRUST_METER_SEGMENTS: usize = 12;
+
+struct TrustMeterView {
+	shader: gfx::Shader,
+	mesh: gfx::Mesh<gfx::ColorVertex>,
+	mesh_data: gfx::MeshData<gfx::ColorVertex>,
+
+	friend_uimesh: BasicMesh,
+}
+
+impl TrustMeterView {
+	fn new(gfx: &mut gfx::Context, ui_scene: &toy::SceneRef<'_>) -> Result<TrustMeterView> {
+		let shader = gfx.new_simple_shader(shaders::COLOR_3D_VERT, shaders::FLAT_COLOR_FRAG)?;
+		let friend_uimesh = BasicMesh::from_entity(ui_scene.find_entity("ICON_friend").unwrap());
+
+		Ok(TrustMeterView {
+			shader,
+			mesh: gfx::Mesh::new(gfx),
+			mesh_data: gfx::MeshData::new(),
+
+			friend_uimesh,
+		})
+	}
+
+	fn update(&mut self, viewer: &model::Viewer<'_>) {
+		self.mesh_data.clear();
+
+		let meter = &viewer.ui.trust_meter;
+
+		if !matches!(meter.state, model::UiPanelState::Closed) {
+			let pos = meter.position().resolve(viewer.ui.metrics);
+
+			self.friend_uimesh.build_into(&mut self.mesh_data, Mat3x4::scale_translate(Vec3::splat(2.0), pos.extend(2.2)));
+
+			let mut mb = gfx::ColorMeshBuilder::new(&mut self.mesh_data);
+			let mut plane = mb.on_plane_ref(ui_plane(pos.extend(2.0)));
+
+			plane.set_color(Color::hsv(40.0, 0.3, 0.4));
+			plane.build(geom::Polygon::from_pos_scale(16, Vec2::zero(), Vec2::splat(3.4)));
+
+			plane.set_color(Color::hsv(45.0, 0.1, 0.9));
+			plane.build(geom::Polygon::from_pos_scale(16, Vec2::zero(), Vec2::splat(3.1)));
+
+			// A ring of segments that light up as trust builds
+			let lit_segments = meter.trust * TRUST_METER_SEGMENTS as f32;
+
+			for segment in 0..TRUST_METER_SEGMENTS {
+				let angle = PI/2.0 - segment as f32 * TAU / TRUST_METER_SEGMENTS as f32;
+				let segment_pos = Vec2::from_angle(angle) * 1.2;
+				let fill = (lit_segments - segment as f32).clamp(0.0, 1.0);
+
+				plane.set_color(Color::grey(0.7));
+				plane.build(geom::Polygon::from_pos_scale(6, segment_pos, Vec2::splat(0.35)));
+
+				if fill > 0.0 {
+					plane.set_color(Color::hsv(330.0, 0.6, 0.9));
+					plane.build(geom::Polygon::from_pos_scale(6, segment_pos, Vec2::splat(0.45 * fill)));
+				}
+			}
+		}
 
 		self.mesh.upload(&self.mesh_data);
 	}
@@ -228,4 +780,752 @@ impl MapView {
 		ctx.gfx.bind_shader(self.shader);
 		self.mesh.draw(&mut ctx.gfx, gfx::DrawMode::Triangles);
 	}
-}
\ No newline at end of file
+}
+
+
+
+
+
+const PICKUP_COUNTER_PIP_SPACING: f32 = 0.55;
+
+struct PickupCounterView {
+	shader: gfx::Shader,
+	mesh: gfx::Mesh<gfx::ColorVertex>,
+	mesh_data: gfx::MeshData<gfx::ColorVertex>,
+}
+
+impl PickupCounterView {
+	fn new(gfx: &mut gfx::Context) -> Result<PickupCounterView> {
+		let shader = gfx.new_simple_shader(shaders::COLOR_3D_VERT, shaders::FLAT_COLOR_FRAG)?;
+
+		Ok(PickupCounterView {
+			shader,
+			mesh: gfx::Mesh::new(gfx),
+			mesh_data: gfx::MeshData::new(),
+		})
+	}
+
+	fn update(&mut self, viewer: &model::Viewer<'_>) {
+		self.mesh_data.clear();
+
+		let counter = &viewer.ui.pickup_counter;
+		let mut mb = gfx::ColorMeshBuilder::new(&mut self.mesh_data);
+
+		let visible_rows = counter.rows.iter()
+			.filter(|row| row.total > 0);
+
+		for (idx, row) in visible_rows.enumerate() {
+			let pos = counter.row_position(idx).resolve(viewer.ui.metrics);
+			let mut plane = mb.on_plane_ref(ui_plane(pos.extend(1.5)));
+
+			let color = pickup_marker_color(row.kind);
+			let icon_size = 1.0 + row.pulse * 0.4;
+
+			plane.set_color(Color::grey(0.2));
+			plane.build(geom::Polygon::from_pos_scale(6, Vec2::zero(), Vec2::splat(icon_size)));
+
+			plane.set_color(color);
+			plane.build(geom::Polygon::from_pos_scale(6, Vec2::zero(), Vec2::splat(icon_size * 0.8)));
+
+			// One pip per pickup of this kind, filled in as they're collected
+			for pip in 0..row.total {
+				let pip_pos = Vec2::new(1.1 + pip as f32 * PICKUP_COUNTER_PIP_SPACING, 0.0);
+
+				plane.set_color(Color::grey(0.6));
+				plane.build(geom::Polygon::from_pos_scale(8, pip_pos, Vec2::splat(0.35)));
+
+				if pip < row.collected {
+					plane.set_color(color);
+					plane.build(geom::Polygon::from_pos_scale(8, pip_pos, Vec2::splat(0.45)));
+				}
+			}
+		}
+
+		self.mesh.upload(&self.mesh_data);
+	}
+
+	fn draw(&self, ctx: &mut view::ViewContext<'_>) {
+		ctx.gfx.bind_shader(self.shader);
+		self.mesh.draw(&mut ctx.gfx, gfx::DrawMode::Triangles);
+	}
+}
+
+
+
+
+
+struct RaceTimerView {
+	shader: gfx::Shader,
+	mesh: gfx::Mesh<gfx::ColorVertex>,
+	mesh_data: gfx::MeshData<gfx::ColorVertex>,
+}
+
+impl RaceTimerView {
+	fn new(gfx: &mut gfx::Context) -> Result<RaceTimerView> {
+		let shader = gfx.new_simple_shader(shaders::COLOR_3D_VERT, shaders::FLAT_COLOR_FRAG)?;
+
+		Ok(RaceTimerView {
+			shader,
+			mesh: gfx::Mesh::new(gfx),
+			mesh_data: gfx::MeshData::new(),
+		})
+	}
+
+	fn update(&mut self, model: &model::Model, viewer: &model::Viewer<'_>) {
+		use model::GameState;
+
+		self.mesh_data.clear();
+
+		let regatta = match &model.regatta {
+			Some(regatta) => regatta,
+			None => {
+				self.mesh.upload(&self.mesh_data);
+				return
+			}
+		};
+
+		let mut mb = gfx::ColorMeshBuilder::new(&mut self.mesh_data);
+
+		let timer_pos = UiPosition::TopRight(Vec2::new(4.5, 4.0)).resolve(viewer.ui.metrics);
+		let mut plane = mb.on_plane_ref(ui_plane(timer_pos.extend(1.5)));
+
+		let timer_color = match model.global.game_state {
+			GameState::RaceFinished(_) | GameState::PostGame => Color::hsv(50.0, 0.9, 1.0),
+			_ => Color::grey(0.95),
+		};
+
+		build_digits(&mut plane, &format_race_time(regatta.timer), Vec2::zero(), 1.2, timer_color);
+
+		if let Some(best_time) = regatta.best_time {
+			build_digits(&mut plane, &format_race_time(best_time), Vec2::from_y(-1.5), 0.7, Color::hsv(200.0, 0.3, 0.9));
+		}
+
+		// One pip per buoy, filled in as they're passed
+		let num_checkpoints = regatta.checkpoints.len();
+		let pip_spacing = 0.5;
+		let pips_start = -(num_checkpoints as f32 - 1.0) * pip_spacing / 2.0;
+
+		for idx in 0..num_checkpoints {
+			let pip_pos = Vec2::new(pips_start + idx as f32 * pip_spacing, -2.6);
+
+			plane.set_color(Color::grey(0.4));
+			plane.build(geom::Polygon::from_pos_scale(8, pip_pos, Vec2::splat(0.35)));
+
+			if idx < regatta.checkpoints_passed {
+				plane.set_color(Color::hsv(20.0, 0.8, 0.9));
+				plane.build(geom::Polygon::from_pos_scale(8, pip_pos, Vec2::splat(0.3)));
+			}
+		}
+
+		if let GameState::RaceCountdown(timer) = model.global.game_state {
+			let center = UiPosition::Center(Vec2::zero()).resolve(viewer.ui.metrics);
+			let mut plane = mb.on_plane_ref(ui_plane(center.extend(2.0)));
+			let pulse = 1.0 + timer.fract() * 0.3;
+
+			build_digits(&mut plane, &format!("{}", timer.ceil() as u32), Vec2::zero(), 3.0 * pulse, Color::hsv(50.0, 0.9, 1.0));
+		}
+
+		self.mesh.upload(&self.mesh_data);
+	}
+
+	fn draw(&self, ctx: &mut view::ViewContext<'_>) {
+		ctx.gfx.bind_shader(self.shader);
+		self.mesh.draw(&mut ctx.gfx, gfx::DrawMode::Triangles);
+	}
+}
+
+
+
+
+
+struct PortPanelView {
+	shader: gfx::Shader,
+	mesh: gfx::Mesh<gfx::ColorVertex>,
+	mesh_data: gfx::MeshData<gfx::ColorVertex>,
+
+	friend_meshes: HashMap<model::FriendName, BasicMesh>,
+	spin_phase: f32,
+}
+
+impl PortPanelView {
+	fn new(gfx: &mut gfx::Context, resources: &model::Resources) -> Result<PortPanelView> {
+		let shader = gfx.new_simple_shader(shaders::COLOR_3D_VERT, shaders::FLAT_COLOR_FRAG)?;
+
+		let friend_scene = resources.main_project.find_scene("friends").unwrap();
+		let friend_meshes = friend_scene.entities_with_prefix("FRIEND_")
+			.map(|entity| (model::FriendName::from_name(&entity.name), BasicMesh::from_entity(entity)))
+			.collect();
+
+		Ok(PortPanelView {
+			shader,
+			mesh: gfx::Mesh::new(gfx),
+			mesh_data: gfx::MeshData::new(),
+
+			friend_meshes,
+			spin_phase: 0.0,
+		})
+	}
+
+	fn update(&mut self, model: &model::Model, viewer: &model::Viewer<'_>) {
+		use model::PortRow;
+
+		self.mesh_data.clear();
+
+		self.spin_phase += 0.3 / 60.0;
+		self.spin_phase %= 1.0;
+
+		let panel = &viewer.ui.port_panel;
+		let phase = panel.state.as_phase();
+		if phase <= 0.0 {
+			self.mesh.upload(&self.mesh_data);
+			return
+		}
+
+		let scale = match panel.state.is_open() {
+			true => phase.ease_back_out(0.0, 1.0),
+			false => phase,
+		};
+
+		let panel_pos = UiPosition::Center(Vec2::zero()).resolve(viewer.ui.metrics);
+		let base_transform = Mat3x4::scale_translate(Vec3::splat(scale), panel_pos.extend(2.5));
+
+		let mut mb = gfx::ColorMeshBuilder::new(&mut self.mesh_data);
+		let mut plane = mb.on_plane_ref(ui_plane(panel_pos.extend(2.5)) * Mat3::from_columns([
+			Vec3::from_x(scale),
+			Vec3::from_y(scale),
+			Vec3::from_z(1.0),
+		]));
+
+		let panel_size = Vec2::new(
+			model::PORT_PANEL_ROW_WIDTH + 1.0,
+			panel.rows.len() as f32 * model::PORT_PANEL_ROW_SPACING + 1.0,
+		);
+
+		plane.set_color(Color::hsv(30.0, 0.4, 0.35));
+		plane.build(geom::Quad::from_matrix(Mat2x3::scale(panel_size)));
+
+		plane.set_color(Color::hsv(40.0, 0.2, 0.9));
+		plane.build(geom::Quad::from_matrix(Mat2x3::scale(panel_size - Vec2::splat(0.5))));
+
+		let mut friend_previews = Vec::new();
+
+		for (row_idx, &row) in panel.rows.iter().enumerate() {
+			let pos = panel.row_position(row_idx);
+			let icon_pos = pos + Vec2::from_x(-model::PORT_PANEL_ROW_WIDTH / 2.0 + 1.5);
+			let value_pos = pos + Vec2::from_x(1.5);
+
+			if row_idx == panel.selected {
+				let row_size = Vec2::new(model::PORT_PANEL_ROW_WIDTH, model::PORT_PANEL_ROW_SPACING * 0.9);
+				plane.set_color(Color::hsv(45.0, 0.4, 1.0));
+				plane.build(geom::Quad::from_matrix(Mat2x3::scale_translate(row_size, pos)));
+			}
+
+			match row {
+				PortRow::Save => {
+					// Lights up for a moment once the game has been saved
+					let saved = (panel.saved_timer / model::PORT_PANEL_SAVED_TIME).clamp(0.0, 1.0);
+					let color = Color::hsv(45.0, 0.2 + saved * 0.6, 0.6 + saved * 0.4);
+					build_star(&mut plane, icon_pos, 0.7 + saved * 0.2, color);
+				}
+
+				PortRow::Paint => {
+					plane.set_color(Color::hsv(210.0, 0.4, 0.4));
+					plane.build(geom::Quad::from_matrix(Mat2x3::scale_translate(Vec2::new(1.2, 0.35), icon_pos - Vec2::from_y(0.3))));
+					plane.build(geom::Polygon::from_pos_scale(3, icon_pos + Vec2::new(0.1, 0.25), Vec2::splat(0.7)));
+
+					// Each boat has its own paint, so show this panel's boat rather than the setting
+					let count = model.settings.num_boat_paints();
+					let spacing = 1.2;
+					let start = value_pos - Vec2::from_x(spacing * (count as f32 - 1.0) / 2.0);
+
+					for option in 0..count {
+						let (size, color) = match option == viewer.player.paint as usize {
+							true => (0.8, Color::hsv(200.0, 0.7, 0.8)),
+							false => (0.4, Color::hsv(200.0, 0.2, 0.6)),
+						};
+
+						plane.set_color(color);
+						plane.build(geom::Polygon::from_pos_scale(8, start + Vec2::from_x(spacing * option as f32), Vec2::splat(size)));
+					}
+				}
+
+				PortRow::Friend{friend, keep} => {
+					if let Some(friend) = model.world.friends.get(friend) {
+						friend_previews.push((friend.name, icon_pos));
+					}
+
+					// Staying aboard, or waving goodbye
+					match keep {
+						true => {
+							plane.set_color(Color::hsv(120.0, 0.6, 0.7));
+							plane.build(geom::Polygon::from_pos_scale(12, value_pos, Vec2::splat(0.9)));
+						}
+
+						false => {
+							plane.set_color(Color::hsv(0.0, 0.7, 0.8));
+							for angle in [PI/4.0, -PI/4.0] {
+								plane.build(geom::Quad::from_matrix(Mat2x3::rotate_translate(angle, value_pos) * Mat2x3::scale(Vec2::new(1.0, 0.25))));
+							}
+						}
+					}
+				}
+
+				PortRow::Leave => {
+					plane.set_color(Color::hsv(120.0, 0.6, 0.7));
+					plane.build(geom::Polygon::from_pos_scale(3, icon_pos, Vec2::splat(0.9)));
+				}
+			}
+		}
+
+		for (name, pos) in friend_previews {
+			if let Some(friend_mesh) = self.friend_meshes.get(&name) {
+				let preview_scale = 0.8 / friend_mesh.radius().max(0.01);
+
+				let transform = base_transform
+					* Mat3x4::rotate_x_translate(PI/8.0, pos.extend(1.0))
+					* Mat3x4::rotate_y(self.spin_phase * TAU)
+					* Mat3x4::uniform_scale(preview_scale);
+
+				friend_mesh.build_into(&mut self.mesh_data, transform);
+			}
+		}
+
+		self.mesh.upload(&self.mesh_data);
+	}
+
+	fn draw(&self, ctx: &mut view::ViewContext<'_>) {
+		ctx.gfx.bind_shader(self.shader);
+		self.mesh.draw(&mut ctx.gfx, gfx::DrawMode::Triangles);
+	}
+}
+
+
+
+
+
+/// The speedometer needle sweeps clockwise through this angle, starting from the bottom left
+const SPEEDOMETER_SWEEP: f32 = PI * 1.5;
+const SPEEDOMETER_TICKS: usize = 7;
+
+struct InstrumentsView {
+	shader: gfx::Shader,
+	mesh: gfx::Mesh<gfx::ColorVertex>,
+	mesh_data: gfx::MeshData<gfx::ColorVertex>,
+}
+
+impl InstrumentsView {
+	fn new(gfx: &mut gfx::Context) -> Result<InstrumentsView> {
+		let shader = gfx.new_simple_shader(shaders::COLOR_3D_VERT, shaders::FLAT_COLOR_FRAG)?;
+
+		Ok(InstrumentsView {
+			shader,
+			mesh: gfx::Mesh::new(gfx),
+			mesh_data: gfx::MeshData::new(),
+		})
+	}
+
+	fn update(&mut self, model: &model::Model, viewer: &model::Viewer<'_>) {
+		self.mesh_data.clear();
+
+		let instruments = &viewer.ui.instruments;
+
+		if !matches!(instruments.state, model::UiPanelState::Closed) {
+			let mut mb = gfx::ColorMeshBuilder::new(&mut self.mesh_data);
+
+			let sail_pos = instruments.sail_gauge_position().resolve(viewer.ui.metrics);
+			build_sail_gauge(&mut mb.on_plane_ref(ui_plane(sail_pos.extend(1.5))), model.tunables.max_sail_speed, viewer.player.sail_state, instruments.sail_level);
+
+			let speedometer_pos = instruments.speedometer_position().resolve(viewer.ui.metrics);
+			build_speedometer(&mut mb.on_plane_ref(ui_plane(speedometer_pos.extend(1.5))), instruments.speed);
+
+			let compass_pos = instruments.compass_position().resolve(viewer.ui.metrics);
+			build_compass(&mut mb.on_plane_ref(ui_plane(compass_pos.extend(1.5))), instruments.heading);
+		}
+
+		self.mesh.upload(&self.mesh_data);
+	}
+
+	fn draw(&self, ctx: &mut view::ViewContext<'_>) {
+		ctx.gfx.bind_shader(self.shader);
+		self.mesh.draw(&mut ctx.gfx, gfx::DrawMode::Triangles);
+	}
+}
+
+
+/// A column of notches, one per sail setting, lit up to the current one - with a marker that slides between them
+fn build_sail_gauge(plane: &mut (impl PolyBuilder2D + ColoredPolyBuilder), max_sail_speed: i32, sail_state: model::SailState, sail_level: f32) {
+	let num_notches = max_sail_speed.max(1) as usize;
+	let notch_spacing = 0.5;
+	let height = num_notches as f32 * notch_spacing;
+
+	plane.set_color(Color::hsv(40.0, 0.3, 0.4));
+	plane.build(geom::Quad::from_matrix(Mat2x3::scale(Vec2::new(1.1, height + 0.4))));
+
+	plane.set_color(Color::hsv(45.0, 0.1, 0.9));
+	plane.build(geom::Quad::from_matrix(Mat2x3::scale(Vec2::new(0.9, height + 0.2))));
+
+	for notch in 0..num_notches {
+		let notch_pos = Vec2::new(0.1, (notch as f32 + 0.5) * notch_spacing - height / 2.0);
+
+		let color = match notch < sail_state.speed() as usize {
+			true => Color::hsv(200.0, 0.7, 0.8),
+			false => Color::grey(0.7),
+		};
+
+		plane.set_color(color);
+		plane.build(geom::Quad::from_matrix(Mat2x3::scale_translate(Vec2::new(0.5, notch_spacing * 0.7), notch_pos)));
+	}
+
+	let marker_pos = Vec2::new(-0.3, sail_level * height - height / 2.0);
+	plane.set_color(Color::hsv(0.0, 0.7, 0.8));
+	plane.build(geom::Polygon::from_pos_scale(3, marker_pos, Vec2::splat(0.25)));
+}
+
+
+fn build_speedometer(plane: &mut (impl PolyBuilder2D + ColoredPolyBuilder), speed: f32) {
+	let start_angle = PI + (SPEEDOMETER_SWEEP - PI) / 2.0;
+
+	plane.set_color(Color::hsv(40.0, 0.3, 0.4));
+	plane.build(geom::Polygon::from_pos_scale(24, Vec2::zero(), Vec2::splat(1.4)));
+
+	plane.set_color(Color::hsv(45.0, 0.1, 0.9));
+	plane.build(geom::Polygon::from_pos_scale(24, Vec2::zero(), Vec2::splat(1.25)));
+
+	plane.set_color(Color::grey(0.5));
+
+	for tick in 0..SPEEDOMETER_TICKS {
+		let angle = start_angle - tick as f32 / (SPEEDOMETER_TICKS - 1) as f32 * SPEEDOMETER_SWEEP;
+		plane.build(geom::Quad::from_matrix(Mat2x3::rotate_translate(angle, Vec2::zero()) * Mat2x3::scale_translate(Vec2::new(0.25, 0.08), Vec2::from_x(0.95))));
+	}
+
+	let ratio = (speed / model::SPEEDOMETER_MAX).clamp(0.0, 1.0);
+	let needle_angle = start_angle - ratio * SPEEDOMETER_SWEEP;
+
+	plane.set_color(Color::hsv(0.0, 0.7, 0.8));
+	plane.build(geom::Quad::from_matrix(Mat2x3::rotate_translate(needle_angle, Vec2::zero()) * Mat2x3::scale_translate(Vec2::new(1.0, 0.1), Vec2::from_x(0.45))));
+
+	plane.set_color(Color::hsv(210.0, 0.4, 0.4));
+	plane.build(geom::Polygon::from_pos_scale(8, Vec2::zero(), Vec2::splat(0.2)));
+}
+
+
+/// North is always up - the needle shows which way the boat is pointing on the chart
+fn build_compass(plane: &mut (impl PolyBuilder2D + ColoredPolyBuilder), heading: f32) {
+	plane.set_color(Color::hsv(40.0, 0.3, 0.4));
+	plane.build(geom::Polygon::from_pos_scale(24, Vec2::zero(), Vec2::splat(1.4)));
+
+	plane.set_color(Color::hsv(45.0, 0.1, 0.9));
+	plane.build(geom::Polygon::from_pos_scale(24, Vec2::zero(), Vec2::splat(1.25)));
+
+	for quarter in 0..4 {
+		let direction = Vec2::from_angle(PI/2.0 - quarter as f32 * PI/2.0);
+
+		// North gets a bigger, red marker
+		let (color, size) = match quarter {
+			0 => (Color::hsv(0.0, 0.7, 0.8), 0.25),
+			_ => (Color::grey(0.5), 0.15),
+		};
+
+		plane.set_color(color);
+		plane.build(geom::Polygon::from_pos_scale(3, direction * 0.95, Vec2::splat(size)));
+	}
+
+	let needle_dir = Vec2::from_angle(heading);
+	plane.set_color(Color::hsv(210.0, 0.6, 0.5));
+	plane.extend_2d_fan(3, [
+		needle_dir * 0.75,
+		needle_dir.perp() * 0.12,
+		-needle_dir.perp() * 0.12,
+	]);
+
+	plane.set_color(Color::grey(0.6));
+	plane.extend_2d_fan(3, [
+		-needle_dir * 0.5,
+		-needle_dir.perp() * 0.12,
+		needle_dir.perp() * 0.12,
+	]);
+}
+
+
+
+
+
+/// Spacing of the rows on the stats screen, and how big their text is, in ui units
+const STATS_ROW_SPACING: f32 = 1.1;
+const STATS_FRIEND_ROW_SPACING: f32 = 0.9;
+const STATS_TEXT_SIZE: f32 = 0.5;
+
+struct StatsScreenView {
+	shader: gfx::Shader,
+	mesh: gfx::Mesh<gfx::ColorVertex>,
+	mesh_data: gfx::MeshData<gfx::ColorVertex>,
+	text: view::TextView,
+}
+
+impl StatsScreenView {
+	fn new(gfx: &mut gfx::Context, font: &gfx::FontAtlas) -> Result<StatsScreenView> {
+		let shader = gfx.new_simple_shader(shaders::COLOR_3D_VERT, shaders::FLAT_COLOR_FRAG)?;
+
+		Ok(StatsScreenView {
+			shader,
+			mesh: gfx::Mesh::new(gfx),
+			mesh_data: gfx::MeshData::new(),
+			text: view::TextView::new(gfx, font)?,
+		})
+	}
+
+	fn update(&mut self, model: &model::Model, viewer: &model::Viewer<'_>) {
+		self.mesh_data.clear();
+		self.text.clear();
+
+		let phase = model.ui.stats_screen.state.as_phase();
+		if phase <= 0.0 {
+			self.mesh.upload(&self.mesh_data);
+			self.text.upload();
+			return
+		}
+
+		let stats = &model.stats;
+		let session = &stats.session;
+		let num_friends = model.world.friends.len();
+		let max_speed = model.tunables.max_sail_speed as f32 * model.tunables.speed_per_sail;
+
+		// Bars show session values as a share of lifetime totals, or of the maximum possible where that makes more sense
+		let rows = [
+			("distance sailed", 200.0, session_ratio(stats, StatKey::DistanceSailed), format!("{:.0}", session.distance_sailed)),
+			("top speed", 30.0, session.top_speed / max_speed, format!("{:.1}", session.top_speed)),
+			("playtime", 100.0, session_ratio(stats, StatKey::Playtime), format_race_time(session.playtime)),
+			("map wraps", 260.0, session_ratio(stats, StatKey::MapWraps), session.map_wraps.to_string()),
+			("friends met", 330.0, session.value(StatKey::FriendsMet) / num_friends.max(1) as f32, format!("{} / {}", session.time_to_meet.len(), num_friends)),
+			("tricks seen", 160.0, session_ratio(stats, StatKey::TricksSeen), session.tricks_seen.values().sum::<u32>().to_string()),
+		];
+
+		let center = UiPosition::Center(Vec2::zero()).resolve(viewer.ui.metrics);
+		let scale = phase.ease_back_out(0.0, 1.0);
+
+		let mut mb = gfx::ColorMeshBuilder::new(&mut self.mesh_data);
+		let mut plane = mb.on_plane_ref(ui_plane(center.extend(3.0)) * Mat3::from_columns([
+			Vec3::from_x(scale),
+			Vec3::from_y(scale),
+			Vec3::from_z(1.0),
+		]));
+
+		// Text is scaled along with the panel, just in front of it
+		let text_transform = |pos: Vec2| Mat3x4::translate(center.extend(3.1)) * Mat3x4::uniform_scale(scale) * Mat3x4::translate(pos.extend(0.0));
+		let text_color = Color::hsv(200.0, 0.5, 0.2);
+
+		let label_style = gfx::TextStyle::new(STATS_TEXT_SIZE)
+			.with_color(text_color)
+			.aligned(gfx::TextAlign::Left, gfx::VerticalAlign::Middle);
+
+		let value_style = gfx::TextStyle::new(STATS_TEXT_SIZE)
+			.with_color(text_color)
+			.aligned(gfx::TextAlign::Right, gfx::VerticalAlign::Middle);
+
+		plane.set_color(Color::hsv(200.0, 0.4, 0.3));
+		plane.build(geom::Quad::from_matrix(Mat2x3::scale(Vec2::new(16.0, 14.0))));
+
+		plane.set_color(Color::hsv(200.0, 0.2, 0.9));
+		plane.build(geom::Quad::from_matrix(Mat2x3::scale(Vec2::new(15.5, 13.5))));
+
+		let bar_width = 10.0;
+		let bar_left = 0.5 - bar_width/2.0;
+		let bar_right = 0.5 + bar_width/2.0;
+		let text_inset = 0.3;
+		let rows_top = 5.8;
+
+		for (row, (label, hue, ratio, value)) in rows.iter().enumerate() {
+			let y = rows_top - row as f32 * STATS_ROW_SPACING;
+			let fill = ratio.clamp(0.0, 1.0) * phase;
+
+			plane.set_color(Color::hsv(*hue, 0.7, 0.8));
+			plane.build(geom::Polygon::from_pos_scale(6, Vec2::new(-6.5, y), Vec2::splat(0.9)));
+
+			plane.set_color(Color::hsv(*hue, 0.2, 0.6));
+			plane.build(geom::Quad::from_matrix(Mat2x3::scale_translate(Vec2::new(bar_width, 0.8), Vec2::new(0.5, y))));
+
+			let fill_width = bar_width * fill;
+			let fill_center = bar_left + fill_width/2.0;
+
+			plane.set_color(Color::hsv(*hue, 0.7, 0.9));
+			plane.build(geom::Quad::from_matrix(Mat2x3::scale_translate(Vec2::new(fill_width, 0.8), Vec2::new(fill_center, y))));
+
+			self.text.add(label, &label_style, text_transform(Vec2::new(bar_left + text_inset, y)));
+			self.text.add(value, &value_style, text_transform(Vec2::new(bar_right - text_inset, y)));
+		}
+
+		// How long each friend on this map took to meet this session, against the best time ever
+		let mut friend_names: Vec<model::FriendName> = Vec::new();
+		for friend in model.world.friends.iter() {
+			if !friend_names.contains(&friend.name) {
+				friend_names.push(friend.name);
+			}
+		}
+
+		let friends_top = rows_top - rows.len() as f32 * STATS_ROW_SPACING;
+
+		for (row, name) in friend_names.iter().enumerate() {
+			let y = friends_top - row as f32 * STATS_FRIEND_ROW_SPACING;
+
+			let time = match (session.time_to_meet.get(name), stats.lifetime.time_to_meet.get(name)) {
+				(Some(&time), Some(&best)) => format!("met after {}, best {}", format_race_time(time), format_race_time(best)),
+				(Some(&time), None) => format!("met after {}", format_race_time(time)),
+				(None, Some(&best)) => format!("not met, best {}", format_race_time(best)),
+				(None, None) => "not met".to_owned(),
+			};
+
+			let label = name.as_str().replace('_', " ");
+
+			self.text.add(&label, &label_style, text_transform(Vec2::new(-6.9, y)));
+			self.text.add(&time, &value_style, text_transform(Vec2::new(bar_right - text_inset, y)));
+		}
+
+		let num_achievements = stats.achievements.len();
+		let star_spacing = 1.5;
+		let stars_start = -(num_achievements as f32 - 1.0) * star_spacing / 2.0;
+
+		for (idx, achievement) in stats.achievements.iter().enumerate() {
+			let pos = Vec2::new(stars_start + idx as f32 * star_spacing, -rows_top);
+			let color = match achievement.unlocked {
+				true => Color::hsv(45.0, 0.8, 1.0),
+				false => Color::grey(0.6),
+			};
+
+			build_star(&mut plane, pos, 0.6 * phase, color);
+		}
+
+		self.mesh.upload(&self.mesh_data);
+		self.text.upload();
+	}
+
+	fn draw(&self, ctx: &mut view::ViewContext<'_>) {
+		ctx.gfx.bind_shader(self.shader);
+		self.mesh.draw(&mut ctx.gfx, gfx::DrawMode::Triangles);
+
+		self.text.draw(ctx);
+	}
+}
+
+
+/// The session's share of the lifetime total, which includes it
+fn session_ratio(stats: &model::Stats, stat: StatKey) -> f32 {
+	let lifetime = stats.lifetime.value(stat);
+	if lifetime > 0.0 {
+		stats.session.value(stat) / lifetime
+	} else {
+		0.0
+	}
+}
+
+
+
+/// A closed book, for the journal button
+fn build_journal_icon() -> BasicMesh {
+	let mut mesh_data = gfx::MeshData::new();
+
+	{
+		let mut mb = gfx::ColorMeshBuilder::new(&mut mesh_data);
+		let mut plane = mb.on_plane_ref(ui_plane(Vec3::zero()));
+
+		plane.set_color(Color::hsv(10.0, 0.6, 0.5));
+		plane.build(geom::Quad::from_matrix(Mat2x3::scale(Vec2::new(1.4, 1.8))));
+
+		plane.set_color(Color::hsv(45.0, 0.1, 0.95));
+		plane.build(geom::Quad::from_matrix(Mat2x3::scale_translate(Vec2::new(0.2, 1.6), Vec2::from_x(0.6))));
+
+		build_star(&mut plane, Vec2::new(0.05, 0.1), 0.4, Color::hsv(45.0, 0.8, 1.0));
+	}
+
+	BasicMesh::from_mesh_data(&mesh_data)
+}
+
+
+fn format_race_time(seconds: f32) -> String {
+	let tenths = (seconds * 10.0) as u32;
+	format!("{}:{:02}.{}", tenths / 600, tenths / 10 % 60, tenths % 10)
+}
+
+
+/// Seven segment style digits, centered on `center`. Only digits, ':' and '.' are drawn - anything else is skipped
+fn build_digits(mb: &mut (impl PolyBuilder2D + ColoredPolyBuilder), text: &str, center: Vec2, height: f32, color: Color) {
+	const SEGMENTS: [u8; 10] = [
+		0b0111111, 0b0000110, 0b1011011, 0b1001111, 0b1100110,
+		0b1101101, 0b1111101, 0b0000111, 0b1111111, 0b1101111,
+	];
+
+	let digit_width = height * 0.5;
+	let thickness = height * 0.12;
+	let digit_advance = digit_width + height * 0.3;
+	let mark_advance = height * 0.3;
+
+	let advance = |c: char| if c.is_ascii_digit() { digit_advance } else { mark_advance };
+	let total_width = text.chars().map(advance).sum::<f32>() - height * 0.3;
+
+	let (hw, hh) = (digit_width / 2.0, height / 2.0);
+
+	// Each segment as (center, size), in the order of the bits in `SEGMENTS`
+	let segment_quads = [
+		(Vec2::new(0.0, hh), Vec2::new(digit_width, thickness)),
+		(Vec2::new(hw, hh/2.0), Vec2::new(thickness, hh)),
+		(Vec2::new(hw, -hh/2.0), Vec2::new(thickness, hh)),
+		(Vec2::new(0.0, -hh), Vec2::new(digit_width, thickness)),
+		(Vec2::new(-hw, -hh/2.0), Vec2::new(thickness, hh)),
+		(Vec2::new(-hw, hh/2.0), Vec2::new(thickness, hh)),
+		(Vec2::new(0.0, 0.0), Vec2::new(digit_width, thickness)),
+	];
+
+	mb.set_color(color);
+
+	let mut x = center.x - total_width / 2.0;
+
+	for c in text.chars() {
+		match c {
+			'0'..='9' => {
+				let segments = SEGMENTS[c as usize - '0' as usize];
+				let digit_center = Vec2::new(x + hw, center.y);
+
+				for (bit, &(offset, size)) in segment_quads.iter().enumerate() {
+					if segments & (1 << bit) != 0 {
+						mb.build(geom::Quad::from_matrix(Mat2x3::scale_translate(size, digit_center + offset)));
+					}
+				}
+			}
+
+			':' => {
+				for y in [-hh/2.0, hh/2.0] {
+					mb.build(geom::Quad::from_matrix(Mat2x3::scale_translate(Vec2::splat(thickness), Vec2::new(x + thickness, center.y + y))));
+				}
+			}
+
+			'.' => {
+				mb.build(geom::Quad::from_matrix(Mat2x3::scale_translate(Vec2::splat(thickness), Vec2::new(x + thickness, center.y - hh))));
+			}
+
+			_ => {}
+		}
+
+		x += advance(c);
+	}
+}
+
+
+/// Maps 2D ui geometry onto the plane at z = `origin.z`, centered on `origin`
+pub(super) fn ui_plane(origin: Vec3) -> Mat3 {
+	Mat3::from_columns([
+		Vec3::from_x(1.0),
+		Vec3::from_y(1.0),
+		origin,
+	])
+}
+
+pub(super) fn build_star(mb: &mut (impl PolyBuilder2D + ColoredPolyBuilder), center: Vec2, radius: f32, color: Color) {
+	let points = (0..10)
+		.map(|i| {
+			let angle = PI/2.0 + i as f32 * TAU / 10.0;
+			let point_radius = if i % 2 == 0 { radius } else { radius * 0.45 };
+			center + Vec2::from_angle(angle) * point_radius
+		});
+
+	mb.set_color(color);
+	mb.extend_2d_fan(12, std::iter::once(center).chain(points).chain(std::iter::once(center + Vec2::from_y(radius))));
+}
@@ -67,7 +67,7 @@ impl WaterView {
 		})
 	}
 
-	pub fn update(&mut self, model: &model::Model) {
+	pub fn update(&mut self, model: &model::Model, viewer: &model::Viewer<'_>) {
 		let mut instances = [
 			Vec2::new(-28.0,-28.0),
 			Vec2::new(-22.0, 0.0),
@@ -82,9 +82,9 @@ impl WaterView {
 			Vec2::new(29.0, 27.0),
 		];
 
-		let player_pos_world = model::map_to_world(model.player.map_position);
+		let player_pos_world = model::map_to_world(viewer.player.map_position);
 
-		let max_diff = 47.0;
+		let max_diff = model.tunables.ripple_wrap_distance;
 
 		for instance in instances.iter_mut() {
 			let diff = *instance - player_pos_world;
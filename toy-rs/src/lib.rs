@@ -8,4 +8,7 @@ pub use self::importer::*;
 
 pub const DEFAULT_COLOR_DATA_NAME: &'static str = "Col";
 
+/// Alternative color layers are named with this prefix followed by the variant name, e.g. `Col_night`
+pub const COLOR_VARIANT_PREFIX: &'static str = "Col_";
+
 pub type ToyResult<T> = Result<T, failure::Error>;
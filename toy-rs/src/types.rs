@@ -129,6 +129,21 @@ impl MeshData {
 		self.color_data.iter()
 			.find(|l| l.name == name)
 	}
+
+	/// The names of any alternative color layers, with the variant prefix stripped - `Col_red` is listed as `red`
+	pub fn color_variants(&self) -> impl Iterator<Item=&str> {
+		self.color_data.iter()
+			.filter_map(|l| l.name.strip_prefix(crate::COLOR_VARIANT_PREFIX))
+	}
+
+	/// The color layer for `variant`, falling back to the default layer if the mesh doesn't have it
+	pub fn color_variant_data<'s>(&self, variant: impl Into<Option<&'s str>>) -> Option<&MeshColorData> {
+		let variant_data = variant.into()
+			.map(|variant| format!("{}{}", crate::COLOR_VARIANT_PREFIX, variant))
+			.and_then(|name| self.color_data(name.as_str()));
+
+		variant_data.or_else(|| self.color_data(None))
+	}
 }
 
 impl<'t> SceneRef<'t> {
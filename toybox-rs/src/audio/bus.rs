@@ -28,6 +28,10 @@ struct SoundInstance {
 	asset_id: SoundAssetID,
 	position: usize,
 	playing: bool,
+
+	gain: f32,
+	/// -1 is fully left, 1 is fully right
+	pan: f32,
 }
 
 
@@ -69,6 +73,9 @@ impl Bus {
 			asset_id,
 			position: 0,
 			playing: true,
+
+			gain: 1.0,
+			pan: 0.0,
 		});
 
 		instance_id
@@ -85,6 +92,15 @@ impl Bus {
 			instance.playing = playing;
 		}
 	}
+
+	pub fn set_gain_pan(&mut self, instance_id: SoundInstanceID, gain: f32, pan: f32) {
+		if let Some(instance) = self.active_sounds.iter_mut()
+			.find(|s| s.instance_id == instance_id)
+		{
+			instance.gain = gain;
+			instance.pan = pan.clamp(-1.0, 1.0);
+		}
+	}
 }
 
 
@@ -128,21 +144,27 @@ impl Bus {
 		self.mixer.clear();
 
 		// Mix each sound into the mix buffer
-		for SoundInstance {asset_id, position, playing, ..} in self.active_sounds.iter_mut() {
+		for SoundInstance {asset_id, position, playing, gain, pan, ..} in self.active_sounds.iter_mut() {
 			if !*playing {
 				continue
 			}
 
+			// Panning only ever attenuates the far side, so centered sounds play at their full gain
+			let channel_gains = [
+				*gain * (1.0 - *pan).min(1.0),
+				*gain * (1.0 + *pan).min(1.0),
+			];
+
 			match asset_id.ty {
 				SoundAssetType::Buffer => {
 					let buffer = &assets.buffers[asset_id.index];
-					let buffer_consumption = self.mixer.mix_buffer(buffer, *position);
+					let buffer_consumption = self.mixer.mix_buffer(buffer, *position, channel_gains);
 					*position += buffer_consumption;
 				}
 
 				SoundAssetType::FileStream => {
 					let stream = &assets.streams[asset_id.index];
-					let buffer_consumption = self.mixer.mix_buffer(&stream.resident_buffer, *position);
+					let buffer_consumption = self.mixer.mix_buffer(&stream.resident_buffer, *position, channel_gains);
 					*position += buffer_consumption;
 
 					// If the stream is running low on samples, queue it for update
@@ -32,19 +32,22 @@ impl Mixer {
 
 	pub fn gain(&self) -> f32 { self.gain }
 
-	pub fn mix_buffer(&mut self, buffer: &Buffer, position: usize) -> usize {
+	/// Mixes `buffer` in from `position`, with `channel_gains` applied to the left and right channels on top of the mixer gain
+	pub fn mix_buffer(&mut self, buffer: &Buffer, position: usize, channel_gains: [f32; 2]) -> usize {
 		let buffer_samples = buffer.data.len() / buffer.channels;
 		let buffer_remaining = buffer_samples - position;
 		let buffer_consumption = buffer_remaining.min(self.mix_buffer.len() / 2);
 
+		let mixer_gain = self.gain;
+		let [left_gain, right_gain] = channel_gains.map(|gain| gain * mixer_gain);
 		let mix_chunks = self.mix_buffer.array_chunks_mut::<2>();
 
 		match buffer.channels {
 			1 => {
 				for ([left, right], sample) in mix_chunks.zip(&buffer.data[position..]) {
-					let sample = i16_to_f32(*sample) * self.gain;
-					*left += sample;
-					*right += sample;
+					let sample = i16_to_f32(*sample);
+					*left += sample * left_gain;
+					*right += sample * right_gain;
 				}
 			}
 
@@ -52,8 +55,8 @@ impl Mixer {
 				let buffer_chunks = buffer.data[position*2..].array_chunks::<2>();
 
 				for ([mix_left, mix_right], [buf_left, buf_right]) in mix_chunks.zip(buffer_chunks) {
-					*mix_left += i16_to_f32(*buf_left) * self.gain;
-					*mix_right += i16_to_f32(*buf_right) * self.gain;
+					*mix_left += i16_to_f32(*buf_left) * left_gain;
+					*mix_right += i16_to_f32(*buf_right) * right_gain;
 				}
 			}
 
@@ -159,6 +159,13 @@ impl AudioSystem {
 		}
 	}
 
+	/// Sets a playing sound's own gain, and where it sits between the left (-1) and right (1) speakers
+	pub fn set_gain_pan(&mut self, instance_id: SoundInstanceID, gain: f32, pan: f32) {
+		if let Some(bus) = self.get_bus_mut(instance_id.bus_id) {
+			bus.set_gain_pan(instance_id, gain, pan)
+		}
+	}
+
 
 
 	pub fn update(&mut self) {
@@ -15,6 +15,8 @@ pub mod shader;
 pub mod query;
 pub mod capabilities;
 pub mod mesh;
+pub mod text;
+pub mod sprite_batch;
 
 pub use self::context::*;
 pub use self::resources::*;
@@ -27,6 +29,8 @@ pub use self::shader::*;
 pub use self::query::*;
 pub use self::capabilities::*;
 pub use self::mesh::*;
+pub use self::text::*;
+pub use self::sprite_batch::*;
 
 pub enum DrawMode {
 	Points,
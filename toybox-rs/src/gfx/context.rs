@@ -102,6 +102,10 @@ impl Context {
 		self.resources.insert_texture(texture)
 	}
 
+	pub fn get_texture_mut(&mut self, texture: TextureKey) -> ResourceLockMut<Texture> {
+		self.resources.get_mut(texture)
+	}
+
 	pub fn new_framebuffer(&mut self, settings: FramebufferSettings) -> FramebufferKey {
 		let framebuffer = Framebuffer::new(settings, &mut self.resources, self.backbuffer_size);
 		self.resources.insert_framebuffer(framebuffer)
@@ -257,19 +261,25 @@ impl<'ctx> RenderState<'ctx> {
 	}
 
 	pub fn bind_framebuffer(&mut self, framebuffer: impl Into<Option<FramebufferKey>>) {
+		self.bind_framebuffer_viewport(framebuffer, Viewport::full());
+	}
+
+	/// Binds `framebuffer` (or the backbuffer if None), restricting rendering to `viewport` within it
+	pub fn bind_framebuffer_viewport(&mut self, framebuffer: impl Into<Option<FramebufferKey>>, viewport: Viewport) {
 		if let Some(framebuffer) = framebuffer.into() {
 			let framebuffer = framebuffer.get(self.resources);
-			let Vec2i{x,y} = framebuffer.size_mode.resolve(self.backbuffer_size);
+			let size = framebuffer.size_mode.resolve(self.backbuffer_size);
+			let (Vec2i{x, y}, Vec2i{x: w, y: h}) = viewport.to_pixels(size);
 
 			unsafe {
-				raw::Viewport(0, 0, x, y);
+				raw::Viewport(x, y, w, h);
 				raw::BindFramebuffer(raw::DRAW_FRAMEBUFFER, framebuffer.handle);
 			}
 		} else {
-			let Vec2i{x,y} = self.backbuffer_size;
+			let (Vec2i{x, y}, Vec2i{x: w, y: h}) = viewport.to_pixels(self.backbuffer_size);
 
 			unsafe {
-				raw::Viewport(0, 0, x, y);
+				raw::Viewport(x, y, w, h);
 				raw::BindFramebuffer(raw::DRAW_FRAMEBUFFER, 0);
 			}
 		}
@@ -321,6 +331,48 @@ impl<'ctx> RenderState<'ctx> {
 
 
 
+/// A region of a render target, in normalised [0, 1] coordinates so that it resolves correctly
+/// against both the backbuffer and framebuffers of any size.
+#[derive(Copy, Clone, Debug)]
+pub struct Viewport {
+	pub min: Vec2,
+	pub max: Vec2,
+}
+
+impl Viewport {
+	pub fn new(min: Vec2, max: Vec2) -> Viewport {
+		Viewport { min, max }
+	}
+
+	pub fn full() -> Viewport {
+		Viewport::new(Vec2::zero(), Vec2::splat(1.0))
+	}
+
+	/// The `index`th of `count` equal width columns, from left to right
+	pub fn column(index: usize, count: usize) -> Viewport {
+		let width = 1.0 / count as f32;
+		let left = index as f32 * width;
+
+		Viewport::new(Vec2::new(left, 0.0), Vec2::new(left + width, 1.0))
+	}
+
+	pub fn aspect(&self, target_size: Vec2i) -> f32 {
+		let (_, Vec2i{x, y}) = self.to_pixels(target_size);
+		x as f32 / y.max(1) as f32
+	}
+
+	/// Returns the position and size in pixels of this viewport within a target of `target_size`
+	pub fn to_pixels(&self, target_size: Vec2i) -> (Vec2i, Vec2i) {
+		let target_size = target_size.to_vec2();
+		let min = (self.min * target_size).to_vec2i();
+		let max = (self.max * target_size).to_vec2i();
+
+		(min, max - min)
+	}
+}
+
+
+
 extern "system" fn gl_message_callback(source: u32, ty: u32, _id: u32, severity: u32,
 	_length: i32, msg: *const i8, _ud: *mut std::ffi::c_void)
 {
@@ -0,0 +1,465 @@
+use crate::prelude::*;
+use crate::gfx::mesh::{ColoredPolyBuilder, PolyBuilder2D};
+
+/// Each sprite is pushed this far in front of the one before it once sorted, so that later layers win the depth test
+pub const SPRITE_DEPTH_STEP: f32 = 1.0 / 1024.0;
+
+const ATLAS_MIN_WIDTH: usize = 256;
+
+/// Each image is surrounded by a copy of its own edge pixels, so linear filtering never picks up its neighbours
+const ATLAS_IMAGE_EXTRUSION: usize = 1;
+
+/// Indices are u16, so any more vertices than this and the batch has to be split across draw calls
+const MAX_VERTICES_PER_DRAW: usize = u16::MAX as usize + 1;
+
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SpriteId(usize);
+
+
+/// Where an image ended up in a `SpriteAtlas`
+#[derive(Copy, Clone, Debug)]
+pub struct SpriteRegion {
+	pub uv_min: Vec2,
+	pub uv_max: Vec2,
+
+	/// In pixels
+	pub size: Vec2i,
+}
+
+
+/// Collects rgba8 images to be packed together into a single texture
+#[derive(Debug, Default)]
+pub struct SpriteAtlasBuilder {
+	images: Vec<(Vec2i, Vec<u8>)>,
+}
+
+impl SpriteAtlasBuilder {
+	pub fn new() -> SpriteAtlasBuilder {
+		SpriteAtlasBuilder::default()
+	}
+
+	/// `data` is tightly packed rows of rgba, top row first
+	pub fn add_rgba(&mut self, size: Vec2i, data: Vec<u8>) -> SpriteId {
+		assert!(size.x > 0 && size.y > 0, "Sprite images can't be empty");
+		assert_eq!(data.len(), (size.x * size.y * 4) as usize, "Sprite image data doesn't match its size");
+
+		let id = SpriteId(self.images.len());
+		self.images.push((size, data));
+		id
+	}
+
+	/// Packs every image onto shelves, left to right then top to bottom.
+	/// A small white image is always added too, so untextured quads can come from the same texture.
+	pub fn build(mut self, gfx: &mut gfx::Context) -> SpriteAtlas {
+		let white = self.add_rgba(Vec2i::splat(2), vec![255; 2 * 2 * 4]);
+
+		let border = ATLAS_IMAGE_EXTRUSION * 2;
+		let widest_image = self.images.iter().map(|(size, _)| size.x as usize + border).max().unwrap_or(0);
+		let atlas_width = widest_image.max(ATLAS_MIN_WIDTH).next_power_of_two();
+
+		let mut placements = Vec::with_capacity(self.images.len());
+		let mut pen = (0, 0);
+		let mut shelf_height = 0;
+
+		for (size, _) in self.images.iter() {
+			let padded_width = size.x as usize + border;
+
+			if pen.0 + padded_width > atlas_width {
+				pen = (0, pen.1 + shelf_height);
+				shelf_height = 0;
+			}
+
+			placements.push((pen.0 + ATLAS_IMAGE_EXTRUSION, pen.1 + ATLAS_IMAGE_EXTRUSION));
+
+			pen.0 += padded_width;
+			shelf_height = shelf_height.max(size.y as usize + border);
+		}
+
+		let atlas_height = (pen.1 + shelf_height).next_power_of_two();
+		let atlas_size = Vec2::new(atlas_width as f32, atlas_height as f32);
+		let mut atlas_data = vec![0u8; atlas_width * atlas_height * 4];
+
+		let mut regions = Vec::with_capacity(self.images.len());
+
+		for ((size, data), &(x, y)) in self.images.iter().zip(placements.iter()) {
+			let (width, height) = (size.x as usize, size.y as usize);
+			let extrusion = ATLAS_IMAGE_EXTRUSION as isize;
+
+			// Copy the image along with its extruded edges, clamping reads to the image
+			for row in -extrusion..height as isize + extrusion {
+				let src_row = row.clamp(0, height as isize - 1) as usize;
+				let dst_row = (y as isize + row) as usize;
+
+				for column in -extrusion..width as isize + extrusion {
+					let src_column = column.clamp(0, width as isize - 1) as usize;
+					let dst_column = (x as isize + column) as usize;
+
+					let src = (src_row * width + src_column) * 4;
+					let dst = (dst_row * atlas_width + dst_column) * 4;
+					atlas_data[dst..dst + 4].copy_from_slice(&data[src..src + 4]);
+				}
+			}
+
+			// Rows are stored top down, so the top of each image has the smaller v
+			let uv_min = Vec2::new(x as f32, y as f32) / atlas_size;
+			let uv_max = uv_min + Vec2::new(width as f32, height as f32) / atlas_size;
+
+			regions.push(SpriteRegion { uv_min, uv_max, size: *size });
+		}
+
+		let texture = gfx.new_texture(Vec2i::new(atlas_width as i32, atlas_height as i32), gfx::TextureFormat::color());
+
+		{
+			let mut texture = gfx.get_texture_mut(texture);
+			texture.upload_u8(&atlas_data);
+			texture.set_filter(true, true);
+		}
+
+		let white_region = regions[white.0];
+
+		SpriteAtlas {
+			texture,
+			regions,
+			white_uv: (white_region.uv_min + white_region.uv_max) / 2.0,
+		}
+	}
+}
+
+
+/// Images packed into one texture, so that everything drawn from it can go out in a single draw call
+#[derive(Clone, Debug)]
+pub struct SpriteAtlas {
+	texture: gfx::TextureKey,
+	regions: Vec<SpriteRegion>,
+
+	/// The middle of a white image, for quads that are only coloured
+	white_uv: Vec2,
+}
+
+impl SpriteAtlas {
+	pub fn texture(&self) -> gfx::TextureKey {
+		self.texture
+	}
+
+	pub fn region(&self, id: SpriteId) -> SpriteRegion {
+		self.regions[id.0]
+	}
+}
+
+
+/// A quad, optionally textured with an image from the batch's atlas, and tinted by `color`
+#[derive(Copy, Clone, Debug)]
+pub struct Sprite {
+	pub image: Option<SpriteId>,
+
+	pub position: Vec2,
+	pub size: Vec2,
+
+	/// Anticlockwise, in radians, about `position`
+	pub rotation: f32,
+
+	pub color: Color,
+
+	/// Higher layers are drawn over lower ones. Within a layer, later sprites are drawn over earlier ones.
+	pub layer: i32,
+}
+
+impl Sprite {
+	/// An untextured white quad centered on `position`
+	pub fn new(position: Vec2, size: Vec2) -> Sprite {
+		Sprite {
+			image: None,
+			position,
+			size,
+			rotation: 0.0,
+			color: Color::white(),
+			layer: 0,
+		}
+	}
+
+	pub fn with_image(mut self, image: SpriteId) -> Sprite {
+		self.image = Some(image);
+		self
+	}
+
+	pub fn with_color(mut self, color: impl Into<Color>) -> Sprite {
+		self.color = color.into();
+		self
+	}
+
+	pub fn rotated(mut self, rotation: f32) -> Sprite {
+		self.rotation = rotation;
+		self
+	}
+
+	pub fn on_layer(mut self, layer: i32) -> Sprite {
+		self.layer = layer;
+		self
+	}
+}
+
+
+/// An image that can be stretched to any size without distorting its corners.
+/// The corners keep their size, the edges stretch along their length, and the middle stretches both ways.
+#[derive(Copy, Clone, Debug)]
+pub struct NineSlice {
+	pub image: SpriteId,
+
+	/// How far in from each edge of the image the corners go, in pixels
+	pub image_border: f32,
+
+	/// How big the corners are drawn, in the units the batch is built in
+	pub border: f32,
+}
+
+impl NineSlice {
+	pub fn new(image: SpriteId, image_border: f32, border: f32) -> NineSlice {
+		NineSlice { image, image_border, border }
+	}
+}
+
+
+#[derive(Copy, Clone, Debug)]
+struct StagedVertex {
+	pos: Vec2,
+	uv: Vec2,
+	color: Color,
+}
+
+/// A run of staged vertices and indices that has to stay together, and in order
+#[derive(Copy, Clone, Debug)]
+struct BatchItem {
+	layer: i32,
+	vertex_start: usize,
+	vertex_count: usize,
+	index_start: usize,
+	index_count: usize,
+}
+
+
+/// Collects 2d quads and shapes over a frame, then sorts them by layer and uploads them in as few meshes as will hold them.
+/// Everything shares the atlas texture, so drawing the whole batch is usually a single draw call.
+pub struct SpriteBatch {
+	atlas: SpriteAtlas,
+
+	staged_vertices: Vec<StagedVertex>,
+	staged_indices: Vec<u16>,
+	items: Vec<BatchItem>,
+
+	meshes: Vec<gfx::Mesh<gfx::TexturedVertex>>,
+	mesh_data: Vec<gfx::MeshData<gfx::TexturedVertex>>,
+	num_chunks: usize,
+}
+
+impl SpriteBatch {
+	pub fn new(atlas: SpriteAtlas) -> SpriteBatch {
+		SpriteBatch {
+			atlas,
+
+			staged_vertices: Vec::new(),
+			staged_indices: Vec::new(),
+			items: Vec::new(),
+
+			meshes: Vec::new(),
+			mesh_data: Vec::new(),
+			num_chunks: 0,
+		}
+	}
+
+	pub fn atlas(&self) -> &SpriteAtlas {
+		&self.atlas
+	}
+
+	/// Drops everything added since the last upload
+	pub fn clear(&mut self) {
+		self.staged_vertices.clear();
+		self.staged_indices.clear();
+		self.items.clear();
+	}
+
+	pub fn add(&mut self, sprite: &Sprite) {
+		let (uv_min, uv_max) = self.uv_rect(sprite.image);
+		let rotation = Mat2::rotate(sprite.rotation);
+		let half_size = sprite.size / 2.0;
+
+		let corners = [
+			(Vec2::new(-1.0, -1.0), Vec2::new(uv_min.x, uv_max.y)),
+			(Vec2::new( 1.0, -1.0), uv_max),
+			(Vec2::new( 1.0,  1.0), Vec2::new(uv_max.x, uv_min.y)),
+			(Vec2::new(-1.0,  1.0), uv_min),
+		];
+
+		let vertices = corners.iter().map(|&(corner, uv)| StagedVertex {
+			pos: sprite.position + rotation * (corner * half_size),
+			uv,
+			color: sprite.color,
+		});
+
+		self.add_item(sprite.layer, vertices, [0, 1, 2, 0, 2, 3]);
+	}
+
+	/// Stretches `nine_slice` to fill an axis aligned rect
+	pub fn add_nine_slice(&mut self, nine_slice: &NineSlice, center: Vec2, size: Vec2, color: impl Into<Color>, layer: i32) {
+		let color = color.into();
+		let region = self.atlas.region(nine_slice.image);
+
+		// Corners shrink rather than overlap when the rect is too small to fit them
+		let border = nine_slice.border.min(size.x / 2.0).min(size.y / 2.0);
+		let uv_border = Vec2::splat(nine_slice.image_border) / region.size.to_vec2() * (region.uv_max - region.uv_min);
+
+		let min = center - size / 2.0;
+		let max = center + size / 2.0;
+
+		let xs = [min.x, min.x + border, max.x - border, max.x];
+		let ys = [min.y, min.y + border, max.y - border, max.y];
+
+		// Image rows run top down, so v runs the opposite way to y
+		let us = [region.uv_min.x, region.uv_min.x + uv_border.x, region.uv_max.x - uv_border.x, region.uv_max.x];
+		let vs = [region.uv_max.y, region.uv_max.y - uv_border.y, region.uv_min.y + uv_border.y, region.uv_min.y];
+
+		let vertices = (0..16).map(|idx| {
+			let (column, row) = (idx % 4, idx / 4);
+
+			StagedVertex {
+				pos: Vec2::new(xs[column], ys[row]),
+				uv: Vec2::new(us[column], vs[row]),
+				color,
+			}
+		});
+
+		let indices = (0..3u16).flat_map(|row| (0..3u16).map(move |column| (row, column)))
+			.flat_map(|(row, column)| {
+				let bottom_left = row * 4 + column;
+				let top_left = bottom_left + 4;
+				[bottom_left, bottom_left + 1, top_left + 1, bottom_left, top_left + 1, top_left]
+			});
+
+		self.add_item(layer, vertices, indices);
+	}
+
+	/// Adds untextured geometry built elsewhere, e.g., with a `ColorMeshBuilder`
+	pub fn add_colored_mesh(&mut self, mesh_data: &gfx::MeshData<gfx::ColorVertex2D>, layer: i32) {
+		let white_uv = self.atlas.white_uv;
+		let vertices = mesh_data.vertices.iter()
+			.map(|vertex| StagedVertex { pos: vertex.pos, uv: white_uv, color: vertex.color });
+
+		self.add_item(layer, vertices, mesh_data.indices.iter().copied());
+	}
+
+	/// Builds untextured geometry, e.g., a `geom::Polygon`, straight into `layer`
+	pub fn layer(&mut self, layer: i32) -> SpriteLayerBuilder<'_> {
+		SpriteLayerBuilder {
+			batch: self,
+			layer,
+			color: Color::white(),
+		}
+	}
+
+	/// Sorts everything added since the last upload by layer, lays it out on the xy plane of `transform`, and uploads it
+	pub fn upload(&mut self, gfx: &mut gfx::Context, transform: Mat3x4) {
+		let mut items = std::mem::take(&mut self.items);
+		items.sort_by_key(|item| item.layer);
+
+		for mesh_data in self.mesh_data.iter_mut() {
+			mesh_data.clear();
+		}
+
+		self.num_chunks = 0;
+
+		for (order, item) in items.iter().enumerate() {
+			let item_indices = &self.staged_indices[item.index_start..item.index_start + item.index_count];
+			let item_vertices = &self.staged_vertices[item.vertex_start..item.vertex_start + item.vertex_count];
+
+			let chunk_is_full = self.num_chunks == 0
+				|| self.mesh_data[self.num_chunks - 1].vertices.len() + item.vertex_count > MAX_VERTICES_PER_DRAW;
+
+			if chunk_is_full {
+				if self.mesh_data.len() <= self.num_chunks {
+					self.mesh_data.push(gfx::MeshData::new());
+					self.meshes.push(gfx::Mesh::new(gfx));
+				}
+
+				self.num_chunks += 1;
+			}
+
+			let z = order as f32 * SPRITE_DEPTH_STEP;
+			let vertices = item_vertices.iter()
+				.map(|vertex| gfx::TexturedVertex::new(transform * vertex.pos.extend(z), vertex.uv, vertex.color));
+
+			self.mesh_data[self.num_chunks - 1].extend(vertices, item_indices.iter().copied());
+		}
+
+		for (mesh, mesh_data) in self.meshes.iter_mut().zip(self.mesh_data.iter()) {
+			mesh.upload(mesh_data);
+		}
+
+		items.clear();
+		self.items = items;
+		self.staged_vertices.clear();
+		self.staged_indices.clear();
+	}
+
+	/// Binds the atlas to texture unit 0 and draws whatever was last uploaded, leaving the shader to the caller
+	pub fn draw(&self, gfx: &mut gfx::RenderState<'_>) {
+		gfx.bind_texture(0, self.atlas.texture);
+
+		for mesh in self.meshes[..self.num_chunks].iter() {
+			mesh.draw(gfx, gfx::DrawMode::Triangles);
+		}
+	}
+
+	fn uv_rect(&self, image: Option<SpriteId>) -> (Vec2, Vec2) {
+		match image {
+			Some(image) => {
+				let region = self.atlas.region(image);
+				(region.uv_min, region.uv_max)
+			}
+
+			None => (self.atlas.white_uv, self.atlas.white_uv),
+		}
+	}
+
+	fn add_item(&mut self, layer: i32, vertices: impl IntoIterator<Item=StagedVertex>, indices: impl IntoIterator<Item=u16>) {
+		let item = BatchItem {
+			layer,
+			vertex_start: self.staged_vertices.len(),
+			vertex_count: 0,
+			index_start: self.staged_indices.len(),
+			index_count: 0,
+		};
+
+		self.staged_vertices.extend(vertices);
+		self.staged_indices.extend(indices);
+
+		let vertex_count = self.staged_vertices.len() - item.vertex_start;
+		let index_count = self.staged_indices.len() - item.index_start;
+
+		if index_count > 0 {
+			self.items.push(BatchItem { vertex_count, index_count, ..item });
+		}
+	}
+}
+
+
+/// Builds untextured 2d geometry into a single layer of a `SpriteBatch`
+pub struct SpriteLayerBuilder<'b> {
+	batch: &'b mut SpriteBatch,
+	layer: i32,
+	color: Color,
+}
+
+impl PolyBuilder2D for SpriteLayerBuilder<'_> {
+	fn extend_2d(&mut self, vs: impl IntoIterator<Item=Vec2>, is: impl IntoIterator<Item=u16>) {
+		let (color, uv) = (self.color, self.batch.atlas.white_uv);
+		let vertices = vs.into_iter().map(move |pos| StagedVertex { pos, uv, color });
+
+		self.batch.add_item(self.layer, vertices, is);
+	}
+}
+
+impl ColoredPolyBuilder for SpriteLayerBuilder<'_> {
+	fn set_color(&mut self, color: impl Into<Color>) {
+		self.color = color.into();
+	}
+}
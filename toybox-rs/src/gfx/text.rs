@@ -0,0 +1,332 @@
+use crate::prelude::*;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// DejaVu Sans - see fonts/DejaVuSans-LICENSE.txt
+pub const DEFAULT_FONT_DATA: &[u8] = include_bytes!("../../fonts/DejaVuSans.ttf");
+
+/// Glyphs are rasterised at this size, and scaled from there.
+/// Coverage is sampled with linear filtering and thresholded, so edges stay sharp well above this size.
+pub const DEFAULT_FONT_PIXEL_SIZE: f32 = 48.0;
+
+const ATLAS_WIDTH: usize = 1024;
+const ATLAS_GLYPH_PADDING: usize = 2;
+
+/// Shown in place of characters the atlas doesn't have
+const FALLBACK_CHAR: char = '?';
+
+
+#[derive(Copy, Clone, Debug)]
+pub enum TextAlign {
+	Left,
+	Center,
+	Right,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum VerticalAlign {
+	Top,
+	Middle,
+	Bottom,
+}
+
+
+#[derive(Copy, Clone, Debug)]
+pub struct TextStyle {
+	/// The size of an em, in whatever units the text is built in - ui units, or world units for text in the 3d view
+	pub size: f32,
+	pub color: Color,
+
+	/// How each line sits horizontally, and the whole block vertically, relative to the origin
+	pub align: TextAlign,
+	pub vertical_align: VerticalAlign,
+
+	/// Lines wider than this, in the same units as `size`, wrap between words
+	pub max_width: Option<f32>,
+}
+
+impl TextStyle {
+	pub fn new(size: f32) -> TextStyle {
+		TextStyle {
+			size,
+			color: Color::white(),
+			align: TextAlign::Left,
+			vertical_align: VerticalAlign::Top,
+			max_width: None,
+		}
+	}
+
+	pub fn with_color(mut self, color: impl Into<Color>) -> TextStyle {
+		self.color = color.into();
+		self
+	}
+
+	pub fn aligned(mut self, align: TextAlign, vertical_align: VerticalAlign) -> TextStyle {
+		self.align = align;
+		self.vertical_align = vertical_align;
+		self
+	}
+
+	pub fn centered(self) -> TextStyle {
+		self.aligned(TextAlign::Center, VerticalAlign::Middle)
+	}
+
+	pub fn wrapped(mut self, max_width: f32) -> TextStyle {
+		self.max_width = Some(max_width);
+		self
+	}
+}
+
+
+/// Where a glyph's bitmap is in the atlas, and how it sits relative to the pen. Everything but uvs is in ems.
+#[derive(Copy, Clone, Debug)]
+struct Glyph {
+	uv_min: Vec2,
+	uv_max: Vec2,
+
+	/// From the pen position on the baseline to the bottom left of the bitmap
+	offset: Vec2,
+	size: Vec2,
+	advance: f32,
+}
+
+
+/// A font rasterised on the cpu into a single channel coverage texture, plus what's needed to lay text out with it.
+/// Cheap to clone - clones share the same font data, glyph table and texture.
+#[derive(Clone)]
+pub struct FontAtlas {
+	font: Rc<fontdue::Font>,
+	pixel_size: f32,
+
+	glyphs: Rc<HashMap<char, Glyph>>,
+	texture: gfx::TextureKey,
+
+	/// In ems
+	ascent: f32,
+	line_height: f32,
+}
+
+impl FontAtlas {
+	pub fn new(gfx: &mut gfx::Context, font_data: &[u8], pixel_size: f32) -> Result<FontAtlas, Box<dyn Error>> {
+		let font = fontdue::Font::from_bytes(font_data, fontdue::FontSettings::default())?;
+
+		let line_metrics = font.horizontal_line_metrics(pixel_size)
+			.ok_or("Font has no horizontal line metrics")?;
+
+		// Printable ascii and latin-1
+		let chars = (' '..='~').chain('\u{a0}'..='\u{ff}');
+
+		let mut bitmaps = Vec::new();
+		let mut pen = (0, 0);
+		let mut shelf_height = 0;
+
+		// Pack glyphs onto shelves, left to right then top to bottom
+		for ch in chars {
+			let (metrics, bitmap) = font.rasterize(ch, pixel_size);
+			let padded_width = metrics.width + ATLAS_GLYPH_PADDING;
+
+			if pen.0 + padded_width > ATLAS_WIDTH {
+				pen = (0, pen.1 + shelf_height);
+				shelf_height = 0;
+			}
+
+			bitmaps.push((ch, metrics, bitmap, pen));
+
+			pen.0 += padded_width;
+			shelf_height = shelf_height.max(metrics.height + ATLAS_GLYPH_PADDING);
+		}
+
+		let atlas_height = (pen.1 + shelf_height).next_power_of_two();
+		let atlas_size = Vec2::new(ATLAS_WIDTH as f32, atlas_height as f32);
+		let mut atlas_data = vec![0u8; ATLAS_WIDTH * atlas_height];
+
+		let mut glyphs = HashMap::new();
+
+		for (ch, metrics, bitmap, (x, y)) in bitmaps {
+			for (row_idx, row) in bitmap.chunks(metrics.width.max(1)).enumerate() {
+				let start = (y + row_idx) * ATLAS_WIDTH + x;
+				atlas_data[start..start + row.len()].copy_from_slice(row);
+			}
+
+			// Rows are stored top down, so the top of each glyph has the smaller v
+			let bitmap_size = Vec2::new(metrics.width as f32, metrics.height as f32);
+			let uv_min = Vec2::new(x as f32, y as f32) / atlas_size;
+			let uv_max = uv_min + bitmap_size / atlas_size;
+
+			glyphs.insert(ch, Glyph {
+				uv_min,
+				uv_max,
+				offset: Vec2::new(metrics.xmin as f32, metrics.ymin as f32) / pixel_size,
+				size: bitmap_size / pixel_size,
+				advance: metrics.advance_width / pixel_size,
+			});
+		}
+
+		let texture = gfx.new_texture(Vec2i::new(ATLAS_WIDTH as i32, atlas_height as i32), gfx::TextureFormat::Red(gfx::BaseFormat::Unorm8));
+
+		{
+			let mut texture = gfx.get_texture_mut(texture);
+			texture.upload_u8(&atlas_data);
+			texture.set_filter(true, true);
+		}
+
+		Ok(FontAtlas {
+			font: Rc::new(font),
+			pixel_size,
+
+			glyphs: Rc::new(glyphs),
+			texture,
+
+			ascent: line_metrics.ascent / pixel_size,
+			line_height: line_metrics.new_line_size / pixel_size,
+		})
+	}
+
+	/// The bundled font at the default size
+	pub fn new_default(gfx: &mut gfx::Context) -> Result<FontAtlas, Box<dyn Error>> {
+		FontAtlas::new(gfx, DEFAULT_FONT_DATA, DEFAULT_FONT_PIXEL_SIZE)
+	}
+
+	/// Coverage in the red channel, to be sampled with the glyph uvs in `TexturedVertex`
+	pub fn texture(&self) -> gfx::TextureKey {
+		self.texture
+	}
+
+	pub fn line_height(&self, style: &TextStyle) -> f32 {
+		self.line_height * style.size
+	}
+
+	/// The size of the block `text` would take up when built with `style`
+	pub fn measure(&self, text: &str, style: &TextStyle) -> Vec2 {
+		let lines = self.layout_lines(text, style);
+		let width = lines.iter().map(|line| line.width).fold(0.0, f32::max);
+
+		Vec2::new(width, lines.len() as f32 * self.line_height) * style.size
+	}
+
+	/// Appends a quad per glyph to `mesh_data`. Text is laid out on the xy plane of `transform`, with y up.
+	pub fn build_text(&self, mesh_data: &mut gfx::MeshData<gfx::TexturedVertex>, text: &str, style: &TextStyle, transform: Mat3x4) {
+		let lines = self.layout_lines(text, style);
+
+		let block_height = lines.len() as f32 * self.line_height;
+		let block_top = match style.vertical_align {
+			VerticalAlign::Top => 0.0,
+			VerticalAlign::Middle => block_height / 2.0,
+			VerticalAlign::Bottom => block_height,
+		};
+
+		for (line_idx, line) in lines.iter().enumerate() {
+			let baseline = block_top - self.ascent - line_idx as f32 * self.line_height;
+			let line_start = match style.align {
+				TextAlign::Left => 0.0,
+				TextAlign::Center => -line.width / 2.0,
+				TextAlign::Right => -line.width,
+			};
+
+			for &(ch, pen_x) in line.glyphs.iter() {
+				let glyph = self.glyph(ch);
+				if glyph.size.x <= 0.0 || glyph.size.y <= 0.0 {
+					continue
+				}
+
+				let min = (Vec2::new(line_start + pen_x, baseline) + glyph.offset) * style.size;
+				let max = min + glyph.size * style.size;
+
+				let vertices = [
+					gfx::TexturedVertex::new(transform * min.extend(0.0), Vec2::new(glyph.uv_min.x, glyph.uv_max.y), style.color),
+					gfx::TexturedVertex::new(transform * Vec3::new(max.x, min.y, 0.0), glyph.uv_max, style.color),
+					gfx::TexturedVertex::new(transform * max.extend(0.0), Vec2::new(glyph.uv_max.x, glyph.uv_min.y), style.color),
+					gfx::TexturedVertex::new(transform * Vec3::new(min.x, max.y, 0.0), glyph.uv_min, style.color),
+				];
+
+				mesh_data.extend(vertices, [0, 1, 2, 0, 2, 3]);
+			}
+		}
+	}
+
+	fn glyph(&self, ch: char) -> Glyph {
+		self.glyphs.get(&ch)
+			.or_else(|| self.glyphs.get(&FALLBACK_CHAR))
+			.copied()
+			.unwrap()
+	}
+
+	fn kerning(&self, left: char, right: char) -> f32 {
+		self.font.horizontal_kern(left, right, self.pixel_size)
+			.map_or(0.0, |kern| kern / self.pixel_size)
+	}
+
+	/// Breaks `text` into lines at newlines, and between words wherever a line would get wider than `style.max_width`
+	fn layout_lines(&self, text: &str, style: &TextStyle) -> Vec<LaidOutLine> {
+		let max_width = style.max_width.map(|max_width| max_width / style.size);
+		let space_advance = self.glyph(' ').advance;
+
+		let mut lines = Vec::new();
+
+		for paragraph in text.split('\n') {
+			let mut line = LaidOutLine::default();
+			let mut line_has_words = false;
+
+			for word in paragraph.split(' ') {
+				let (word_glyphs, word_width) = self.layout_word(word);
+
+				let mut start = match line_has_words {
+					true => line.width + space_advance,
+					false => 0.0,
+				};
+
+				// Words wider than a whole line are left to overflow it
+				let overflows = max_width.map_or(false, |max_width| start + word_width > max_width);
+				if overflows && line_has_words {
+					lines.push(std::mem::take(&mut line));
+					start = 0.0;
+				}
+
+				line.glyphs.extend(word_glyphs.into_iter().map(|(ch, x)| (ch, start + x)));
+				line.width = start + word_width;
+				line_has_words = true;
+			}
+
+			lines.push(line);
+		}
+
+		lines
+	}
+
+	/// Glyphs and their pen positions relative to the start of the word, and the width of the whole word
+	fn layout_word(&self, word: &str) -> (Vec<(char, f32)>, f32) {
+		let mut glyphs = Vec::with_capacity(word.len());
+		let mut pen_x = 0.0;
+		let mut prev_char = None;
+
+		for ch in word.chars() {
+			if let Some(prev_char) = prev_char {
+				pen_x += self.kerning(prev_char, ch);
+			}
+
+			glyphs.push((ch, pen_x));
+			pen_x += self.glyph(ch).advance;
+			prev_char = Some(ch);
+		}
+
+		(glyphs, pen_x)
+	}
+}
+
+impl std::fmt::Debug for FontAtlas {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("FontAtlas")
+			.field("pixel_size", &self.pixel_size)
+			.field("glyphs", &self.glyphs.len())
+			.field("texture", &self.texture)
+			.finish()
+	}
+}
+
+
+#[derive(Default)]
+struct LaidOutLine {
+	/// Characters and the pen position they start at, in ems from the start of the line
+	glyphs: Vec<(char, f32)>,
+	width: f32,
+}
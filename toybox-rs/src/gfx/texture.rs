@@ -50,6 +50,28 @@ impl Texture {
 		}
 	}
 
+	/// Replaces the contents of the whole texture with tightly packed rows of 8 bit channels
+	pub fn upload_u8(&mut self, data: &[u8]) {
+		let Vec2i{x: width, y: height} = self.current_size;
+		let format = self.format.to_gl_unsized();
+
+		let channels = match format {
+			raw::RGBA => 4,
+			raw::RGB => 3,
+			raw::RG => 2,
+			_ => 1,
+		};
+
+		assert_eq!(data.len(), (width * height * channels) as usize, "Texture data doesn't match its size");
+
+		unsafe {
+			// Rows of odd widths aren't 4 byte aligned - put the default back afterwards so other uploads aren't affected
+			raw::PixelStorei(raw::UNPACK_ALIGNMENT, 1);
+			raw::TextureSubImage2D(self.texture_handle, 0, 0, 0, width, height, format, raw::UNSIGNED_BYTE, data.as_ptr() as *const _);
+			raw::PixelStorei(raw::UNPACK_ALIGNMENT, 4);
+		}
+	}
+
 	pub fn format(&self) -> TextureFormat { self.format }
 	pub fn size(&self) -> Vec2i { self.current_size }
 	pub fn size_mode(&self) -> TextureSize { self.size_mode }
@@ -91,6 +91,39 @@ impl Vertex for ColorVertex {
 
 
 
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct TexturedVertex {
+	pub pos: Vec3,
+	pub uv: Vec2,
+	pub color: Color,
+}
+
+impl TexturedVertex {
+	pub fn new(pos: Vec3, uv: Vec2, color: impl Into<Color>) -> TexturedVertex {
+		let color = color.into();
+		TexturedVertex { pos, uv, color }
+	}
+}
+
+static TEXTURED_VERTEX_ATTRIBUTES: &'static [Attribute] = &[
+	Attribute::new(0, AttributeType::Vec3),
+	Attribute::new(12, AttributeType::Vec2),
+	Attribute::new(20, AttributeType::Vec4),
+];
+
+impl Vertex for TexturedVertex {
+	fn descriptor() -> Descriptor {
+		Descriptor {
+			attributes: TEXTURED_VERTEX_ATTRIBUTES,
+			size_bytes: std::mem::size_of::<Self>() as u32,
+		}
+	}
+}
+
+
+
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
 pub struct ColorVertex2D {
@@ -23,6 +23,9 @@ pub struct InputSystem {
 	/// Used for remapping mouse input
 	mouse_interactive_region: Vec2,
 
+	/// Applied on top of the sensitivity of every Mouse action - for user preference
+	mouse_sensitivity_scale: f32,
+
 
 	/// The current mouse position in screenspace
 	/// Normalised to window height, and will be None if a capturing input context is active
@@ -57,6 +60,7 @@ impl InputSystem {
 			active_contexts_changed: false,
 
 			mouse_interactive_region: Vec2::new(w as f32, h as f32),
+			mouse_sensitivity_scale: 1.0,
 
 			mouse_absolute: None,
 			mouse_delta: None,
@@ -158,7 +162,7 @@ impl InputSystem {
 
 		if let Some(((action, action_id), context)) = mouse_action {
 			if action.kind().is_relative() {
-				let sensitivity = context.mouse_sensitivity().unwrap_or(1.0);
+				let sensitivity = context.mouse_sensitivity().unwrap_or(1.0) * self.mouse_sensitivity_scale;
 				self.frame_state.mouse = self.mouse_delta.map(|state| (action_id, state * sensitivity));
 			} else {
 				self.frame_state.mouse = self.mouse_absolute.map(|state| (action_id, state));
@@ -235,6 +239,14 @@ impl InputSystem {
 		self.active_contexts.contains(&context_id)
 	}
 
+	pub fn set_mouse_sensitivity_scale(&mut self, scale: f32) {
+		self.mouse_sensitivity_scale = scale;
+	}
+
+	pub fn mouse_sensitivity_scale(&self) -> f32 {
+		self.mouse_sensitivity_scale
+	}
+
 	pub fn frame_state(&self) -> &FrameState {
 		&self.frame_state
 	}
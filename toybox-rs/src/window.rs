@@ -26,3 +26,37 @@ pub fn init_window(sdl_video: &sdl2::VideoSubsystem, window_name: &str) -> Resul
 }
 
 
+
+
+pub fn set_fullscreen(window: &mut sdl2::video::Window, fullscreen: bool) -> Result<(), Box<dyn Error>> {
+	use sdl2::video::FullscreenType;
+
+	let fullscreen_type = match fullscreen {
+		true => FullscreenType::Desktop,
+		false => FullscreenType::Off,
+	};
+
+	window.set_fullscreen(fullscreen_type)?;
+	Ok(())
+}
+
+
+/// The diagonal dots per inch of the display the window is on, if the platform knows it
+pub fn display_dpi(window: &sdl2::video::Window) -> Option<f32> {
+	let display_index = window.display_index().ok()?;
+	let (diagonal_dpi, _, _) = window.subsystem().display_dpi(display_index).ok()?;
+	Some(diagonal_dpi)
+}
+
+
+pub fn set_vsync(window: &sdl2::video::Window, vsync: bool) -> Result<(), Box<dyn Error>> {
+	use sdl2::video::SwapInterval;
+
+	let swap_interval = match vsync {
+		true => SwapInterval::VSync,
+		false => SwapInterval::Immediate,
+	};
+
+	window.subsystem().gl_set_swap_interval(swap_interval)?;
+	Ok(())
+}